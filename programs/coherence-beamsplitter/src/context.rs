@@ -1,6 +1,6 @@
-use crate::state::*;
+use crate::{enums::Role, errors::BeamsplitterErrors, state::*, token_interface::token_2022};
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token::{Mint, Token, TokenAccount},
@@ -9,6 +9,150 @@ use anchor_spl::{
 const BEAMSPLITTER_SIZE: usize = std::mem::size_of::<Beamsplitter>();
 const PRISM_ETF_SIZE: usize = std::mem::size_of::<PrismEtf>();
 const ORDER_STATE_SIZE: usize = std::mem::size_of::<OrderState>();
+const PRICE_FEED_SIZE: usize = std::mem::size_of::<PriceFeed>();
+const FEE_VAULT_SIZE: usize = std::mem::size_of::<FeeVault>();
+const FEE_EXEMPTION_SIZE: usize = std::mem::size_of::<FeeExemption>();
+const PRISM_ETF_DESCRIPTOR_SIZE: usize = std::mem::size_of::<PrismEtfDescriptor>();
+const ALLOWLIST_ENTRY_SIZE: usize = std::mem::size_of::<AllowlistEntry>();
+
+/// Checks that `info` is the [FeeVault]'s [TokenAccount] for `mint`, which UNDERLYING fee mode sends fees to
+pub fn fee_vault_ata<'info>(
+    info: &AccountInfo<'info>,
+    fee_vault: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Account<'info, TokenAccount>> {
+    let fee_vault_ata = Account::<TokenAccount>::try_from(info)?;
+    if fee_vault_ata.owner != *fee_vault || fee_vault_ata.mint != *mint {
+        return err!(BeamsplitterErrors::WrongFeeAccount);
+    }
+    Ok(fee_vault_ata)
+}
+
+/// The construction fee `orderer` is charged on `prism_etf`, lowered if `remaining_accounts` starts with their
+/// [FeeExemption] for it
+pub fn exempt_construction_bps(
+    remaining_accounts: &[AccountInfo],
+    prism_etf: &Pubkey,
+    orderer: &Pubkey,
+    construction_bps: u16,
+) -> Result<u16> {
+    let fee_exemption_info = match remaining_accounts.first() {
+        Some(fee_exemption_info) => fee_exemption_info,
+        None => return Ok(construction_bps),
+    };
+
+    if fee_exemption_info.owner != &crate::ID {
+        return err!(BeamsplitterErrors::WrongFeeExemption);
+    }
+
+    let fee_exemption =
+        FeeExemption::try_deserialize(&mut &fee_exemption_info.try_borrow_data()?[..])?;
+    if fee_exemption.prism_etf != *prism_etf || fee_exemption.exempt != *orderer {
+        return err!(BeamsplitterErrors::WrongFeeExemption);
+    }
+
+    // Only the exemption at the orderer's PDA is one a fee admin added
+    let address = Pubkey::create_program_address(
+        &[
+            b"FeeExemption".as_ref(),
+            &prism_etf.to_bytes(),
+            &orderer.to_bytes(),
+            &[fee_exemption.bump],
+        ],
+        &crate::ID,
+    );
+    match address {
+        Ok(address) if address == fee_exemption_info.key() => {}
+        _ => return err!(BeamsplitterErrors::WrongFeeExemption),
+    }
+
+    Ok(construction_bps.min(fee_exemption.construction_bps))
+}
+
+/// Splits the orderer's [FeeExemption] off the front of `remaining_accounts`, for instructions that pass the accounts
+/// after it on. It is optional there, so it is recognised by being a [FeeExemption] of this program
+pub fn split_fee_exemption<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> (&'a [AccountInfo<'info>], &'a [AccountInfo<'info>]) {
+    let is_fee_exemption = remaining_accounts.first().map_or(false, |info| {
+        info.owner == &crate::ID
+            && info.try_borrow_data().map_or(false, |data| {
+                data.starts_with(&FeeExemption::discriminator())
+            })
+    });
+    remaining_accounts.split_at(is_fee_exemption as usize)
+}
+
+/// Checks that `orderer` may construct a permissioned ETF, by their [AllowlistEntry] at the start of
+/// `remaining_accounts`. Returns the remaining accounts that follow it
+pub fn allowlisted<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    prism_etf: &Account<PrismEtf>,
+    orderer: &Pubkey,
+) -> Result<&'a [AccountInfo<'info>]> {
+    if !prism_etf.permissioned {
+        return Ok(remaining_accounts);
+    }
+
+    let allowlist_entry_info = match remaining_accounts.first() {
+        Some(allowlist_entry_info) => allowlist_entry_info,
+        None => return err!(BeamsplitterErrors::NotAllowlisted),
+    };
+
+    if allowlist_entry_info.owner != &crate::ID {
+        return err!(BeamsplitterErrors::NotAllowlisted);
+    }
+
+    let allowlist_entry =
+        AllowlistEntry::try_deserialize(&mut &allowlist_entry_info.try_borrow_data()?[..])?;
+    if allowlist_entry.prism_etf != prism_etf.key() || allowlist_entry.holder != *orderer {
+        return err!(BeamsplitterErrors::NotAllowlisted);
+    }
+
+    // Only the entry at the holder's PDA is one the manager added
+    let address = Pubkey::create_program_address(
+        &[
+            b"AllowlistEntry".as_ref(),
+            &prism_etf.key().to_bytes(),
+            &orderer.to_bytes(),
+            &[allowlist_entry.bump],
+        ],
+        &crate::ID,
+    );
+    match address {
+        Ok(address) if address == allowlist_entry_info.key() => {}
+        _ => return err!(BeamsplitterErrors::NotAllowlisted),
+    }
+
+    Ok(&remaining_accounts[1..])
+}
+
+/// The [PrismEtf] in `info` if it is the one of `mint`, used to recognise nested ETFs among pushed tokens
+pub fn nested_prism_etf(
+    info: &AccountInfo,
+    mint: &Pubkey,
+    beamsplitter: &Pubkey,
+) -> Result<Option<PrismEtf>> {
+    if info.owner != &crate::ID {
+        return err!(BeamsplitterErrors::InvalidNesting);
+    }
+
+    let prism_etf = PrismEtf::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let address = Pubkey::create_program_address(
+        &[
+            b"PrismEtf".as_ref(),
+            &mint.to_bytes(),
+            &beamsplitter.to_bytes(),
+            &[prism_etf.bump],
+        ],
+        &crate::ID,
+    );
+
+    Ok(match address {
+        Ok(address) if address == info.key() => Some(prism_etf),
+        _ => None,
+    })
+}
 
 #[derive(Accounts)]
 #[instruction(bump: u8)]
@@ -52,12 +196,15 @@ pub struct InitPrismEtf<'info> {
     /// [Mint] of the [PrismEtf].
     pub prism_etf_mint: Account<'info, Mint>,
 
-    #[account(mut)]
     pub manager: Signer<'info>,
 
+    /// Pays for the new accounts, separate from the manager so PDA managers (multisigs, governance) can sign
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     // ========================= PDA's =========================
     /// Information about the [PrismEtf].
-    #[account(init, seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump, payer = manager, space = PRISM_ETF_SIZE + 1 + 8)]
+    #[account(init, seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump, payer = payer, space = PRISM_ETF_SIZE + 1 + 8)]
     pub prism_etf: Account<'info, PrismEtf>,
 
     /// The central mint authority for all registered tokens, used for checks
@@ -73,8 +220,57 @@ pub struct InitPrismEtf<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(bump: u8, nonce: u64, decimals: u8)]
+pub struct InitPrismEtfWithMint<'info> {
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    pub manager: Signer<'info>,
+
+    /// Pays for the new accounts, separate from the manager so PDA managers (multisigs, governance) can sign
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// [Mint] of the [PrismEtf], created here with Beamsplitter as its authorities
+    #[account(
+        init,
+        seeds = [
+            b"PrismEtfMint".as_ref(),
+            &manager.key().to_bytes(),
+            &nonce.to_le_bytes(),
+        ],
+        bump,
+        payer = payer,
+        mint::decimals = decimals,
+        mint::authority = beamsplitter,
+        mint::freeze_authority = beamsplitter,
+    )]
+    pub prism_etf_mint: Box<Account<'info, Mint>>,
+
+    /// Information about the [PrismEtf].
+    #[account(init, seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump, payer = payer, space = PRISM_ETF_SIZE + 1 + 8)]
+    pub prism_etf: Account<'info, PrismEtf>,
+
+    /// The central mint authority for all registered tokens
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Account<'info, Beamsplitter>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
 #[derive(Accounts)]
 pub struct FinalizePrismEtf<'info> {
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
     /// [Mint] of the [PrismEtf].
     pub prism_etf_mint: Account<'info, Mint>,
 
@@ -82,9 +278,69 @@ pub struct FinalizePrismEtf<'info> {
 
     // ========================= PDA's =========================
     /// Information about the [PrismEtf].
-    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens, has_one = manager, mut)]
+    pub prism_etf: Account<'info, PrismEtf>,
+
+    /// The central mint authority for all registered tokens, used for checks
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Account<'info, Beamsplitter>,
+}
+
+#[derive(Accounts)]
+pub struct InitPrismEtfDescriptor<'info> {
+    /// [Mint] of the [PrismEtf].
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    /// Pays for the [PrismEtfDescriptor], so the manager can be a program owned PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// Information about the [PrismEtf].
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Account<'info, PrismEtf>,
+
+    /// Human-readable description of the [PrismEtf]
+    #[account(init, seeds = [b"PrismEtfDescriptor".as_ref(), &prism_etf.key().to_bytes()], bump, payer = payer, space = PRISM_ETF_DESCRIPTOR_SIZE + 8)]
+    pub descriptor: Box<Account<'info, PrismEtfDescriptor>>,
+
+    /// The central mint authority for all registered tokens, used for checks
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Account<'info, Beamsplitter>,
+
+    // ========================= Programs =========================
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrismEtfDescriptor<'info> {
+    /// [Mint] of the [PrismEtf].
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// Information about the [PrismEtf].
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
     pub prism_etf: Account<'info, PrismEtf>,
 
+    /// Human-readable description of the [PrismEtf]
+    #[account(seeds = [b"PrismEtfDescriptor".as_ref(), &prism_etf.key().to_bytes()], bump = descriptor.bump, has_one = prism_etf, mut)]
+    pub descriptor: Box<Account<'info, PrismEtfDescriptor>>,
+
     /// The central mint authority for all registered tokens, used for checks
     #[account(
         seeds = [
@@ -95,6 +351,72 @@ pub struct FinalizePrismEtf<'info> {
     pub beamsplitter: Account<'info, Beamsplitter>,
 }
 
+#[derive(Accounts)]
+pub struct CreatePrismEtfMetadata<'info> {
+    /// [Mint] of the [PrismEtf].
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    /// Pays for the metadata account, separate from the manager so PDA managers (multisigs, governance) can sign
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Metaplex metadata of the mint, the metadata program checks its address
+    #[account(mut)]
+    pub metadata: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// Information about the [PrismEtf].
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Account<'info, PrismEtf>,
+
+    /// The central mint authority for all registered tokens, signs as mint and update authority
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Account<'info, Beamsplitter>,
+
+    // ========================= Programs =========================
+    #[account(address = crate::metadata::token_metadata_program::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePrismEtfMetadata<'info> {
+    /// [Mint] of the [PrismEtf].
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    /// Metaplex metadata of the mint, the metadata program checks its address
+    #[account(mut)]
+    pub metadata: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// Information about the [PrismEtf].
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Account<'info, PrismEtf>,
+
+    /// The central mint authority for all registered tokens, signs as update authority
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Account<'info, Beamsplitter>,
+
+    // ========================= Programs =========================
+    #[account(address = crate::metadata::token_metadata_program::ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 pub struct PushTokens<'info> {
     #[account(mut)]
@@ -107,7 +429,7 @@ pub struct PushTokens<'info> {
 
     // ========================= PDA's =========================
     /// Information about the [PrismEtf].
-    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens, has_one = manager)]
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens, has_one = manager, mut)]
     pub prism_etf: Account<'info, PrismEtf>,
 
     /// The central mint authority for all registered tokens, used for checks
@@ -184,6 +506,7 @@ pub struct StartOrder<'info> {
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
 
     // ========================= Big Data Accounts =========================
+    #[account(mut)]
     pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
 
     #[account(mut)]
@@ -235,9 +558,17 @@ pub struct Cohere<'info> {
     #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
     pub prism_etf: Box<Account<'info, PrismEtf>>,
 
-    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
     pub order_state: Box<Account<'info, OrderState>>,
 
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The [FeeVault]'s [TokenAccount] for the transfer mint, only checked and used in UNDERLYING fee mode
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
+
     // ========================= Big Data Accounts =========================
     pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
 
@@ -257,33 +588,35 @@ pub struct Cohere<'info> {
 }
 
 #[derive(Accounts)]
-pub struct Decohere<'info> {
-    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+pub struct CohereNested<'info> {
+    /// The [Signer] of the tx and orderer of both the outer and the inner order
     pub orderer: Signer<'info>,
 
     // ========================= Mint's =========================
-    /// The mint of the asset being transferred
-    pub transfer_mint: Account<'info, Mint>,
-
     pub prism_etf_mint: Account<'info, Mint>,
 
+    /// The mint of the nested ETF being cohered
+    #[account(mut)]
+    pub transfer_mint: Account<'info, Mint>,
+
     // ========================= ATA's =========================
-    /// The [TokenAccount] that transfers out tokens
-    #[account(associated_token::mint = transfer_mint, associated_token::authority = orderer, mut)]
-    pub orderer_transfer_ata: Box<Account<'info, TokenAccount>>,
+    /// The orderer's [TokenAccount] of the nested ETF, minted to by the inner order and cohered from
+    #[account(mut)]
+    pub orderer_transfer_ata: AccountInfo<'info>,
 
-    /// The [TokenAccount] that transfers in tokens
-    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
-    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
+    /// The outer [PrismEtf]'s [TokenAccount] of the nested ETF
+    #[account(mut)]
+    pub beamsplitter_transfer_ata: AccountInfo<'info>,
 
-    // ========================= PDA's =========================
-    /// The Prism ETF [Account] that this instruction uses
-    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
-    pub prism_etf: Box<Account<'info, PrismEtf>>,
+    /// The inner [FeeVault]'s [TokenAccount] of the nested ETF
+    #[account(mut)]
+    pub inner_fee_vault_etf_ata: AccountInfo<'info>,
 
-    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
-    pub order_state: Box<Account<'info, OrderState>>,
+    /// The outer [FeeVault]'s [TokenAccount] of the nested ETF, only used in UNDERLYING fee mode
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
 
+    // ========================= PDA's =========================
     /// The [Beamsplitter] [Account] that holds all of the Program's funds
     #[account(
         seeds = [
@@ -293,13 +626,41 @@ pub struct Decohere<'info> {
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
 
+    /// The outer Prism ETF [Account]
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    // The accounts below are only passed on, cohere and finalize_order check them. They are not deserialized here
+    // so nothing stale is written back over what the CPIs changed
+    #[account(mut)]
+    pub order_state: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub fee_vault: AccountInfo<'info>,
+
+    /// The nested ETF's [PrismEtf]
+    pub inner_prism_etf: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub inner_order_state: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub inner_fee_vault: AccountInfo<'info>,
+
     // ========================= Big Data Accounts =========================
-    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+    pub weighted_tokens: AccountInfo<'info>,
 
     #[account(mut)]
-    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+    pub transferred_tokens: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub inner_weighted_tokens: AccountInfo<'info>,
+
+    pub inner_transferred_tokens: AccountInfo<'info>,
 
     // ========================= Programs =========================
+    pub beamsplitter_program: Program<'info, crate::program::CoherenceBeamsplitter>,
+
     pub rent: Sysvar<'info, Rent>,
 
     pub clock: Sysvar<'info, Clock>,
@@ -312,54 +673,61 @@ pub struct Decohere<'info> {
 }
 
 #[derive(Accounts)]
-pub struct FinalizeOrder<'info> {
-    #[account(mut)]
-    pub prism_etf_mint: Account<'info, Mint>,
-
+pub struct Decohere<'info> {
     /// The [Signer] of the tx and owner of the [Deposit] [Account]
     pub orderer: Signer<'info>,
 
-    pub manager: AccountInfo<'info>,
+    // ========================= Mint's =========================
+    /// The mint of the asset being transferred
+    pub transfer_mint: Account<'info, Mint>,
 
-    pub owner: AccountInfo<'info>,
+    pub prism_etf_mint: Account<'info, Mint>,
 
     // ========================= ATA's =========================
-    /// The [TokenAccount] that recieves the Basket Tokens
-    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = orderer, mut)]
-    pub orderer_etf_ata: Box<Account<'info, TokenAccount>>,
-
-    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = manager, mut)]
-    pub manager_etf_ata: Box<Account<'info, TokenAccount>>,
+    /// The [TokenAccount] that transfers out tokens
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = orderer, mut)]
+    pub orderer_transfer_ata: Box<Account<'info, TokenAccount>>,
 
-    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = owner, mut)]
-    pub owner_etf_ata: Box<Account<'info, TokenAccount>>,
+    /// The [TokenAccount] that transfers in tokens
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
+    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
 
     // ========================= PDA's =========================
     /// The Prism ETF [Account] that this instruction uses
-    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
     pub prism_etf: Box<Account<'info, PrismEtf>>,
 
     #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
     pub order_state: Box<Account<'info, OrderState>>,
 
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The [FeeVault]'s [TokenAccount] for the transfer mint, only checked and used to refund an UNDERLYING fee
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
+
     /// The [Beamsplitter] [Account] that holds all of the Program's funds
     #[account(
         seeds = [
             b"Beamsplitter".as_ref(),
         ],
         bump = beamsplitter.bump,
-        has_one = owner,
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
 
     // ========================= Big Data Accounts =========================
     pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
 
+    #[account(mut)]
     pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
 
     // ========================= Programs =========================
     pub rent: Sysvar<'info, Rent>,
 
+    pub clock: Sysvar<'info, Clock>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
 
     pub token_program: Program<'info, Token>,
@@ -368,10 +736,22 @@ pub struct FinalizeOrder<'info> {
 }
 
 #[derive(Accounts)]
-pub struct SetOwner<'info> {
-    pub new_owner: AccountInfo<'info>,
+pub struct CohereNative<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account], pays the lamports being wrapped
+    #[account(mut)]
+    pub orderer: Signer<'info>,
 
-    pub owner: Signer<'info>,
+    // ========================= Mint's =========================
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The wSOL mint
+    #[account(address = spl_token::native_mint::ID)]
+    pub transfer_mint: Account<'info, Mint>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that receives the wrapped lamports
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
+    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
 
     // ========================= PDA's =========================
     /// The [Beamsplitter] [Account] that holds all of the Program's funds
@@ -380,68 +760,1194 @@ pub struct SetOwner<'info> {
             b"Beamsplitter".as_ref(),
         ],
         bump = beamsplitter.bump,
-        has_one = owner,
-        mut
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
-}
 
-#[derive(Accounts)]
-pub struct SetDefaultManagerCut<'info> {
-    pub owner: Signer<'info>,
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
 
-    // ========================= PDA's =========================
-    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The [FeeVault]'s wSOL [TokenAccount], only checked and used in UNDERLYING fee mode
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DecohereNative<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account], receives the unwrapped lamports
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= Mint's =========================
+    /// The wSOL mint
+    #[account(address = spl_token::native_mint::ID)]
+    pub transfer_mint: Account<'info, Mint>,
+
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that transfers out the wSOL
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
+    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
+
+    /// Holds the wSOL until it is unwrapped to the orderer, closed again by this instruction
+    #[account(init, payer = orderer, associated_token::mint = transfer_mint, associated_token::authority = order_state)]
+    pub order_native_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The [FeeVault]'s wSOL [TokenAccount], only checked and used to refund an UNDERLYING fee
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub rent: Sysvar<'info, Rent>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CohereToken2022<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    pub orderer: Signer<'info>,
+
+    // ========================= Mint's =========================
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The Token-2022 mint of the asset being transferred, loaded by the instruction to read its extensions
+    #[account(owner = token_2022::ID)]
+    pub transfer_mint: AccountInfo<'info>,
+
+    // ========================= ATA's =========================
+    /// The orderer's Token-2022 account that transfers out tokens
+    #[account(mut)]
+    pub orderer_transfer_ata: AccountInfo<'info>,
+
+    /// The [PrismEtf]'s associated Token-2022 account that transfers in tokens, credited net of the transfer fee
+    #[account(mut)]
+    pub beamsplitter_transfer_ata: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    /// Its epoch picks the mint's transfer fee in effect
+    pub clock: Sysvar<'info, Clock>,
+
+    #[account(address = token_2022::ID)]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DecohereToken2022<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    pub orderer: Signer<'info>,
+
+    // ========================= Mint's =========================
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The Token-2022 mint of the asset being transferred, loaded by the instruction to read its extensions
+    #[account(owner = token_2022::ID)]
+    pub transfer_mint: AccountInfo<'info>,
+
+    // ========================= ATA's =========================
+    /// The orderer's Token-2022 account that transfers in tokens, credited net of the transfer fee
+    #[account(mut)]
+    pub orderer_transfer_ata: AccountInfo<'info>,
+
+    /// The [PrismEtf]'s associated Token-2022 account that transfers out tokens
+    #[account(mut)]
+    pub beamsplitter_transfer_ata: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    #[account(address = token_2022::ID)]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeOrder<'info> {
+    #[account(mut)]
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    pub orderer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that recieves the Basket Tokens
+    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = orderer, mut)]
+    pub orderer_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] that accrues the fees of the [PrismEtf]
+    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = fee_vault, mut)]
+    pub fee_vault_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(mut, seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    #[account(mut)]
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub rent: Sysvar<'info, Rent>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StartSingleAssetOrder<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer pays in
+    pub swap_mint: Account<'info, Mint>,
+
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] the input is paid from
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = orderer)]
+    pub orderer_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] holding the input until it is swapped into each weighted token
+    #[account(init, payer = orderer, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(mut, seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub rent: Sysvar<'info, Rent>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SwapCohere<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    pub orderer: Signer<'info>,
+
+    // ========================= Mint's =========================
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The mint of the asset being swapped into
+    pub transfer_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer paid in
+    #[account(address = order_state.swap_mint)]
+    pub swap_mint: Account<'info, Mint>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] holding the order's remaining input
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] that receives the swapped tokens
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
+    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(mut, seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The [FeeVault]'s [TokenAccount] for the transfer mint, only checked and used in UNDERLYING fee mode
+    #[account(mut)]
+    pub fee_vault_transfer_ata: AccountInfo<'info>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    /// The swap program allowed by the [Beamsplitter]
+    #[account(executable, address = beamsplitter.swap_program @ BeamsplitterErrors::SwapProgramNotAllowed)]
+    pub swap_program: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSingleAssetOrder<'info> {
+    #[account(mut)]
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer paid in
+    #[account(address = order_state.swap_mint)]
+    pub swap_mint: Account<'info, Mint>,
+
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that recieves the Basket Tokens
+    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = orderer, mut)]
+    pub orderer_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] that accrues the fees of the [PrismEtf]
+    #[account(associated_token::mint = prism_etf_mint, associated_token::authority = fee_vault, mut)]
+    pub fee_vault_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] refunded any input that was not swapped
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = orderer)]
+    pub orderer_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] holding the order's remaining input, closed here
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(mut, seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct StartSingleAssetRedemption<'info> {
+    #[account(mut)]
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer is paid out in
+    pub swap_mint: Account<'info, Mint>,
+
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] the etf tokens are burned from
+    #[account(mut, associated_token::mint = prism_etf_mint, associated_token::authority = orderer)]
+    pub orderer_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] collecting the output of each swap
+    #[account(init, payer = orderer, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(mut, seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    #[account(mut)]
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub rent: Sysvar<'info, Rent>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SwapDecohere<'info> {
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= Mint's =========================
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The mint of the asset being swapped out of
+    pub transfer_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer is paid out in
+    #[account(address = order_state.swap_mint)]
+    pub swap_mint: Account<'info, Mint>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that transfers out tokens
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = prism_etf, mut)]
+    pub beamsplitter_transfer_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] refunded anything the swap leaves behind
+    #[account(associated_token::mint = transfer_mint, associated_token::authority = orderer, mut)]
+    pub orderer_transfer_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] collecting the output of each swap
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// Holds the decohered asset while it is swapped, closed by the end of the instruction
+    #[account(init, seeds = [b"OrderVault".as_ref(), &order_state.key().to_bytes(), &transfer_mint.key().to_bytes()], bump, payer = orderer, token::mint = transfer_mint, token::authority = order_state)]
+    pub order_vault: Box<Account<'info, TokenAccount>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(mut, seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    #[account(mut)]
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    /// The swap program allowed by the [Beamsplitter]
+    #[account(executable, address = beamsplitter.swap_program @ BeamsplitterErrors::SwapProgramNotAllowed)]
+    pub swap_program: AccountInfo<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSingleAssetRedemption<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The single asset the orderer is paid out in
+    #[account(address = order_state.swap_mint)]
+    pub swap_mint: Account<'info, Mint>,
+
+    /// The [Signer] of the tx and owner of the [Deposit] [Account]
+    #[account(mut)]
+    pub orderer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that receives the order's output
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = orderer)]
+    pub orderer_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    /// The [TokenAccount] collecting the output of each swap, closed here
+    #[account(mut, associated_token::mint = swap_mint, associated_token::authority = order_state)]
+    pub order_swap_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    #[account(seeds = [b"OrderState".as_ref(), &beamsplitter.key().to_bytes(), &prism_etf_mint.key().to_bytes(), &orderer.key().to_bytes(), &(order_state.id as u64).to_le_bytes()], bump = order_state.bump, has_one = transferred_tokens, mut)]
+    pub order_state: Box<Account<'info, OrderState>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub transferred_tokens: AccountLoader<'info, TransferredTokens>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteOrder<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitFeeVault<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] that accrues the fees of the [PrismEtf]
+    #[account(init, payer = payer, associated_token::mint = prism_etf_mint, associated_token::authority = fee_vault)]
+    pub fee_vault_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(init, seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump, payer = payer, space = FEE_VAULT_SIZE + 8)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub rent: Sysvar<'info, Rent>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub owner: Signer<'info>,
+
+    /// The mint of the fees being withdrawn, the etf itself or one of its weighted tokens
+    pub fee_mint: Account<'info, Mint>,
+
+    /// The [TokenAccount] the fees are withdrawn to
+    #[account(mut, token::mint = fee_mint)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] of the [FeeVault] holding `fee_mint`
+    #[account(associated_token::mint = fee_mint, associated_token::authority = fee_vault, mut)]
+    pub fee_vault_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(mut, seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        has_one = owner,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawManagerFees<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    /// The mint of the fees being withdrawn, the etf itself or one of its weighted tokens
+    pub fee_mint: Account<'info, Mint>,
+
+    /// The [TokenAccount] the fees are withdrawn to
+    #[account(mut, token::mint = fee_mint)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    // ========================= ATA's =========================
+    /// The [TokenAccount] of the [FeeVault] holding `fee_mint`
+    #[account(associated_token::mint = fee_mint, associated_token::authority = fee_vault, mut)]
+    pub fee_vault_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The [FeeVault] of the [PrismEtf]
+    #[account(mut, seeds = [b"FeeVault".as_ref(), &prism_etf.key().to_bytes()], bump = fee_vault.bump)]
+    pub fee_vault: Box<Account<'info, FeeVault>>,
+
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = weighted_tokens, has_one = manager)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Big Data Accounts =========================
+    pub weighted_tokens: AccountLoader<'info, WeightedTokens>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct AddFeeExemption<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The orderer whose fees are lowered
+    pub exempt: AccountInfo<'info>,
+
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    /// Pays for the [FeeExemption], so the authority can be a program owned PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [FeeExemption] of the exempt orderer
+    #[account(init, seeds = [b"FeeExemption".as_ref(), &prism_etf.key().to_bytes(), &exempt.key().to_bytes()], bump, payer = payer, space = FEE_EXEMPTION_SIZE + 8)]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFeeExemption<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub exempt: AccountInfo<'info>,
+
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    /// Paid for the [FeeExemption], and is refunded its rent
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [FeeExemption] of the exempt orderer
+    #[account(seeds = [b"FeeExemption".as_ref(), &prism_etf.key().to_bytes(), &exempt.key().to_bytes()], bump = fee_exemption.bump, has_one = prism_etf, has_one = exempt, has_one = payer, mut, close = payer)]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPendingChanges<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingFeeChanges<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingManagerChanges<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetChangeDelay<'info> {
+    pub owner: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        has_one = owner,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyChangeDelay<'info> {
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    /// The key being given the role
+    pub grantee: AccountInfo<'info>,
+
+    pub owner: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        has_one = owner,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    pub owner: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        has_one = owner,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    /// The Beamsplitter owner or its pauser
+    #[account(constraint = beamsplitter.has_role(Role::PAUSER, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetOwner<'info> {
+    pub new_owner: AccountInfo<'info>,
+
+    pub owner: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        has_one = owner,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetSwapProgram<'info> {
+    /// The program single asset orders may swap through
+    #[account(executable)]
+    pub swap_program: AccountInfo<'info>,
+
+    /// The Beamsplitter owner or its curator
+    #[account(constraint = beamsplitter.has_role(Role::CURATOR, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultManagerCut<'info> {
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultConstruction<'info> {
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetDefaultDeconstruction<'info> {
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+        mut
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetManager<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub new_manager: AccountInfo<'info>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
     #[account(
         seeds = [
             b"Beamsplitter".as_ref(),
         ],
         bump = beamsplitter.bump,
-        has_one = owner,
-        mut
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-pub struct SetDefaultConstruction<'info> {
-    pub owner: Signer<'info>,
+pub struct SetManagerCut<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
 
     // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
     /// The [Beamsplitter] [Account] that holds all of the Program's funds
     #[account(
         seeds = [
             b"Beamsplitter".as_ref(),
         ],
         bump = beamsplitter.bump,
-        has_one = owner,
-        mut
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
-pub struct SetDefaultDeconstruction<'info> {
-    pub owner: Signer<'info>,
+pub struct SetWeightMode<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
 
     // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
     /// The [Beamsplitter] [Account] that holds all of the Program's funds
     #[account(
         seeds = [
             b"Beamsplitter".as_ref(),
         ],
         bump = beamsplitter.bump,
-        has_one = owner,
-        mut
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
 }
 
 #[derive(Accounts)]
-pub struct SetManager<'info> {
+pub struct SetOrderLimits<'info> {
     pub prism_etf_mint: Account<'info, Mint>,
 
-    pub new_manager: AccountInfo<'info>,
+    pub manager: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetPermissioned<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
 
     pub manager: Signer<'info>,
 
@@ -461,7 +1967,152 @@ pub struct SetManager<'info> {
 }
 
 #[derive(Accounts)]
-pub struct SetManagerCut<'info> {
+pub struct AddAllowlistEntry<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    /// The wallet being allowlisted
+    pub holder: AccountInfo<'info>,
+
+    pub manager: Signer<'info>,
+
+    /// Pays for the [AllowlistEntry], so the manager can be a program owned PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [AllowlistEntry] of the holder
+    #[account(init, seeds = [b"AllowlistEntry".as_ref(), &prism_etf.key().to_bytes(), &holder.key().to_bytes()], bump, payer = payer, space = ALLOWLIST_ENTRY_SIZE + 8)]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowlistEntry<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub holder: AccountInfo<'info>,
+
+    pub manager: Signer<'info>,
+
+    /// Paid for the [AllowlistEntry], and is refunded its rent
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [AllowlistEntry] of the holder
+    #[account(seeds = [b"AllowlistEntry".as_ref(), &prism_etf.key().to_bytes(), &holder.key().to_bytes()], bump = allowlist_entry.bump, has_one = prism_etf, has_one = holder, has_one = payer, mut, close = payer)]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct SetFreezable<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager, mut)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct FreezeHolder<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The holder's etf [TokenAccount] being frozen
+    #[account(mut, token::mint = prism_etf_mint)]
+    pub holder_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ThawHolder<'info> {
+    pub prism_etf_mint: Account<'info, Mint>,
+
+    pub manager: Signer<'info>,
+
+    // ========================= ATA's =========================
+    /// The holder's etf [TokenAccount] being thawed
+    #[account(mut, token::mint = prism_etf_mint)]
+    pub holder_etf_ata: Box<Account<'info, TokenAccount>>,
+
+    // ========================= PDA's =========================
+    /// The Prism ETF [Account] that this instruction uses
+    #[account(seeds = [b"PrismEtf".as_ref(), &prism_etf_mint.key().to_bytes(), &beamsplitter.key().to_bytes()], bump = prism_etf.bump, has_one = manager)]
+    pub prism_etf: Box<Account<'info, PrismEtf>>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeMode<'info> {
     pub prism_etf_mint: Account<'info, Mint>,
 
     pub manager: Signer<'info>,
@@ -481,11 +2132,99 @@ pub struct SetManagerCut<'info> {
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
 }
 
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct InitPriceFeed<'info> {
+    /// The mint being priced
+    pub mint: Account<'info, Mint>,
+
+    /// The price account for the mint
+    pub feed: AccountInfo<'info>,
+
+    /// The Beamsplitter owner or its curator
+    #[account(constraint = beamsplitter.has_role(Role::CURATOR, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    /// Pays for the new account, separate from the authority so PDA authorities (multisigs, governance) can sign
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [PriceFeed] registered for the mint
+    #[account(init, seeds = [b"PriceFeed".as_ref(), &beamsplitter.key().to_bytes(), &mint.key().to_bytes()], bump, payer = payer, space = PRICE_FEED_SIZE + 8)]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    pub mint: Account<'info, Mint>,
+
+    /// The new price account for the mint
+    pub feed: AccountInfo<'info>,
+
+    /// The Beamsplitter owner or its curator
+    #[account(constraint = beamsplitter.has_role(Role::CURATOR, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [PriceFeed] registered for the mint
+    #[account(seeds = [b"PriceFeed".as_ref(), &beamsplitter.key().to_bytes(), &mint.key().to_bytes()], bump = price_feed.bump, mut)]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePriceFeed<'info> {
+    pub mint: Account<'info, Mint>,
+
+    /// The Beamsplitter owner or its curator
+    #[account(mut, constraint = beamsplitter.has_role(Role::CURATOR, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
+
+    // ========================= PDA's =========================
+    /// The [PriceFeed] registered for the mint
+    #[account(seeds = [b"PriceFeed".as_ref(), &beamsplitter.key().to_bytes(), &mint.key().to_bytes()], bump = price_feed.bump, mut, close = authority)]
+    pub price_feed: Account<'info, PriceFeed>,
+
+    /// The [Beamsplitter] [Account] that holds all of the Program's funds
+    #[account(
+        seeds = [
+            b"Beamsplitter".as_ref(),
+        ],
+        bump = beamsplitter.bump,
+    )]
+    pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct SetConstruction<'info> {
     pub prism_etf_mint: Account<'info, Mint>,
 
-    pub owner: Signer<'info>,
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
 
     // ========================= PDA's =========================
     /// The Prism ETF [Account] that this instruction uses
@@ -500,13 +2239,18 @@ pub struct SetConstruction<'info> {
         bump = beamsplitter.bump,
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
 pub struct SetDeconstruction<'info> {
     pub prism_etf_mint: Account<'info, Mint>,
 
-    pub owner: Signer<'info>,
+    /// The Beamsplitter owner or its fee admin
+    #[account(constraint = beamsplitter.has_role(Role::FEE_ADMIN, &authority.key()) @ BeamsplitterErrors::MissingRole)]
+    pub authority: Signer<'info>,
 
     // ========================= PDA's =========================
     /// The Prism ETF [Account] that this instruction uses
@@ -521,6 +2265,9 @@ pub struct SetDeconstruction<'info> {
         bump = beamsplitter.bump,
     )]
     pub beamsplitter: Box<Account<'info, Beamsplitter>>,
+
+    // ========================= Programs =========================
+    pub clock: Sysvar<'info, Clock>,
 }
 
 #[derive(Accounts)]
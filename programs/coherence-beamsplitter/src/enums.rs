@@ -40,6 +40,62 @@ impl Default for OrderStatus {
     }
 }
 
+// How the weight of each weighted token is interpreted
+#[derive(Debug, Copy, Clone, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum WeightMode {
+    // Weight is a fixed quantity of the asset per etf token
+    QUANTITY,
+    // Weight is a target allocation (bps) of the etf's value, amounts are priced at order time
+    PERCENTAGE,
+}
+
+impl Default for WeightMode {
+    fn default() -> Self {
+        WeightMode::QUANTITY
+    }
+}
+
+// The account format of a registered price feed
+#[derive(Debug, Copy, Clone, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum PriceFeedKind {
+    PYTH,
+    SWITCHBOARD,
+}
+
+impl Default for PriceFeedKind {
+    fn default() -> Self {
+        PriceFeedKind::PYTH
+    }
+}
+
+// What construction fees are charged in
+#[derive(Debug, Copy, Clone, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum FeeMode {
+    // Extra etf tokens are minted to the fee vault, diluting holders
+    DILUTION,
+    // A share of each cohered asset is sent to the fee vault, so supply stays exactly backed
+    UNDERLYING,
+}
+
+impl Default for FeeMode {
+    fn default() -> Self {
+        FeeMode::DILUTION
+    }
+}
+
+// Operational roles the Beamsplitter owner can hand to hot keys, the owner always holds every role
+#[derive(Debug, Copy, Clone, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
+pub enum Role {
+    // Sets default and per-ETF fees, fee tiers and fee exemptions
+    FEE_ADMIN,
+    // Pauses and unpauses new orders
+    PAUSER,
+    // Rebalances ETFs with an autorebalancing schedule
+    AUTOREBALANCER,
+    // Allowlists the swap program and registers price feeds
+    CURATOR,
+}
+
 #[derive(Debug, Copy, Clone, AnchorDeserialize, AnchorSerialize, PartialEq, Eq)]
 pub enum RebalancingMode {
     OFF,
@@ -34,9 +34,10 @@ pub enum BeamsplitterErrors {
     IndexPassedBound, // 6012 - 0x177c
     #[msg("Wrong asset mint. Mint keys did not match. Try changing index passed.")]
     WrongIndexMint, // 6013 - 0x177d
+    // Reserved, no longer raised since weighted amounts use checked u128 math. Kept so later codes don't shift
     #[msg("Scaling failed or overflowed.")]
     ScaleFailure, // 6014 - 0x177e
-    #[msg("Decimal to u64 conversion failed or overflowed.")]
+    #[msg("Amount did not fit in a u64.")]
     U64Failure, // 6015 - 0x177f
     #[msg("Prism Etf was not done being designed when you tried to start an order.")]
     PrismEtfNotFinished, // 6016 - 0x1780
@@ -56,4 +57,86 @@ pub enum BeamsplitterErrors {
         "Attempted to take ownership of order state but order is pending and slot timeout has not yet passed."
     )]
     CouldNotBecomeOrderer, // 6021 - 0x1785
+    #[msg("Checked arithmetic overflowed or divided by zero.")]
+    MathOverflow, // 6022 - 0x1786
+    #[msg("Price feed account is not a valid price account.")]
+    InvalidPriceFeed, // 6023 - 0x1787
+    #[msg("Price feed is not currently trading or reported an unusable price.")]
+    PriceUnavailable, // 6024 - 0x1788
+    #[msg("Price feed passed does not belong to the weighted token's mint.")]
+    WrongPriceFeed, // 6025 - 0x1789
+    #[msg("Not enough price feeds were passed to price every weighted token.")]
+    MissingPriceFeeds, // 6026 - 0x178a
+    #[msg("Percentage allocations must add up to exactly 10000 bps.")]
+    InvalidAllocation, // 6027 - 0x178b
+    #[msg("Net asset value of the Prism Etf is zero.")]
+    ZeroNav, // 6028 - 0x178c
+    #[msg("Price feed has not been updated within its staleness limit.")]
+    StalePrice, // 6029 - 0x178d
+    #[msg("Price feed confidence interval is wider than its configured limit.")]
+    PriceTooUncertain, // 6030 - 0x178e
+    #[msg("Price feed limits are invalid, confidence cannot exceed 10000 bps.")]
+    InvalidPriceFeedConfig, // 6031 - 0x178f
+    #[msg("Swap program passed is not the one allowed by Beamsplitter.")]
+    SwapProgramNotAllowed, // 6032 - 0x1790
+    #[msg("Swap spent more of the order's input than allowed.")]
+    SwapInputExceeded, // 6033 - 0x1791
+    #[msg("Order would produce less than its minimum amount out.")]
+    MinAmountOutNotMet, // 6034 - 0x1792
+    #[msg("Order must be continued with the instructions matching how it was started.")]
+    WrongOrderFlow, // 6035 - 0x1793
+    #[msg("Instruction does not support the Prism Etf's weight mode.")]
+    UnsupportedWeightMode, // 6036 - 0x1794
+    #[msg("Attempted to withdraw more fees than have accrued.")]
+    InsufficientFees, // 6037 - 0x1795
+    #[msg("Fee account passed is not the fee vault's token account for this mint.")]
+    WrongFeeAccount, // 6038 - 0x1796
+    #[msg("Mint is already a weighted token of this ETF.")]
+    DuplicateMint, // 6039 - 0x1797
+    #[msg("Fee tiers must have increasing thresholds and never raise the construction fee.")]
+    InvalidFeeTiers, // 6040 - 0x1798
+    #[msg("Fee exemption passed does not belong to the orderer.")]
+    WrongFeeExemption, // 6041 - 0x1799
+    #[msg("There are no pending changes to apply or cancel.")]
+    NoPendingChanges, // 6042 - 0x179a
+    #[msg("Pending changes cannot be applied before their effective time.")]
+    ChangesNotEffective, // 6043 - 0x179b
+    // Reserved, no longer raised since the owner and the manager each cancel only their own changes. Kept so later
+    // codes don't shift
+    #[msg("Signer is neither the Beamsplitter owner nor the ETF manager.")]
+    NotOwnerOrManager, // 6044 - 0x179c
+    #[msg("Signer is neither the Beamsplitter owner nor holds the required role.")]
+    MissingRole, // 6045 - 0x179d
+    #[msg("New orders are paused.")]
+    Paused, // 6046 - 0x179e
+    #[msg("Descriptor name, symbol or tags are too long, or there are too many tags.")]
+    InvalidDescriptor, // 6047 - 0x179f
+    #[msg("An ETF cannot contain itself.")]
+    NestingCycle, // 6048 - 0x17a0
+    #[msg("Nesting this ETF would exceed the maximum nesting depth.")]
+    NestingTooDeep, // 6049 - 0x17a1
+    #[msg("Token is not a nested ETF, or a nested ETF's account does not follow its mint.")]
+    InvalidNesting, // 6050 - 0x17a2
+    #[msg("Order would take the ETF's supply above its cap.")]
+    SupplyCapExceeded, // 6051 - 0x17a3
+    #[msg("Order is below the ETF's minimum order amount.")]
+    OrderTooSmall, // 6052 - 0x17a4
+    #[msg("Order is above the ETF's maximum order amount.")]
+    OrderTooLarge, // 6053 - 0x17a5
+    #[msg("Minimum order amount cannot be above the maximum order amount.")]
+    InvalidOrderLimits, // 6054 - 0x17a6
+    #[msg("Orderer is not on the allowlist of this permissioned ETF.")]
+    NotAllowlisted, // 6055 - 0x17a7
+    #[msg("Freezing holders was not enabled for this ETF.")]
+    FreezingDisabled, // 6056 - 0x17a8
+    #[msg("The fee vault's token account cannot be frozen.")]
+    CannotFreezeFeeVault, // 6057 - 0x17a9
+    #[msg("Token-2022 mint is non-transferable, has a permanent delegate or a transfer hook.")]
+    IncompatibleMint, // 6058 - 0x17aa
+    #[msg("Token account is not the expected account of its mint and owner.")]
+    WrongTokenAccount, // 6059 - 0x17ab
+    #[msg("Instruction does not support the Prism Etf's fee mode.")]
+    UnsupportedFeeMode, // 6060 - 0x17ac
+    #[msg("Transfer credited less than required once its transfer fee was withheld.")]
+    TransferFeeExceeded, // 6061 - 0x17ad
 }
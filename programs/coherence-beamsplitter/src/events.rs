@@ -0,0 +1,441 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    enums::{FeeMode, OrderType, PriceFeedKind, Role, WeightMode},
+    state::{PendingFeeChanges, PendingManagerChanges},
+};
+
+/// The Beamsplitter program state was initialized
+#[event]
+pub struct BeamsplitterInitialized {
+    pub owner: Pubkey,
+}
+
+/// A new [PrismEtf] was created for `prism_etf_mint`
+#[event]
+pub struct PrismEtfCreated {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub weighted_tokens: Pubkey,
+}
+
+/// The manager created or updated the token metadata of `prism_etf_mint`
+#[event]
+pub struct PrismEtfMetadataChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+/// The manager created or edited the [PrismEtfDescriptor] of `prism_etf_mint`
+#[event]
+pub struct PrismEtfDescribed {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub descriptor: Pubkey,
+}
+
+/// Weighted tokens were pushed into an unfinished ETF
+#[event]
+pub struct TokensPushed {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    /// Mints of the pushed tokens, in index order
+    pub mints: Vec<Pubkey>,
+    /// Weights of the pushed tokens, in index order
+    pub weights: Vec<u64>,
+    /// Number of weighted tokens in the ETF after the push
+    pub length: u16,
+}
+
+/// The weight mode of an unfinished ETF was changed
+#[event]
+pub struct WeightModeChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub weight_mode: WeightMode,
+    pub initial_nav: u64,
+}
+
+/// The fee mode of an unfinished ETF was changed
+#[event]
+pub struct FeeModeChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub fee_mode: FeeMode,
+}
+
+/// The manager changed the supply cap or order limits of an ETF
+#[event]
+pub struct OrderLimitsChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub max_supply: u64,
+    pub min_order_amount: u64,
+    pub max_order_amount: u64,
+}
+
+/// The manager made constructing an ETF require an [AllowlistEntry], or lifted that
+#[event]
+pub struct PermissionedChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub permissioned: bool,
+}
+
+/// The manager allowlisted `holder` to construct a permissioned ETF
+#[event]
+pub struct AllowlistEntryAdded {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub holder: Pubkey,
+}
+
+/// The manager removed `holder` from the allowlist of a permissioned ETF
+#[event]
+pub struct AllowlistEntryRemoved {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub holder: Pubkey,
+}
+
+/// The manager of an unfinished ETF enabled or disabled freezing holders
+#[event]
+pub struct FreezableChanged {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub freezable: bool,
+}
+
+/// The manager froze a holder's etf token account
+#[event]
+pub struct HolderFrozen {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub holder: Pubkey,
+    pub token_account: Pubkey,
+}
+
+/// The manager thawed a holder's etf token account
+#[event]
+pub struct HolderThawed {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub holder: Pubkey,
+    pub token_account: Pubkey,
+}
+
+/// An ETF was finalized and can now take orders
+#[event]
+pub struct PrismEtfFinalized {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub weight_mode: WeightMode,
+    pub length: u16,
+}
+
+/// A price feed was registered or changed for `mint`
+#[event]
+pub struct PriceFeedSet {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub feed: Pubkey,
+    pub kind: PriceFeedKind,
+    pub max_staleness: u32,
+    pub max_confidence_bps: u16,
+}
+
+/// The price feed registered for `mint` was closed
+#[event]
+pub struct PriceFeedClosed {
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// An orderer created a new order state for an ETF
+#[event]
+pub struct OrderStateCreated {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub id: u16,
+}
+
+/// An order was started, DECONSTRUCTION orders have burned `amount` at this point
+#[event]
+pub struct OrderStarted {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub order_type: OrderType,
+    pub amount: u64,
+}
+
+/// A weighted token was transferred from the orderer into the ETF
+#[event]
+pub struct AssetCohered {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub mint: Pubkey,
+    pub index: u16,
+    pub amount: u64,
+    /// Sent to the fee vault on top of `amount` (UNDERLYING fee mode)
+    pub fee: u64,
+}
+
+/// A weighted token was transferred from the ETF to the orderer
+#[event]
+pub struct AssetDecohered {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub mint: Pubkey,
+    pub index: u16,
+    pub amount: u64,
+    /// Refunded from the fee vault on top of `amount`, if cohere took an UNDERLYING fee for it
+    pub fee: u64,
+}
+
+/// An order completed. Fees are only charged when etf tokens are minted
+#[event]
+pub struct OrderFinalized {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub order_type: OrderType,
+    pub amount: u64,
+    /// Etf tokens minted to the orderer
+    pub orderer_amount: u64,
+    /// Etf tokens accrued to the Beamsplitter owner in the fee vault
+    pub owner_fee: u64,
+    /// Etf tokens accrued to the ETF manager in the fee vault
+    pub manager_fee: u64,
+}
+
+/// An order was unwound instead of completed. A CONSTRUCTION order had every asset decohered back,
+/// a DECONSTRUCTION order had none decohered and its burned etf tokens were minted back
+#[event]
+pub struct OrderCancelled {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub order_type: OrderType,
+    pub amount: u64,
+    /// Etf tokens minted back to the orderer
+    pub orderer_amount: u64,
+    /// Etf tokens accrued to the Beamsplitter owner in the fee vault
+    pub owner_fee: u64,
+    /// Etf tokens accrued to the ETF manager in the fee vault
+    pub manager_fee: u64,
+}
+
+/// The Beamsplitter owner or fee admin changed the defaults new ETFs start with
+#[event]
+pub struct DefaultFeesChanged {
+    pub authority: Pubkey,
+    pub default_construction_bps: u16,
+    pub default_deconstruction_bps: u16,
+    pub default_manager_cut: u16,
+}
+
+/// The Beamsplitter owner or fee admin lowered or waived the construction fee of `exempt` on an ETF
+#[event]
+pub struct FeeExemptionAdded {
+    pub prism_etf_mint: Pubkey,
+    pub authority: Pubkey,
+    pub exempt: Pubkey,
+    pub construction_bps: u16,
+}
+
+/// The Beamsplitter owner or fee admin removed the fee exemption of `exempt`
+#[event]
+pub struct FeeExemptionRemoved {
+    pub prism_etf_mint: Pubkey,
+    pub authority: Pubkey,
+    pub exempt: Pubkey,
+}
+
+/// Ownership of the Beamsplitter program was transferred
+#[event]
+pub struct OwnerChanged {
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+/// Fee changes of an ETF were queued by `authority`, they can be applied from `pending_fee_changes.effective_at`
+#[event]
+pub struct PrismEtfFeeChangesQueued {
+    pub prism_etf_mint: Pubkey,
+    pub authority: Pubkey,
+    /// Every fee change now pending, including ones queued earlier
+    pub pending_fee_changes: PendingFeeChanges,
+}
+
+/// Manager changes of an ETF were queued by its manager, they can be applied from
+/// `pending_manager_changes.effective_at`
+#[event]
+pub struct PrismEtfManagerChangesQueued {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    /// Every manager change now pending, including ones queued earlier
+    pub pending_manager_changes: PendingManagerChanges,
+}
+
+/// The pending changes of an ETF whose delay passed took effect, either side is empty if it was not applied
+#[event]
+pub struct PrismEtfChangesApplied {
+    pub prism_etf_mint: Pubkey,
+    pub applied_fee_changes: PendingFeeChanges,
+    pub applied_manager_changes: PendingManagerChanges,
+    pub construction_bps: u16,
+    pub deconstruction_bps: u16,
+    pub manager_cut: u16,
+    pub manager: Pubkey,
+}
+
+/// The pending fee changes of an ETF were dropped by `authority`
+#[event]
+pub struct PrismEtfFeeChangesCancelled {
+    pub prism_etf_mint: Pubkey,
+    pub authority: Pubkey,
+    pub cancelled_fee_changes: PendingFeeChanges,
+}
+
+/// The pending manager changes of an ETF were dropped by its manager
+#[event]
+pub struct PrismEtfManagerChangesCancelled {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub cancelled_manager_changes: PendingManagerChanges,
+}
+
+/// The Beamsplitter owner queued a new change delay, which can be applied from `effective_at`
+#[event]
+pub struct ChangeDelayQueued {
+    pub owner: Pubkey,
+    pub change_delay: u32,
+    pub effective_at: i64,
+}
+
+/// The change delay the Beamsplitter owner queued took effect
+#[event]
+pub struct ChangeDelayChanged {
+    pub owner: Pubkey,
+    pub change_delay: u32,
+}
+
+/// One of an ETF's asset token accounts was closed, `crumbs` is the amount swept to `destination`
+#[event]
+pub struct PrismAtaClosed {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    pub prism_asset_ata: Pubkey,
+    pub destination: Pubkey,
+    pub crumbs: u64,
+}
+
+/// An ETF and its weighted tokens were closed
+#[event]
+pub struct PrismEtfClosed {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+}
+
+/// An order state and its transferred tokens were closed
+#[event]
+pub struct OrderStateClosed {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+}
+
+/// A fee vault was created for an ETF
+#[event]
+pub struct FeeVaultCreated {
+    pub prism_etf_mint: Pubkey,
+    pub fee_vault: Pubkey,
+}
+
+/// The Beamsplitter owner withdrew accrued protocol fees
+#[event]
+pub struct ProtocolFeesWithdrawn {
+    pub prism_etf_mint: Pubkey,
+    pub owner: Pubkey,
+    /// The etf mint or the weighted token the fees were accrued in
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// Protocol fees left in the vault
+    pub remaining: u64,
+}
+
+/// The ETF manager withdrew accrued manager fees
+#[event]
+pub struct ManagerFeesWithdrawn {
+    pub prism_etf_mint: Pubkey,
+    pub manager: Pubkey,
+    /// The etf mint or the weighted token the fees were accrued in
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// Manager fees left in the vault
+    pub remaining: u64,
+}
+
+/// The Beamsplitter owner or curator changed the program single asset orders swap through
+#[event]
+pub struct SwapProgramChanged {
+    pub authority: Pubkey,
+    pub swap_program: Pubkey,
+}
+
+/// A single asset order was started, `amount` is the input deposited (CONSTRUCTION) or etf tokens burned (DECONSTRUCTION)
+#[event]
+pub struct SingleAssetOrderStarted {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub order_type: OrderType,
+    pub swap_mint: Pubkey,
+    pub amount: u64,
+    pub min_amount_out: u64,
+}
+
+/// Part of a single asset order was swapped between `swap_mint` and the weighted token `mint`
+#[event]
+pub struct AssetSwapped {
+    pub prism_etf_mint: Pubkey,
+    pub orderer: Pubkey,
+    pub order_state: Pubkey,
+    pub swap_mint: Pubkey,
+    pub mint: Pubkey,
+    pub index: u16,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// The Beamsplitter owner gave `grantee` a role, replacing its previous holder
+#[event]
+pub struct RoleGranted {
+    pub owner: Pubkey,
+    pub role: Role,
+    pub grantee: Pubkey,
+}
+
+/// The Beamsplitter owner took a role away from `revoked`, leaving it to the owner alone
+#[event]
+pub struct RoleRevoked {
+    pub owner: Pubkey,
+    pub role: Role,
+    pub revoked: Pubkey,
+}
+
+/// New orders were paused or unpaused by `authority`
+#[event]
+pub struct PausedChanged {
+    pub authority: Pubkey,
+    pub paused: bool,
+}
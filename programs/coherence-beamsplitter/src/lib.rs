@@ -2,12 +2,21 @@
 pub mod context;
 pub mod enums;
 pub mod errors;
+pub mod events;
+pub mod math;
+pub mod metadata;
+pub mod native;
+pub mod oracle;
+pub mod quote;
 pub mod state;
+pub mod swap;
+pub mod token_interface;
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
 use context::*;
 use enums::*;
 use errors::BeamsplitterErrors;
+use events::*;
 use state::*;
 
 declare_id!("Cm921Cpvi9CbeWyrjTUFccdaHTZwcQjJmEuGhNTo2NTh");
@@ -26,6 +35,18 @@ const DEFAULT_MANAGER_BPS: u16 = 2_000;
 #[constant]
 const BASIS_POINT_DECIMALS: u8 = 4;
 
+// Weights are whole asset tokens per whole etf token with this many decimals of precision
+#[constant]
+const WEIGHT_DECIMALS: u8 = 9;
+
+// Prices and net asset values are quote units per whole token with this many decimals of precision
+#[constant]
+const PRICE_DECIMALS: u8 = 9;
+
+// The default number of seconds fee and manager changes are queued for before they can be applied (1 day)
+#[constant]
+const DEFAULT_CHANGE_DELAY: u32 = 86_400;
+
 // The number of slots by default orders can pend for before ownership may be stolen
 #[constant]
 const DEFAULT_TIMEOUT_SLOTS: u16 = 10;
@@ -33,18 +54,36 @@ const DEFAULT_TIMEOUT_SLOTS: u16 = 10;
 #[program]
 pub mod coherence_beamsplitter {
     use anchor_lang::solana_program::*;
-    use std::{
-        mem::size_of,
-        ops::{Mul, MulAssign, SubAssign},
-    };
+    use std::mem::size_of;
 
+    use anchor_lang::solana_program::program::set_return_data;
     use anchor_spl::token::{
-        burn, close_account, mint_to, transfer, Burn, CloseAccount, MintTo, Transfer,
+        burn, close_account, freeze_account, mint_to, thaw_account, transfer, Burn, CloseAccount,
+        FreezeAccount, MintTo, ThawAccount, Transfer,
     };
 
-    use rust_decimal::{prelude::ToPrimitive, Decimal};
+    use crate::{
+        context::{
+            allowlisted, exempt_construction_bps, fee_vault_ata, nested_prism_etf,
+            split_fee_exemption,
+        },
+        math::{
+            backed_amount, split_asset_fee, split_construction_fee, to_u64, valid_fee_tiers,
+            weighted_amount, AssetFee, FeeSplit, BPS_DENOMINATOR,
+        },
+        native::wrap_lamports,
+        oracle::load_prices,
+        quote::{
+            percentage_construction_amounts, percentage_deconstruction_amounts, percentage_nav,
+            quantity_nav,
+        },
+        swap::invoke_swap,
+        token_interface::{associated_token_amount, load_mint, token_amount, transfer_checked},
+    };
     const BEAMSPLITTER_PDA_SEED: &[u8] = b"Beamsplitter" as &[u8];
     const PRISM_ETF_PDA_SEED: &[u8] = b"PrismEtf" as &[u8];
+    const ORDER_STATE_PDA_SEED: &[u8] = b"OrderState" as &[u8];
+    const FEE_VAULT_PDA_SEED: &[u8] = b"FeeVault" as &[u8];
 
     use super::*;
 
@@ -62,8 +101,20 @@ pub mod coherence_beamsplitter {
             autorebalancer: ctx.accounts.owner.key(),
             referral_cut: 0,
             timeout_slots: DEFAULT_TIMEOUT_SLOTS,
+            swap_program: Pubkey::default(),
+            change_delay: DEFAULT_CHANGE_DELAY,
+            pending_change_delay: None,
+            change_delay_effective_at: 0,
+            fee_admin: Pubkey::default(),
+            pauser: Pubkey::default(),
+            curator: Pubkey::default(),
+            paused: false,
         };
 
+        emit!(BeamsplitterInitialized {
+            owner: beamsplitter.owner,
+        });
+
         Ok(())
     }
 
@@ -94,23 +145,9 @@ pub mod coherence_beamsplitter {
         let mint = &ctx.accounts.prism_etf_mint;
         let manager = &ctx.accounts.manager;
 
-        **prism_etf = PrismEtf {
-            manager: manager.key(),
-            weighted_tokens: weighted_tokens.key(),
-            status: PrismEtfStatus::UNFINISHED,
-            bump,
-            total_shared_order_states: 0,
-            construction_bps: beamsplitter.default_construction_bps,
-            deconstruction_bps: beamsplitter.default_deconstruction_bps,
-            manager_cut: beamsplitter.default_manager_cut,
-            manager_fee: beamsplitter.default_manager_fee,
-            rebalancing_mode: RebalancingMode::OFF,
-            autorebalancing_schedule: AutorebalancingSchedule::NEVER,
-            manager_schedule: ManagerSchedule::NEVER,
-            referer: manager.key(),
-        };
-
-        if beamsplitter.key() != mint.mint_authority.unwrap() {
+        **prism_etf = PrismEtf::new(manager.key(), weighted_tokens.key(), bump, beamsplitter);
+
+        if mint.mint_authority != COption::Some(beamsplitter.key()) {
             return Err(BeamsplitterErrors::NotMintAuthority.into());
         }
 
@@ -123,25 +160,222 @@ pub mod coherence_beamsplitter {
             return Err(BeamsplitterErrors::NonZeroSupply.into());
         }
 
+        emit!(PrismEtfCreated {
+            prism_etf_mint: mint.key(),
+            manager: manager.key(),
+            weighted_tokens: weighted_tokens.key(),
+        });
+
+        Ok(())
+    }
+
+    /*
+    Creates a [PrismEtf] along with its [Mint], so no separately created mint has to be handed over to Beamsplitter first
+
+    The mint is a PDA of the manager and a nonce chosen by the manager, with Beamsplitter as its mint and freeze
+    authority
+
+    Failure cases:
+        - the manager already used this nonce
+    */
+    pub fn init_prism_etf_with_mint(
+        ctx: Context<InitPrismEtfWithMint>,
+        bump: u8,
+        _nonce: u64,
+        _decimals: u8,
+    ) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        let beamsplitter = &ctx.accounts.beamsplitter;
+        let weighted_tokens = &ctx.accounts.weighted_tokens;
+        let mint = &ctx.accounts.prism_etf_mint;
+        let manager = &ctx.accounts.manager;
+
+        **prism_etf = PrismEtf::new(manager.key(), weighted_tokens.key(), bump, beamsplitter);
+
+        emit!(PrismEtfCreated {
+            prism_etf_mint: mint.key(),
+            manager: manager.key(),
+            weighted_tokens: weighted_tokens.key(),
+        });
+
         Ok(())
     }
 
     pub fn finalize_prism_etf(ctx: Context<FinalizePrismEtf>) -> Result<()> {
         let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+            let mut total_allocation: u64 = 0;
+            for idx in 0..weighted_tokens.length as usize {
+                total_allocation = total_allocation
+                    .checked_add(weighted_tokens.weighted_tokens[idx].weight)
+                    .ok_or(BeamsplitterErrors::MathOverflow)?;
+            }
+
+            if total_allocation != BPS_DENOMINATOR as u64 {
+                return Err(BeamsplitterErrors::InvalidAllocation.into());
+            }
+
+            if prism_etf.initial_nav == 0 {
+                return Err(BeamsplitterErrors::ZeroNav.into());
+            }
+        }
+
         prism_etf.status = PrismEtfStatus::FINISHED;
 
+        emit!(PrismEtfFinalized {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            weight_mode: prism_etf.weight_mode,
+            length: ctx.accounts.weighted_tokens.load()?.length,
+        });
+
+        Ok(())
+    }
+
+    /// Creates the [PrismEtfDescriptor] of an ETF, recording when it was described
+    pub fn init_prism_etf_descriptor(
+        ctx: Context<InitPrismEtfDescriptor>,
+        bump: u8,
+        name: String,
+        symbol: String,
+        description_hash: [u8; 32],
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let descriptor = &mut ctx.accounts.descriptor;
+        descriptor.prism_etf = ctx.accounts.prism_etf.key();
+        descriptor.created_at = ctx.accounts.clock.unix_timestamp;
+        descriptor.bump = bump;
+        descriptor.describe(&name, &symbol, description_hash, &tags)?;
+
+        emit!(PrismEtfDescribed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            descriptor: descriptor.key(),
+        });
+        Ok(())
+    }
+
+    /// Replaces the name, symbol, description hash and tags of an ETF's [PrismEtfDescriptor]
+    pub fn set_prism_etf_descriptor(
+        ctx: Context<SetPrismEtfDescriptor>,
+        name: String,
+        symbol: String,
+        description_hash: [u8; 32],
+        tags: Vec<String>,
+    ) -> Result<()> {
+        let descriptor = &mut ctx.accounts.descriptor;
+        descriptor.describe(&name, &symbol, description_hash, &tags)?;
+
+        emit!(PrismEtfDescribed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            descriptor: descriptor.key(),
+        });
+        Ok(())
+    }
+
+    /*
+    Creates Metaplex token metadata for the [PrismEtf]'s mint, so wallets can show its name and symbol
+
+    Beamsplitter signs as the mint authority and stays the update authority, so only the manager can change it later
+    through update_prism_etf_metadata
+
+    Failure cases:
+        - metadata already exists for the mint
+        - name, symbol or uri are longer than the metadata program allows
+    */
+    pub fn create_prism_etf_metadata(
+        ctx: Context<CreatePrismEtfMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        metadata::create_metadata(
+            &ctx.accounts.token_metadata_program,
+            &ctx.accounts.metadata,
+            &ctx.accounts.prism_etf_mint.to_account_info(),
+            &ctx.accounts.beamsplitter.to_account_info(),
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.rent.to_account_info(),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+            signer_seeds,
+        )?;
+
+        emit!(PrismEtfMetadataChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            name,
+            symbol,
+            uri,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces the name, symbol and uri of the [PrismEtf]'s token metadata
+    pub fn update_prism_etf_metadata(
+        ctx: Context<UpdatePrismEtfMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        metadata::update_metadata(
+            &ctx.accounts.token_metadata_program,
+            &ctx.accounts.metadata,
+            &ctx.accounts.beamsplitter.to_account_info(),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+            signer_seeds,
+        )?;
+
+        emit!(PrismEtfMetadataChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            name,
+            symbol,
+            uri,
+        });
+
         Ok(())
     }
 
-    /// Push weighted tokens into an ETF
+    /*
+    Push weighted tokens into an ETF
+
+    Failure cases:
+    - prism_etf is not UNFINISHED
+    - a token has zero weight, is the ETF's own mint or is already weighted
+    - a token's mint is not passed, or a nested ETF's [PrismEtf] is missing or not FINISHED
+    - nesting would exceed MAX_NESTING_DEPTH
+    - a Token-2022 mint is non-transferable, has a permanent delegate or a transfer hook
+
+    Remaining accounts:
+    - the [Mint] of each new token, in order, of either token program. A mint whose authority is Beamsplitter belongs to a Prism ETF, so its
+      [PrismEtf] must follow the mint. This way no nested ETF can be pushed without its depth being checked
+    */
     pub fn push_tokens(ctx: Context<PushTokens>, new_tokens: Vec<WeightedToken>) -> Result<()> {
-        let prism_etf = &ctx.accounts.prism_etf;
+        let prism_etf_mint = ctx.accounts.prism_etf_mint.key();
+        let beamsplitter = ctx.accounts.beamsplitter.key();
+        let prism_etf = &mut ctx.accounts.prism_etf;
         let weighted_tokens = &mut ctx.accounts.weighted_tokens.load_mut()?;
 
         if prism_etf.status != PrismEtfStatus::UNFINISHED {
             return Err(BeamsplitterErrors::IsFinished.into());
         }
 
+        let mut remaining_accounts = ctx.remaining_accounts.iter();
         for (idx, weighted_token) in new_tokens.iter().enumerate() {
             if weighted_token.weight == 0 {
                 return Err(BeamsplitterErrors::ZeroWeight.into());
@@ -149,12 +383,379 @@ pub mod coherence_beamsplitter {
             if weighted_tokens.length >= weighted_tokens.capacity {
                 return Err(BeamsplitterErrors::ETFFull.into());
             }
+            if weighted_token.mint == prism_etf_mint {
+                return Err(BeamsplitterErrors::NestingCycle.into());
+            }
             let etf_idx = weighted_tokens.length as usize;
+            // Fees and backing are tracked per mint by index, so each mint may only be weighted once
+            if weighted_tokens.weighted_tokens[..idx + etf_idx]
+                .iter()
+                .any(|pushed_token| pushed_token.mint == weighted_token.mint)
+            {
+                return Err(BeamsplitterErrors::DuplicateMint.into());
+            }
             weighted_tokens.weighted_tokens[idx + etf_idx] = weighted_token.clone();
+
+            let mint_info = remaining_accounts
+                .next()
+                .ok_or(BeamsplitterErrors::IndexPassedBound)?;
+            let mint = load_mint(mint_info)?;
+            if mint.key != weighted_token.mint {
+                return Err(BeamsplitterErrors::WrongIndexMint.into());
+            }
+
+            // Only Prism ETF mints are minted by Beamsplitter
+            if mint.mint_authority != COption::Some(beamsplitter) {
+                continue;
+            }
+
+            let nested_prism_etf = match remaining_accounts.next() {
+                Some(info) => nested_prism_etf(info, &weighted_token.mint, &beamsplitter)?,
+                None => None,
+            }
+            .ok_or(BeamsplitterErrors::InvalidNesting)?;
+
+            // Only FINISHED ETFs, whose tokens can no longer change, can be nested. So no ETF can end up inside one of
+            // its own constituents
+            if nested_prism_etf.status != PrismEtfStatus::FINISHED {
+                return Err(BeamsplitterErrors::PrismEtfNotFinished.into());
+            }
+
+            let nesting_depth = nested_prism_etf.nesting_depth + 1;
+            if nesting_depth > state::MAX_NESTING_DEPTH {
+                return Err(BeamsplitterErrors::NestingTooDeep.into());
+            }
+
+            prism_etf.nesting_depth = prism_etf.nesting_depth.max(nesting_depth);
+            prism_etf.nested_tokens |= 1 << (idx + etf_idx);
+        }
+
+        // Every remaining account must have matched a pushed token
+        if remaining_accounts.next().is_some() {
+            return Err(BeamsplitterErrors::InvalidNesting.into());
         }
 
         weighted_tokens.length += new_tokens.len() as u16;
 
+        emit!(TokensPushed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            mints: new_tokens.iter().map(|token| token.mint).collect(),
+            weights: new_tokens.iter().map(|token| token.weight).collect(),
+            length: weighted_tokens.length,
+        });
+
+        Ok(())
+    }
+
+    /// Sets how weights are interpreted, only while the ETF is still being designed
+    pub fn set_weight_mode(
+        ctx: Context<SetWeightMode>,
+        weight_mode: WeightMode,
+        initial_nav: u64,
+    ) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.status != PrismEtfStatus::UNFINISHED {
+            return Err(BeamsplitterErrors::IsFinished.into());
+        }
+
+        prism_etf.weight_mode = weight_mode;
+        prism_etf.initial_nav = initial_nav;
+
+        emit!(WeightModeChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            weight_mode,
+            initial_nav,
+        });
+        Ok(())
+    }
+
+    /// Lets the manager freeze and thaw holders, only while the ETF is still being designed. Beamsplitter must be the
+    /// mint's freeze authority
+    pub fn set_freezable(ctx: Context<SetFreezable>, freezable: bool) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.status != PrismEtfStatus::UNFINISHED {
+            return Err(BeamsplitterErrors::IsFinished.into());
+        }
+
+        if freezable
+            && ctx.accounts.prism_etf_mint.freeze_authority
+                != COption::Some(ctx.accounts.beamsplitter.key())
+        {
+            return Err(BeamsplitterErrors::NotFreezeAuthority.into());
+        }
+
+        prism_etf.freezable = freezable;
+
+        emit!(FreezableChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            freezable,
+        });
+        Ok(())
+    }
+
+    /*
+    Freezes a holder's etf token account, signed by Beamsplitter as the mint's freeze authority. For compliance
+    actions, e.g. holders removed from the allowlist of a permissioned ETF
+
+    Failure cases:
+    - freezing was not enabled for the ETF before it was finished
+    - the token account belongs to the ETF's fee vault
+    */
+    pub fn freeze_holder(ctx: Context<FreezeHolder>) -> Result<()> {
+        if !ctx.accounts.prism_etf.freezable {
+            return Err(BeamsplitterErrors::FreezingDisabled.into());
+        }
+
+        let (fee_vault, _) = Pubkey::find_program_address(
+            &[FEE_VAULT_PDA_SEED, &ctx.accounts.prism_etf.key().to_bytes()],
+            ctx.program_id,
+        );
+        if ctx.accounts.holder_etf_ata.owner == fee_vault {
+            return Err(BeamsplitterErrors::CannotFreezeFeeVault.into());
+        }
+
+        let freeze_accounts = FreezeAccount {
+            account: ctx.accounts.holder_etf_ata.to_account_info(),
+            mint: ctx.accounts.prism_etf_mint.to_account_info(),
+            authority: ctx.accounts.beamsplitter.to_account_info(),
+        };
+
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let freeze_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            freeze_accounts,
+            signer_seeds,
+        );
+
+        freeze_account(freeze_ctx)?;
+
+        emit!(HolderFrozen {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            holder: ctx.accounts.holder_etf_ata.owner,
+            token_account: ctx.accounts.holder_etf_ata.key(),
+        });
+        Ok(())
+    }
+
+    /// Thaws a holder's etf token account frozen by freeze_holder
+    pub fn thaw_holder(ctx: Context<ThawHolder>) -> Result<()> {
+        if !ctx.accounts.prism_etf.freezable {
+            return Err(BeamsplitterErrors::FreezingDisabled.into());
+        }
+
+        let thaw_accounts = ThawAccount {
+            account: ctx.accounts.holder_etf_ata.to_account_info(),
+            mint: ctx.accounts.prism_etf_mint.to_account_info(),
+            authority: ctx.accounts.beamsplitter.to_account_info(),
+        };
+
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let thaw_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            thaw_accounts,
+            signer_seeds,
+        );
+
+        thaw_account(thaw_ctx)?;
+
+        emit!(HolderThawed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            holder: ctx.accounts.holder_etf_ata.owner,
+            token_account: ctx.accounts.holder_etf_ata.key(),
+        });
+        Ok(())
+    }
+
+    /// Sets what construction fees are charged in, only while the ETF is still being designed
+    pub fn set_fee_mode(ctx: Context<SetFeeMode>, fee_mode: FeeMode) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.status != PrismEtfStatus::UNFINISHED {
+            return Err(BeamsplitterErrors::IsFinished.into());
+        }
+
+        prism_etf.fee_mode = fee_mode;
+
+        emit!(FeeModeChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            fee_mode,
+        });
+        Ok(())
+    }
+
+    /// Sets the supply cap and order size limits of an ETF, 0 removes the cap or maximum
+    pub fn set_order_limits(
+        ctx: Context<SetOrderLimits>,
+        max_supply: u64,
+        min_order_amount: u64,
+        max_order_amount: u64,
+    ) -> Result<()> {
+        if max_order_amount != 0 && min_order_amount > max_order_amount {
+            return Err(BeamsplitterErrors::InvalidOrderLimits.into());
+        }
+
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        prism_etf.max_supply = max_supply;
+        prism_etf.min_order_amount = min_order_amount;
+        prism_etf.max_order_amount = max_order_amount;
+
+        emit!(OrderLimitsChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            max_supply,
+            min_order_amount,
+            max_order_amount,
+        });
+        Ok(())
+    }
+
+    /// Makes constructing an ETF require an [AllowlistEntry], or lifts that requirement
+    pub fn set_permissioned(ctx: Context<SetPermissioned>, permissioned: bool) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        prism_etf.permissioned = permissioned;
+
+        emit!(PermissionedChanged {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: prism_etf.manager,
+            permissioned,
+        });
+        Ok(())
+    }
+
+    /// Allowlists a holder to construct a permissioned ETF
+    pub fn add_allowlist_entry(ctx: Context<AddAllowlistEntry>, bump: u8) -> Result<()> {
+        let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+        allowlist_entry.prism_etf = ctx.accounts.prism_etf.key();
+        allowlist_entry.holder = ctx.accounts.holder.key();
+        allowlist_entry.payer = ctx.accounts.payer.key();
+        allowlist_entry.bump = bump;
+
+        emit!(AllowlistEntryAdded {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            holder: allowlist_entry.holder,
+        });
+        Ok(())
+    }
+
+    /// Removes a holder from the allowlist, they can still redeem the tokens they hold
+    pub fn remove_allowlist_entry(ctx: Context<RemoveAllowlistEntry>) -> Result<()> {
+        emit!(AllowlistEntryRemoved {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            holder: ctx.accounts.holder.key(),
+        });
+        Ok(())
+    }
+
+    /// Registers the price feed used to price `mint` in PERCENTAGE mode ETFs
+    pub fn init_price_feed(
+        ctx: Context<InitPriceFeed>,
+        bump: u8,
+        kind: PriceFeedKind,
+        max_staleness: u32,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        if max_confidence_bps as u128 > BPS_DENOMINATOR {
+            return Err(BeamsplitterErrors::InvalidPriceFeedConfig.into());
+        }
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.mint = ctx.accounts.mint.key();
+        price_feed.feed = ctx.accounts.feed.key();
+        price_feed.kind = kind;
+        price_feed.max_staleness = max_staleness;
+        price_feed.max_confidence_bps = max_confidence_bps;
+        price_feed.decimals = ctx.accounts.mint.decimals;
+        price_feed.bump = bump;
+
+        emit!(PriceFeedSet {
+            mint: price_feed.mint,
+            authority: ctx.accounts.authority.key(),
+            feed: price_feed.feed,
+            kind,
+            max_staleness,
+            max_confidence_bps,
+        });
+        Ok(())
+    }
+
+    /// Points a registered mint at a new feed and/or changes its limits
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        kind: PriceFeedKind,
+        max_staleness: u32,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        if max_confidence_bps as u128 > BPS_DENOMINATOR {
+            return Err(BeamsplitterErrors::InvalidPriceFeedConfig.into());
+        }
+
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.feed = ctx.accounts.feed.key();
+        price_feed.kind = kind;
+        price_feed.max_staleness = max_staleness;
+        price_feed.max_confidence_bps = max_confidence_bps;
+
+        emit!(PriceFeedSet {
+            mint: price_feed.mint,
+            authority: ctx.accounts.authority.key(),
+            feed: price_feed.feed,
+            kind,
+            max_staleness,
+            max_confidence_bps,
+        });
+        Ok(())
+    }
+
+    pub fn close_price_feed(ctx: Context<ClosePriceFeed>) -> Result<()> {
+        emit!(PriceFeedClosed {
+            mint: ctx.accounts.mint.key(),
+            authority: ctx.accounts.authority.key(),
+        });
+        Ok(())
+    }
+
+    /// Lowers (or with 0 waives) the construction fee charged to `exempt` on the ETF
+    pub fn add_fee_exemption(
+        ctx: Context<AddFeeExemption>,
+        bump: u8,
+        construction_bps: u16,
+    ) -> Result<()> {
+        let fee_exemption = &mut ctx.accounts.fee_exemption;
+        fee_exemption.prism_etf = ctx.accounts.prism_etf.key();
+        fee_exemption.exempt = ctx.accounts.exempt.key();
+        fee_exemption.payer = ctx.accounts.payer.key();
+        fee_exemption.construction_bps = construction_bps;
+        fee_exemption.bump = bump;
+
+        emit!(FeeExemptionAdded {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            exempt: fee_exemption.exempt,
+            construction_bps,
+        });
+        Ok(())
+    }
+
+    pub fn remove_fee_exemption(ctx: Context<RemoveFeeExemption>) -> Result<()> {
+        emit!(FeeExemptionRemoved {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            exempt: ctx.accounts.exempt.key(),
+        });
         Ok(())
     }
 
@@ -167,6 +768,13 @@ pub mod coherence_beamsplitter {
         order_state.status = OrderStatus::SUCCEEDED;
         order_state.orderer = ctx.accounts.orderer.key();
         prism_etf.total_shared_order_states += 1;
+
+        emit!(OrderStateCreated {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            id,
+        });
         Ok(())
     }
 
@@ -174,18 +782,31 @@ pub mod coherence_beamsplitter {
     Initalize a new Prism ETF CONSTRUCTION or DECONSTRUCTION order
 
     Failure cases:
+    - new orders are paused
     - prism_etf.weighted_tokens_at != weighted_tokens.key()
     - order_state.status = PENDING
     - prism_etf is not owned by Beamsplitter
     - order_state is not owned by Beamsplitter
     - the amount of etf tokens being constructed or deconstructed is invalid
+    - the order is outside the ETF's order limits, or a CONSTRUCTION would exceed its supply cap
+    - CONSTRUCTION of a permissioned ETF without the orderer's [AllowlistEntry] as the first remaining account
+    - PERCENTAGE construction without a ([PriceFeed], feed) remaining account pair per weighted token
+
+    Remaining accounts:
+    - the orderer's [AllowlistEntry] for CONSTRUCTION of a permissioned ETF
+    - a ([PriceFeed], feed) pair per weighted token for PERCENTAGE construction
 
     Flow:
     1. Set order_state.status = PENDING
     2. Set order_state.type = <order_type>
-    3. if order_state.type == DECONSTRUCTION, burn <amount> of tokens
+    3. if PERCENTAGE mode, snapshot the amount of each asset into transferred_tokens.amounts
+    4. if order_state.type == DECONSTRUCTION, burn <amount> of tokens
     */
     pub fn start_order(ctx: Context<StartOrder>, order_type: OrderType, amount: u64) -> Result<()> {
+        if ctx.accounts.beamsplitter.paused {
+            return Err(BeamsplitterErrors::Paused.into());
+        }
+
         let order_state = &mut ctx.accounts.order_state;
         let prism_etf = &ctx.accounts.prism_etf;
 
@@ -210,15 +831,50 @@ pub mod coherence_beamsplitter {
             return Err(BeamsplitterErrors::ZeroOrder.into());
         }
 
+        prism_etf.check_order_limits(order_type, amount, ctx.accounts.prism_etf_mint.supply)?;
+
+        // Anyone may redeem, only allowlisted orderers may construct a permissioned ETF
+        let price_accounts = match order_type {
+            OrderType::CONSTRUCTION => allowlisted(
+                ctx.remaining_accounts,
+                prism_etf,
+                &ctx.accounts.orderer.key(),
+            )?,
+            OrderType::DECONSTRUCTION => ctx.remaining_accounts,
+        };
+
+        // A pending single asset order still holds the orderer's input
+        if order_state.status == OrderStatus::PENDING && order_state.swap_mint != Pubkey::default()
+        {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
         order_state.amount = amount;
         order_state.order_type = order_type;
         order_state.status = OrderStatus::PENDING;
+        order_state.swap_mint = Pubkey::default();
+        order_state.min_amount_out = 0;
 
-        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        emit!(OrderStarted {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            order_type,
+            amount,
+        });
+
+        let weighted_tokens = &mut ctx.accounts.weighted_tokens.load_mut()?;
         let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
         transferred_tokens.length = weighted_tokens.length;
 
+        let prism_etf_mint = &ctx.accounts.prism_etf_mint;
         let transferred_tokens_length = transferred_tokens.length as usize;
+
+        // No UNDERLYING fee has been taken for this order yet
+        for fee in transferred_tokens.fees[..transferred_tokens_length].iter_mut() {
+            *fee = 0;
+        }
+
         if order_state.order_type == OrderType::CONSTRUCTION {
             // Set all all switches to NOT transferred
             for transferred_token in
@@ -226,6 +882,34 @@ pub mod coherence_beamsplitter {
             {
                 *transferred_token = false;
             }
+
+            // Price each asset so it fills its target allocation of the order's value at the current NAV
+            if prism_etf.weight_mode == WeightMode::PERCENTAGE {
+                let prices = load_prices(
+                    price_accounts,
+                    weighted_tokens,
+                    ctx.accounts.clock.unix_timestamp,
+                )?;
+
+                let nav = percentage_nav(
+                    weighted_tokens,
+                    &prices,
+                    prism_etf_mint.supply,
+                    prism_etf.initial_nav,
+                    prism_etf_mint.decimals,
+                )?;
+
+                let amounts = percentage_construction_amounts(
+                    weighted_tokens,
+                    &prices,
+                    amount,
+                    nav,
+                    prism_etf_mint.decimals,
+                )?;
+
+                transferred_tokens.amounts[..amounts.len()].copy_from_slice(&amounts);
+            }
+
             // We can exit here, burning not required for CONSTRUCT
             return Ok(());
         } else {
@@ -235,6 +919,22 @@ pub mod coherence_beamsplitter {
             {
                 *transferred_token = true;
             }
+
+            // Pay out a pro rata share of the backing, which then no longer backs any supply
+            if prism_etf.weight_mode == WeightMode::PERCENTAGE {
+                let amounts = percentage_deconstruction_amounts(
+                    weighted_tokens,
+                    amount,
+                    prism_etf_mint.supply,
+                )?;
+
+                for (idx, &share) in amounts.iter().enumerate() {
+                    transferred_tokens.amounts[idx] = share;
+                    weighted_tokens.backing[idx] = weighted_tokens.backing[idx]
+                        .checked_sub(share)
+                        .ok_or(BeamsplitterErrors::PotentialUnderflow)?;
+                }
+            }
         }
 
         let mint_accounts = Burn {
@@ -252,9 +952,7 @@ pub mod coherence_beamsplitter {
             signer_seeds,
         );
 
-        let burn_amount = amount.to_u64().ok_or(BeamsplitterErrors::U64Failure)?;
-
-        burn(burn_ctx, burn_amount)?;
+        burn(burn_ctx, amount)?;
 
         // Set timeout of order
         /*order_state.timeout =
@@ -271,10 +969,16 @@ pub mod coherence_beamsplitter {
     - order_state.status = CANCELLED || SUCCEEDED
     - prism_etf is not owned by Beamsplitter
     - order_state is not owned by Beamsplitter
-    - the amount delegated is below required amount for the etf tokens being created
+    - the amount delegated is below required amount for the etf tokens being created (plus the fee in UNDERLYING fee mode)
+    - fee_vault_transfer_ata is not the fee vault's ATA for transfer_mint (UNDERLYING fee mode)
+    - the first remaining account is not the orderer's [FeeExemption] for the ETF
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption] for the ETF, lowering the fee charged in UNDERLYING fee mode
 
     Flow:
     1. Transfer amount of required tokens to Beamspltitter from user ata accounts
+    2. if UNDERLYING fee mode, transfer the fee on the required tokens to the fee vault
     */
     pub fn cohere(ctx: Context<Cohere>, index: u16) -> Result<()> {
         let order_state = &mut ctx.accounts.order_state;
@@ -284,6 +988,11 @@ pub mod coherence_beamsplitter {
             return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
         }
 
+        // Single asset orders are filled by swap_cohere
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
         let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
         let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
 
@@ -302,23 +1011,41 @@ pub mod coherence_beamsplitter {
 
         // The weighted token of asset being transferred
         let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
-        let required_amount = &mut Decimal::from(order_state.amount);
-
         let delegated_amount = ctx.accounts.orderer_transfer_ata.delegated_amount;
-        let amount = &Decimal::from(delegated_amount);
-        let weight = &mut Decimal::from(weighted_token.weight);
 
-        let prism_etf_decimals = ctx.accounts.prism_etf_mint.decimals;
-
-        required_amount.mul_assign(*weight);
+        // We need to account for the decimals of both the etf and the input
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                ctx.accounts.transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
 
-        // We need to account for the decimals of the input
-        match required_amount.set_scale(prism_etf_decimals.into()) {
-            Err(_error) => return Err(BeamsplitterErrors::ScaleFailure.into()),
-            _ => (),
-        }
+        // In UNDERLYING fee mode the fee is taken on top of the required amount, so supply stays exactly backed
+        let fee = match ctx.accounts.prism_etf.fee_mode {
+            FeeMode::DILUTION => AssetFee::default(),
+            FeeMode::UNDERLYING => split_asset_fee(
+                required_amount,
+                exempt_construction_bps(
+                    ctx.remaining_accounts,
+                    &ctx.accounts.prism_etf.key(),
+                    &order_state.orderer,
+                    ctx.accounts
+                        .prism_etf
+                        .construction_bps_for(order_state.amount),
+                )?,
+                ctx.accounts.prism_etf.manager_cut,
+            )?,
+        };
 
-        if amount < required_amount {
+        if delegated_amount
+            < required_amount
+                .checked_add(fee.total())
+                .ok_or(BeamsplitterErrors::MathOverflow)?
+        {
             return Err(BeamsplitterErrors::NotEnoughApproved.into());
         }
 
@@ -336,20 +1063,133 @@ pub mod coherence_beamsplitter {
         // Mark this token as successfully transferred
         transferred_tokens.transferred_tokens[index_usize] = true;
 
-        // Converts to u64, cut's off decimals,
-        let mut required_64 = required_amount
-            .to_u64()
-            .ok_or(BeamsplitterErrors::U64Failure)?;
-
         // This used to round up by default. Ex. if required_amount = 1.5, we must take 2. We always round up 1 even if required amount is 1.0 (becomes 2)
         // This prevents free coheres where required amount could equal 0.2 and gets truncated to 0
-        required_64 += 1;
+        let required_amount = required_amount
+            .checked_add(1)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
 
-        transfer(transfer_ctx, required_64)?;
-        /*order_state.timeout =
-        ctx.accounts.clock.slot + ctx.accounts.beamsplitter.timeout_slots as u64;*/
-        Ok(())
-    }
+        // finalize_order adds the recorded amount to the backing, so it must be what was actually transferred
+        if ctx.accounts.prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            transferred_tokens.amounts[index_usize] = required_amount;
+        }
+
+        transfer(transfer_ctx, required_amount)?;
+
+        if fee.total() > 0 {
+            let fee_vault = &ctx.accounts.fee_vault;
+            let fee_vault_transfer_ata = fee_vault_ata(
+                &ctx.accounts.fee_vault_transfer_ata,
+                &fee_vault.key(),
+                &ctx.accounts.transfer_mint.key(),
+            )?;
+
+            let fee_accounts = Transfer {
+                to: fee_vault_transfer_ata.to_account_info(),
+                authority: ctx.accounts.orderer.to_account_info(),
+                from: ctx.accounts.orderer_transfer_ata.to_account_info(),
+            };
+
+            let fee_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), fee_accounts);
+
+            transfer(fee_ctx, fee.total())?;
+
+            // Only accrued to the vault at finalize, so a decohere before then can refund it
+            transferred_tokens.fees[index_usize] = fee.total();
+        }
+
+        emit!(AssetCohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: required_amount,
+            fee: fee.total(),
+        });
+        /*order_state.timeout =
+        ctx.accounts.clock.slot + ctx.accounts.beamsplitter.timeout_slots as u64;*/
+        Ok(())
+    }
+
+    /*
+    Cohere a nested ETF, constructing the orderer's tokens of it on the way. Used in CONSTRUCTION orders
+
+    The orderer first starts a CONSTRUCTION order of the nested ETF and coheres its assets, with cohere_nested again
+    for ETFs nested deeper. This finalizes that inner order through a CPI into Beamsplitter, minting the nested ETF
+    tokens to the orderer, then coheres them into the outer ETF through another CPI. Each level only ever adds one
+    CPI, however deep the nesting
+
+    Failure cases:
+    - the weighted token at index was not pushed as a nested ETF
+    - anything finalize_order fails on for the inner order (it must be a PENDING CONSTRUCTION order)
+    - anything cohere fails on for the outer order, including the orderer not holding or having delegated enough of
+      the nested ETF after the inner order's fees
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption], passed on to both finalize_order and cohere
+
+    Flow:
+    1. finalize_order the inner order, minting the nested ETF to the orderer
+    2. cohere the nested ETF into the outer order
+    */
+    pub fn cohere_nested(ctx: Context<CohereNested>, index: u16) -> Result<()> {
+        if !ctx.accounts.prism_etf.is_nested(index) {
+            return Err(BeamsplitterErrors::InvalidNesting.into());
+        }
+
+        let program = ctx.accounts.beamsplitter_program.to_account_info();
+
+        let finalize_accounts = crate::cpi::accounts::FinalizeOrder {
+            prism_etf_mint: ctx.accounts.transfer_mint.to_account_info(),
+            orderer: ctx.accounts.orderer.to_account_info(),
+            orderer_etf_ata: ctx.accounts.orderer_transfer_ata.to_account_info(),
+            fee_vault_etf_ata: ctx.accounts.inner_fee_vault_etf_ata.to_account_info(),
+            prism_etf: ctx.accounts.inner_prism_etf.to_account_info(),
+            fee_vault: ctx.accounts.inner_fee_vault.to_account_info(),
+            order_state: ctx.accounts.inner_order_state.to_account_info(),
+            beamsplitter: ctx.accounts.beamsplitter.to_account_info(),
+            weighted_tokens: ctx.accounts.inner_weighted_tokens.to_account_info(),
+            transferred_tokens: ctx.accounts.inner_transferred_tokens.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+
+        let finalize_ctx = CpiContext::new(program.clone(), finalize_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        crate::cpi::finalize_order(finalize_ctx)?;
+
+        let cohere_accounts = crate::cpi::accounts::Cohere {
+            orderer: ctx.accounts.orderer.to_account_info(),
+            prism_etf_mint: ctx.accounts.prism_etf_mint.to_account_info(),
+            transfer_mint: ctx.accounts.transfer_mint.to_account_info(),
+            orderer_transfer_ata: ctx.accounts.orderer_transfer_ata.to_account_info(),
+            beamsplitter_transfer_ata: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+            beamsplitter: ctx.accounts.beamsplitter.to_account_info(),
+            prism_etf: ctx.accounts.prism_etf.to_account_info(),
+            order_state: ctx.accounts.order_state.to_account_info(),
+            fee_vault: ctx.accounts.fee_vault.to_account_info(),
+            fee_vault_transfer_ata: ctx.accounts.fee_vault_transfer_ata.to_account_info(),
+            weighted_tokens: ctx.accounts.weighted_tokens.to_account_info(),
+            transferred_tokens: ctx.accounts.transferred_tokens.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            clock: ctx.accounts.clock.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+
+        let cohere_ctx = CpiContext::new(program, cohere_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        crate::cpi::cohere(cohere_ctx, index)?;
+
+        Ok(())
+    }
 
     /*
     Decohere an asset from the etf being built. Used in DECONSTRUCTION orders
@@ -361,11 +1201,13 @@ pub mod coherence_beamsplitter {
     - prism_etf is not owned by Beamsplitter
     - order_state is not owned by Beamsplitter
     - the amount delegated is below required amount for the etf tokens being created
+    - fee_vault_transfer_ata is not the fee vault's account for the transfer mint (refunding an UNDERLYING fee)
 
     Flow:
     1. Transfer tokens from beamsplitter to user
+    2. if cohere took an UNDERLYING fee for the asset, refund it from the fee vault
     */
-    pub fn decohere(ctx: Context<Cohere>, index: u16) -> Result<()> {
+    pub fn decohere(ctx: Context<Decohere>, index: u16) -> Result<()> {
         let index_usize = index as usize;
         let order_state = &mut ctx.accounts.order_state;
 
@@ -395,202 +1237,1938 @@ pub mod coherence_beamsplitter {
             from: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
         };
 
-        let seeds = &[
-            PRISM_ETF_PDA_SEED,
-            &ctx.accounts.prism_etf_mint.key().to_bytes(),
-            &ctx.accounts.beamsplitter.key().to_bytes(),
-            &[ctx.accounts.prism_etf.bump],
-        ];
-        let signer_seeds = &[&seeds[..]];
+        let seeds = &[
+            PRISM_ETF_PDA_SEED,
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &[ctx.accounts.prism_etf.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = false;
+
+        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
+
+        let single_asset = order_state.swap_mint != Pubkey::default();
+
+        // Taking an asset as is gives up the single asset order's minimum output
+        if single_asset && order_state.order_type == OrderType::DECONSTRUCTION {
+            order_state.min_amount_out = 0;
+        }
+
+        // We need to account for the decimals of both the etf and the output
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            // Unwinding a single asset CONSTRUCTION returns exactly what its swap received
+            _ if single_asset && order_state.order_type == OrderType::CONSTRUCTION => {
+                transferred_tokens.amounts[index_usize]
+            }
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                ctx.accounts.transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        transfer(transfer_ctx, required_amount)?;
+
+        // The fee is only accrued at finalize, so an asset taken back before then gets its fee back too
+        let fee = transferred_tokens.fees[index_usize];
+        if fee > 0 {
+            transferred_tokens.fees[index_usize] = 0;
+
+            let fee_vault = &ctx.accounts.fee_vault;
+            let fee_vault_transfer_ata = fee_vault_ata(
+                &ctx.accounts.fee_vault_transfer_ata,
+                &fee_vault.key(),
+                &ctx.accounts.transfer_mint.key(),
+            )?;
+
+            let refund_accounts = Transfer {
+                to: ctx.accounts.orderer_transfer_ata.to_account_info(),
+                authority: fee_vault.to_account_info(),
+                from: fee_vault_transfer_ata.to_account_info(),
+            };
+
+            let prism_etf_key = ctx.accounts.prism_etf.key();
+            let fee_vault_seeds = &[
+                FEE_VAULT_PDA_SEED,
+                prism_etf_key.as_ref(),
+                &[fee_vault.bump],
+            ];
+            let fee_vault_signer_seeds = &[&fee_vault_seeds[..]];
+
+            let refund_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                refund_accounts,
+                fee_vault_signer_seeds,
+            );
+
+            transfer(refund_ctx, fee)?;
+        }
+
+        emit!(AssetDecohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: required_amount,
+            fee,
+        });
+
+        /*order_state.timeout =
+        ctx.accounts.clock.slot + ctx.accounts.beamsplitter.timeout_slots as u64;*/
+
+        Ok(())
+    }
+
+    /*
+    Cohere native SOL into the etf being built, wrapping the orderer's lamports straight into the ETF's wSOL account.
+    Used in CONSTRUCTION orders of ETFs holding wSOL, instead of cohere
+
+    Failure cases:
+    - the weighted token at index is not wSOL
+    - order_state.status != PENDING, or the order is a single asset order
+    - the orderer has fewer lamports than required (plus the fee in UNDERLYING fee mode)
+    - fee_vault_transfer_ata is not the fee vault's wSOL account (UNDERLYING fee mode)
+    - the first remaining account is not the orderer's [FeeExemption] for the ETF
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption] for the ETF, lowering the fee charged in UNDERLYING fee mode
+
+    Flow:
+    1. Transfer the required lamports to the ETF's wSOL account and sync it
+    2. if UNDERLYING fee mode, wrap the fee into the fee vault's wSOL account
+    */
+    pub fn cohere_native(ctx: Context<CohereNative>, index: u16) -> Result<()> {
+        let order_state = &mut ctx.accounts.order_state;
+        let index_usize = index as usize;
+
+        if order_state.status != OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        // Single asset orders are filled by swap_cohere
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        if transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
+
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                ctx.accounts.transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        let fee = match ctx.accounts.prism_etf.fee_mode {
+            FeeMode::DILUTION => AssetFee::default(),
+            FeeMode::UNDERLYING => split_asset_fee(
+                required_amount,
+                exempt_construction_bps(
+                    ctx.remaining_accounts,
+                    &ctx.accounts.prism_etf.key(),
+                    &order_state.orderer,
+                    ctx.accounts
+                        .prism_etf
+                        .construction_bps_for(order_state.amount),
+                )?,
+                ctx.accounts.prism_etf.manager_cut,
+            )?,
+        };
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = true;
+
+        // Rounds up like cohere, so no cohere is ever free
+        let required_amount = required_amount
+            .checked_add(1)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+
+        // Recorded for finalize_order's backing like in cohere
+        if ctx.accounts.prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            transferred_tokens.amounts[index_usize] = required_amount;
+        }
+
+        wrap_lamports(
+            &ctx.accounts.orderer.to_account_info(),
+            &ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+            required_amount,
+        )?;
+
+        if fee.total() > 0 {
+            let fee_vault = &ctx.accounts.fee_vault;
+            let fee_vault_transfer_ata = fee_vault_ata(
+                &ctx.accounts.fee_vault_transfer_ata,
+                &fee_vault.key(),
+                &ctx.accounts.transfer_mint.key(),
+            )?;
+
+            wrap_lamports(
+                &ctx.accounts.orderer.to_account_info(),
+                &fee_vault_transfer_ata.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.token_program.to_account_info(),
+                fee.total(),
+            )?;
+
+            // Only accrued to the vault at finalize, so a decohere before then can refund it
+            transferred_tokens.fees[index_usize] = fee.total();
+        }
+
+        emit!(AssetCohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: required_amount,
+            fee: fee.total(),
+        });
+        Ok(())
+    }
+
+    /*
+    Decohere wSOL from the etf as native SOL, so the orderer does not need a wSOL account. Used in DECONSTRUCTION
+    orders and to unwind CONSTRUCTION orders of ETFs holding wSOL, instead of decohere
+
+    Failure cases:
+    - the weighted token at index is not wSOL
+    - order_state.status != PENDING, or the order is a single asset order
+    - fee_vault_transfer_ata is not the fee vault's wSOL account (refunding an UNDERLYING fee)
+
+    Flow:
+    1. Transfer the wSOL to a token account of the order state, created for this instruction, along with any
+       UNDERLYING fee cohere_native took for it from the fee vault
+    2. Close that account to the orderer, unwrapping the wSOL and returning its rent
+    */
+    pub fn decohere_native(ctx: Context<DecohereNative>, index: u16) -> Result<()> {
+        let index_usize = index as usize;
+        let order_state = &ctx.accounts.order_state;
+
+        if order_state.status != OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        // Single asset orders keep their own wSOL account for the order state, and are unwound by decohere
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        // Already decohered
+        if !transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
+
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                ctx.accounts.transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = false;
+
+        let transfer_accounts = Transfer {
+            to: ctx.accounts.order_native_ata.to_account_info(),
+            authority: ctx.accounts.prism_etf.to_account_info(),
+            from: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+        };
+
+        let prism_etf_seeds = &[
+            PRISM_ETF_PDA_SEED,
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &[ctx.accounts.prism_etf.bump],
+        ];
+        let prism_etf_signer_seeds = &[&prism_etf_seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            prism_etf_signer_seeds,
+        );
+
+        transfer(transfer_ctx, required_amount)?;
+
+        // The fee is only accrued at finalize, so an asset taken back before then gets its fee back too
+        let fee = transferred_tokens.fees[index_usize];
+        if fee > 0 {
+            transferred_tokens.fees[index_usize] = 0;
+
+            let fee_vault = &ctx.accounts.fee_vault;
+            let fee_vault_transfer_ata = fee_vault_ata(
+                &ctx.accounts.fee_vault_transfer_ata,
+                &fee_vault.key(),
+                &ctx.accounts.transfer_mint.key(),
+            )?;
+
+            let refund_accounts = Transfer {
+                to: ctx.accounts.order_native_ata.to_account_info(),
+                authority: fee_vault.to_account_info(),
+                from: fee_vault_transfer_ata.to_account_info(),
+            };
+
+            let prism_etf_key = ctx.accounts.prism_etf.key();
+            let fee_vault_seeds = &[
+                FEE_VAULT_PDA_SEED,
+                prism_etf_key.as_ref(),
+                &[fee_vault.bump],
+            ];
+            let fee_vault_signer_seeds = &[&fee_vault_seeds[..]];
+
+            let refund_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                refund_accounts,
+                fee_vault_signer_seeds,
+            );
+
+            transfer(refund_ctx, fee)?;
+        }
+
+        let close_accounts = CloseAccount {
+            account: ctx.accounts.order_native_ata.to_account_info(),
+            destination: ctx.accounts.orderer.to_account_info(),
+            authority: order_state.to_account_info(),
+        };
+
+        let id_bytes = (order_state.id as u64).to_le_bytes();
+        let order_state_seeds = &[
+            ORDER_STATE_PDA_SEED,
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.orderer.key().to_bytes(),
+            &id_bytes,
+            &[order_state.bump],
+        ];
+        let order_state_signer_seeds = &[&order_state_seeds[..]];
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            order_state_signer_seeds,
+        );
+
+        close_account(close_ctx)?;
+
+        emit!(AssetDecohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: required_amount,
+            fee,
+        });
+        Ok(())
+    }
+
+    /*
+    Cohere a Token-2022 asset into the etf being built. Used in CONSTRUCTION orders of ETFs holding Token-2022 assets,
+    instead of cohere
+
+    Failure cases:
+    - the weighted token at index is not transfer_mint, or transfer_mint is not a Token-2022 mint
+    - order_state.status != PENDING, or the order is a single asset order
+    - the ETF is in UNDERLYING fee mode
+    - orderer_transfer_ata is not the orderer's account of transfer_mint, or beamsplitter_transfer_ata is not the ETF's
+      associated token account of it
+    - the orderer holds less than the required amount plus the mint's transfer fee on it
+
+    Flow:
+    1. Transfer with transfer_checked, enough that the ETF is credited the required amount once Token-2022 withholds
+       the mint's transfer fee in the ETF's account
+    2. Check what the ETF's account was credited, which never includes the withheld fee
+    */
+    pub fn cohere_token_2022(ctx: Context<CohereToken2022>, index: u16) -> Result<()> {
+        let order_state = &ctx.accounts.order_state;
+        let index_usize = index as usize;
+
+        if order_state.status != OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        // Single asset orders are filled by swap_cohere
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        // UNDERLYING fees accrue in the fee vault, which can only withdraw SPL Token assets
+        if ctx.accounts.prism_etf.fee_mode != FeeMode::DILUTION {
+            return Err(BeamsplitterErrors::UnsupportedFeeMode.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        if transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        let transfer_mint = load_mint(&ctx.accounts.transfer_mint)?;
+        token_amount(
+            &ctx.accounts.orderer_transfer_ata,
+            &transfer_mint,
+            &ctx.accounts.orderer.key(),
+        )?;
+        let backing_before = associated_token_amount(
+            &ctx.accounts.beamsplitter_transfer_ata,
+            &transfer_mint,
+            &ctx.accounts.prism_etf.key(),
+        )?;
+
+        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
+
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = true;
+
+        // Rounds up like cohere, so no cohere is ever free
+        let required_amount = required_amount
+            .checked_add(1)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+
+        let transfer_fee = transfer_mint.transfer_fee(ctx.accounts.clock.epoch);
+        transfer_checked(
+            &ctx.accounts.token_program,
+            &ctx.accounts.orderer_transfer_ata,
+            &ctx.accounts.transfer_mint,
+            &ctx.accounts.beamsplitter_transfer_ata,
+            &ctx.accounts.orderer.to_account_info(),
+            transfer_fee.gross_amount(required_amount)?,
+            transfer_mint.decimals,
+            &[],
+        )?;
+
+        // Only the credit backs the order. The withheld fee stays apart from the amount until the mint's withdraw
+        // authority harvests it
+        let credited = token_amount(
+            &ctx.accounts.beamsplitter_transfer_ata,
+            &transfer_mint,
+            &ctx.accounts.prism_etf.key(),
+        )?
+        .checked_sub(backing_before)
+        .ok_or(BeamsplitterErrors::MathOverflow)?;
+        if credited < required_amount {
+            return Err(BeamsplitterErrors::TransferFeeExceeded.into());
+        }
+
+        // Recorded for finalize_order's backing like in cohere
+        if ctx.accounts.prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            transferred_tokens.amounts[index_usize] = credited;
+        }
+
+        emit!(AssetCohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: credited,
+            fee: 0,
+        });
+        Ok(())
+    }
+
+    /*
+    Decohere a Token-2022 asset from the etf. Used in DECONSTRUCTION orders and to unwind CONSTRUCTION orders of ETFs
+    holding Token-2022 assets, instead of decohere
+
+    Failure cases:
+    - the weighted token at index is not transfer_mint, or transfer_mint is not a Token-2022 mint
+    - order_state.status != PENDING, or the order is a single asset order
+    - orderer_transfer_ata is not the orderer's account of transfer_mint, or beamsplitter_transfer_ata is not the ETF's
+      associated token account of it
+
+    Flow:
+    1. Transfer the asset with transfer_checked. Token-2022 withholds the mint's transfer fee in the orderer's account,
+       so the orderer is credited that much less than the ETF sends
+    */
+    pub fn decohere_token_2022(ctx: Context<DecohereToken2022>, index: u16) -> Result<()> {
+        let index_usize = index as usize;
+        let order_state = &ctx.accounts.order_state;
+
+        if order_state.status != OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        // Single asset orders only ever hold SPL Token assets, and are unwound by decohere
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        // Already decohered
+        if !transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        let transfer_mint = load_mint(&ctx.accounts.transfer_mint)?;
+        token_amount(
+            &ctx.accounts.orderer_transfer_ata,
+            &transfer_mint,
+            &ctx.accounts.orderer.key(),
+        )?;
+        associated_token_amount(
+            &ctx.accounts.beamsplitter_transfer_ata,
+            &transfer_mint,
+            &ctx.accounts.prism_etf.key(),
+        )?;
+
+        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
+
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_token.weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = false;
+
+        let prism_etf_seeds = &[
+            PRISM_ETF_PDA_SEED,
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &[ctx.accounts.prism_etf.bump],
+        ];
+
+        transfer_checked(
+            &ctx.accounts.token_program,
+            &ctx.accounts.beamsplitter_transfer_ata,
+            &ctx.accounts.transfer_mint,
+            &ctx.accounts.orderer_transfer_ata,
+            &ctx.accounts.prism_etf.to_account_info(),
+            required_amount,
+            transfer_mint.decimals,
+            &[&prism_etf_seeds[..]],
+        )?;
+
+        emit!(AssetDecohered {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount: required_amount,
+            fee: 0,
+        });
+        Ok(())
+    }
+
+    /*
+    Finalize a Prism ETF CONSTRUCTION or DECONSTRUCTION order
+
+    Failure cases:
+    - prism_etf.weighted_tokens_at != weighted_tokens.key()
+    - order_state.status = CANCELLED || SUCCEEDED (at instruction start)
+    - prism_etf is not owned by Beamsplitter
+    - order_state is not owned by Beamsplitter
+    - the amount of etf tokens being constructed or deconstructed is invalid
+    - the first remaining account is not the orderer's [FeeExemption] for the ETF
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption] for the ETF, lowering the fee charged in DILUTION fee mode
+
+    Flow:
+    1. Set order_state.status = SUCCEEDED
+    2. if order_state.type == CONSTRUCTION, mint order_state.amount of tokens, with the fees going to the fee vault
+       (in UNDERLYING fee mode the fees were taken by cohere and the orderer receives the full amount)
+    */
+    pub fn finalize_order(ctx: Context<FinalizeOrder>) -> Result<()> {
+        let order_state = &mut ctx.accounts.order_state;
+
+        if order_state.status != OrderStatus::PENDING {
+            return Err(ProgramError::InvalidArgument.into());
+        }
+
+        if order_state.swap_mint != Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let transferred_tokens = &ctx.accounts.transferred_tokens.load()?;
+        let transferred_tokens_index = transferred_tokens.length as usize;
+
+        // If all transferred tokens are false (0) than we can finalize knowing all assets were removed (or never added)
+        if !transferred_tokens.transferred_tokens[0] {
+            for transferred_token in
+                transferred_tokens.transferred_tokens[..transferred_tokens_index].iter()
+            {
+                if *transferred_token {
+                    return Err(BeamsplitterErrors::StillPending.into());
+                }
+            }
+            order_state.status = OrderStatus::SUCCEEDED;
+
+            // Every asset left the ETF, which completes a DECONSTRUCTION and unwinds a CONSTRUCTION
+            if order_state.order_type == OrderType::DECONSTRUCTION {
+                emit!(OrderFinalized {
+                    prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+                    orderer: order_state.orderer,
+                    order_state: order_state.key(),
+                    order_type: order_state.order_type,
+                    amount: order_state.amount,
+                    orderer_amount: 0,
+                    owner_fee: 0,
+                    manager_fee: 0,
+                });
+            } else {
+                emit!(OrderCancelled {
+                    prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+                    orderer: order_state.orderer,
+                    order_state: order_state.key(),
+                    order_type: order_state.order_type,
+                    amount: order_state.amount,
+                    orderer_amount: 0,
+                    owner_fee: 0,
+                    manager_fee: 0,
+                });
+            }
+            return Ok(());
+        }
+
+        // Otherwise we need to make sure all were transferred to Beamsplitter (or were never removed)
+        for transferred_token in
+            transferred_tokens.transferred_tokens[..transferred_tokens_index].iter()
+        {
+            if !*transferred_token {
+                return Err(BeamsplitterErrors::StillPending.into());
+            }
+        }
+
+        // The transferred assets now back the supply being minted
+        if ctx.accounts.prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            let weighted_tokens = &mut ctx.accounts.weighted_tokens.load_mut()?;
+            for idx in 0..transferred_tokens_index {
+                weighted_tokens.backing[idx] = weighted_tokens.backing[idx]
+                    .checked_add(transferred_tokens.amounts[idx])
+                    .ok_or(BeamsplitterErrors::MathOverflow)?;
+            }
+        }
+
+        // Split the minted tokens between orderer, owner and manager
+        let fee_split = match ctx.accounts.prism_etf.fee_mode {
+            FeeMode::DILUTION => split_construction_fee(
+                order_state.amount,
+                exempt_construction_bps(
+                    ctx.remaining_accounts,
+                    &ctx.accounts.prism_etf.key(),
+                    &order_state.orderer,
+                    ctx.accounts
+                        .prism_etf
+                        .construction_bps_for(order_state.amount),
+                )?,
+                ctx.accounts.prism_etf.manager_cut,
+            )?,
+            // Fees were already charged in the underlying assets
+            FeeMode::UNDERLYING => FeeSplit {
+                orderer: order_state.amount,
+                owner: 0,
+                manager: 0,
+            },
+        };
+
+        // Mint tokens to the orderer
+        let mint_accounts_orderer = MintTo {
+            mint: ctx.accounts.prism_etf_mint.to_account_info(),
+            to: ctx.accounts.orderer_etf_ata.to_account_info(),
+            authority: ctx.accounts.beamsplitter.to_account_info(),
+        };
+
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let mint_ctx_orderer = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts_orderer,
+            signer_seeds,
+        );
+
+        mint_to(mint_ctx_orderer, fee_split.orderer)?;
+
+        // Mint the owner's and manager's fees to the fee vault, where they accrue until withdrawn
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.protocol_fees = fee_vault
+            .protocol_fees
+            .checked_add(fee_split.owner)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+        fee_vault.manager_fees = fee_vault
+            .manager_fees
+            .checked_add(fee_split.manager)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+
+        // UNDERLYING fees coheres took are already in the vault, and are only owed now the order can't be unwound
+        fee_vault.accrue_asset_fees(
+            &transferred_tokens.fees[..transferred_tokens_index],
+            ctx.accounts.prism_etf.manager_cut,
+        )?;
+
+        let mint_accounts_fees = MintTo {
+            mint: ctx.accounts.prism_etf_mint.to_account_info(),
+            to: ctx.accounts.fee_vault_etf_ata.to_account_info(),
+            authority: ctx.accounts.beamsplitter.to_account_info(),
+        };
+
+        let mint_ctx_fees = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts_fees,
+            signer_seeds,
+        );
+
+        mint_to(mint_ctx_fees, fee_split.owner + fee_split.manager)?;
+
+        order_state.status = OrderStatus::SUCCEEDED;
+
+        // Every asset is in the ETF, which completes a CONSTRUCTION and unwinds a DECONSTRUCTION
+        if order_state.order_type == OrderType::CONSTRUCTION {
+            emit!(OrderFinalized {
+                prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+                orderer: order_state.orderer,
+                order_state: order_state.key(),
+                order_type: order_state.order_type,
+                amount: order_state.amount,
+                orderer_amount: fee_split.orderer,
+                owner_fee: fee_split.owner,
+                manager_fee: fee_split.manager,
+            });
+        } else {
+            emit!(OrderCancelled {
+                prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+                orderer: order_state.orderer,
+                order_state: order_state.key(),
+                order_type: order_state.order_type,
+                amount: order_state.amount,
+                orderer_amount: fee_split.orderer,
+                owner_fee: fee_split.owner,
+                manager_fee: fee_split.manager,
+            });
+        }
+
+        Ok(())
+    }
+
+    /*
+    Start a CONSTRUCTION order paid in a single asset. The input is held by the order state and swapped into each
+    weighted token with swap_cohere, the etf amount minted is whatever the swaps back (at least min_amount_out)
+
+    Failure cases:
+    - new orders are paused
+    - prism_etf is not FINISHED or not in QUANTITY mode
+    - order_state.status = PENDING
+    - no swap program has been allowed by the Beamsplitter owner
+    - input_amount is 0
+    - the ETF is permissioned and the first remaining account is not the orderer's [AllowlistEntry]
+
+    Remaining accounts:
+    - the orderer's [AllowlistEntry] if the ETF is permissioned
+
+    Flow:
+    1. Set order_state.status = PENDING, order_state.swap_mint = swap_mint
+    2. Transfer input_amount of swap_mint from the orderer to the order state's ATA
+    */
+    pub fn start_single_asset_order(
+        ctx: Context<StartSingleAssetOrder>,
+        input_amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        if ctx.accounts.beamsplitter.paused {
+            return Err(BeamsplitterErrors::Paused.into());
+        }
+
+        let order_state = &mut ctx.accounts.order_state;
+        let prism_etf = &ctx.accounts.prism_etf;
+
+        if prism_etf.status != PrismEtfStatus::FINISHED {
+            return Err(BeamsplitterErrors::PrismEtfNotFinished.into());
+        }
+
+        // Percentage amounts depend on prices at order time, swaps can't be checked against them
+        if prism_etf.weight_mode != WeightMode::QUANTITY {
+            return Err(BeamsplitterErrors::UnsupportedWeightMode.into());
+        }
+
+        if ctx.accounts.beamsplitter.swap_program == Pubkey::default() {
+            return Err(BeamsplitterErrors::SwapProgramNotAllowed.into());
+        }
+
+        if order_state.status == OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        if input_amount == 0 {
+            return Err(BeamsplitterErrors::ZeroOrder.into());
+        }
+
+        allowlisted(
+            ctx.remaining_accounts,
+            prism_etf,
+            &ctx.accounts.orderer.key(),
+        )?;
+
+        // Narrowed down by each swap to the etf amount every asset so far can back
+        order_state.amount = u64::MAX;
+        order_state.order_type = OrderType::CONSTRUCTION;
+        order_state.status = OrderStatus::PENDING;
+        order_state.swap_mint = ctx.accounts.swap_mint.key();
+        order_state.min_amount_out = min_amount_out;
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+        transferred_tokens.length = weighted_tokens.length;
+
+        let transferred_tokens_length = transferred_tokens.length as usize;
+        for idx in 0..transferred_tokens_length {
+            transferred_tokens.transferred_tokens[idx] = false;
+            transferred_tokens.amounts[idx] = 0;
+            transferred_tokens.fees[idx] = 0;
+        }
+
+        let transfer_accounts = Transfer {
+            to: ctx.accounts.order_swap_ata.to_account_info(),
+            authority: ctx.accounts.orderer.to_account_info(),
+            from: ctx.accounts.orderer_swap_ata.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+        );
+
+        transfer(transfer_ctx, input_amount)?;
+
+        emit!(SingleAssetOrderStarted {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            order_type: OrderType::CONSTRUCTION,
+            swap_mint: order_state.swap_mint,
+            amount: input_amount,
+            min_amount_out,
+        });
+
+        Ok(())
+    }
+
+    /*
+    Swap part of a single asset order's input into one weighted token, straight into the ETF's ATA. The swap
+    instruction is built from the remaining accounts and `data`, with the order state signing for its input ATA
+
+    Failure cases:
+    - order_state is not a PENDING single asset order
+    - swap_program is not the one allowed by the Beamsplitter
+    - the swap spends more than amount_in of the input
+    - the first remaining account is a [FeeExemption] other than the orderer's for the ETF
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption] for the ETF, lowering the fee charged in UNDERLYING fee mode
+    - the accounts of the swap instruction
+
+    Flow:
+    1. Swap (or transfer, if the weighted token is the input) up to amount_in of input into the ETF's ATA
+    2. if UNDERLYING fee mode, move the fee on what was received to the fee vault
+    3. Record what was received and lower order_state.amount to the etf amount it backs
+    */
+    pub fn swap_cohere(
+        ctx: Context<SwapCohere>,
+        index: u16,
+        amount_in: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let order_state = &mut ctx.accounts.order_state;
+        let index_usize = index as usize;
+
+        if order_state.status != OrderStatus::PENDING
+            || order_state.order_type != OrderType::CONSTRUCTION
+        {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        if order_state.swap_mint == Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        if transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        let (fee_exemption, swap_accounts) = split_fee_exemption(ctx.remaining_accounts);
+
+        let input_before = ctx.accounts.order_swap_ata.amount;
+        let output_before = ctx.accounts.beamsplitter_transfer_ata.amount;
+
+        let id_bytes = (order_state.id as u64).to_le_bytes();
+        let seeds = &[
+            ORDER_STATE_PDA_SEED,
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.orderer.key().to_bytes(),
+            &id_bytes,
+            &[order_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        if ctx.accounts.transfer_mint.key() == order_state.swap_mint {
+            let transfer_accounts = Transfer {
+                to: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+                authority: order_state.to_account_info(),
+                from: ctx.accounts.order_swap_ata.to_account_info(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer(transfer_ctx, amount_in)?;
+        } else {
+            invoke_swap(
+                &ctx.accounts.swap_program,
+                swap_accounts,
+                data,
+                &order_state.key(),
+                signer_seeds,
+            )?;
+        }
+
+        ctx.accounts.order_swap_ata.reload()?;
+        ctx.accounts.beamsplitter_transfer_ata.reload()?;
+
+        let amount_spent = input_before
+            .checked_sub(ctx.accounts.order_swap_ata.amount)
+            .ok_or(BeamsplitterErrors::SwapInputExceeded)?;
+        if amount_spent > amount_in {
+            return Err(BeamsplitterErrors::SwapInputExceeded.into());
+        }
+
+        let amount_received = ctx
+            .accounts
+            .beamsplitter_transfer_ata
+            .amount
+            .checked_sub(output_before)
+            .ok_or(BeamsplitterErrors::PotentialUnderflow)?;
+
+        let weight = weighted_tokens.weighted_tokens[index_usize].weight;
+        let fee = match ctx.accounts.prism_etf.fee_mode {
+            FeeMode::DILUTION => AssetFee::default(),
+            FeeMode::UNDERLYING => {
+                // The minted amount is only known once every swap is done, so the fee tier is the one reached by the
+                // etf amount this swap cohered
+                let cohered = backed_amount(
+                    amount_received,
+                    weight,
+                    ctx.accounts.prism_etf_mint.decimals,
+                    ctx.accounts.transfer_mint.decimals,
+                )?
+                .min(order_state.amount);
+                split_asset_fee(
+                    amount_received,
+                    exempt_construction_bps(
+                        fee_exemption,
+                        &ctx.accounts.prism_etf.key(),
+                        &order_state.orderer,
+                        ctx.accounts.prism_etf.construction_bps_for(cohered),
+                    )?,
+                    ctx.accounts.prism_etf.manager_cut,
+                )?
+            }
+        };
+
+        if fee.total() > 0 {
+            let fee_vault = &ctx.accounts.fee_vault;
+            let fee_vault_transfer_ata = fee_vault_ata(
+                &ctx.accounts.fee_vault_transfer_ata,
+                &fee_vault.key(),
+                &ctx.accounts.transfer_mint.key(),
+            )?;
+
+            let fee_accounts = Transfer {
+                to: fee_vault_transfer_ata.to_account_info(),
+                authority: ctx.accounts.prism_etf.to_account_info(),
+                from: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+            };
+
+            let prism_etf_seeds = &[
+                PRISM_ETF_PDA_SEED,
+                &ctx.accounts.prism_etf_mint.key().to_bytes(),
+                &ctx.accounts.beamsplitter.key().to_bytes(),
+                &[ctx.accounts.prism_etf.bump],
+            ];
+            let prism_etf_signer_seeds = &[&prism_etf_seeds[..]];
+
+            let fee_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                fee_accounts,
+                prism_etf_signer_seeds,
+            );
+
+            transfer(fee_ctx, fee.total())?;
+
+            // Only accrued to the vault at finalize, so a decohere before then can refund it
+            transferred_tokens.fees[index_usize] = fee.total();
+        }
+
+        // Only what stays in the ETF backs the order
+        let amount_received = amount_received
+            .checked_sub(fee.total())
+            .ok_or(BeamsplitterErrors::PotentialUnderflow)?;
+
+        let backed = backed_amount(
+            amount_received,
+            weight,
+            ctx.accounts.prism_etf_mint.decimals,
+            ctx.accounts.transfer_mint.decimals,
+        )?;
+        order_state.amount = order_state.amount.min(backed);
+
+        // Mark this token as successfully transferred
+        transferred_tokens.transferred_tokens[index_usize] = true;
+        transferred_tokens.amounts[index_usize] = amount_received;
+
+        emit!(AssetSwapped {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            swap_mint: order_state.swap_mint,
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount_in: amount_spent,
+            amount_out: amount_received,
+        });
+
+        Ok(())
+    }
+
+    /*
+    Finalize a single asset CONSTRUCTION order. Any input left unswapped is refunded and the order's ATA closed.
+    Output swapped beyond what the minted amount needs stays with the ETF, so swaps should target the quoted amounts
+
+    Failure cases:
+    - order_state is not a PENDING single asset order
+    - only some weighted tokens were swapped (decohere them to cancel instead)
+    - the etf amount the swaps back is below order_state.min_amount_out
+    - the etf amount the swaps back breaks the ETF's order limits or supply cap
+    - the first remaining account is not the orderer's [FeeExemption] for the ETF
+
+    Remaining accounts:
+    - optionally the orderer's [FeeExemption] for the ETF, lowering the fee charged in DILUTION fee mode
+
+    Flow:
+    1. Refund the remaining input and close the order's ATA
+    2. if every weighted token was swapped, mint order_state.amount to the orderer, with the fees going to the fee vault
+    3. Set order_state.status = SUCCEEDED
+    */
+    pub fn finalize_single_asset_order(ctx: Context<FinalizeSingleAssetOrder>) -> Result<()> {
+        let order_state = &mut ctx.accounts.order_state;
+
+        if order_state.status != OrderStatus::PENDING
+            || order_state.order_type != OrderType::CONSTRUCTION
+        {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        if order_state.swap_mint == Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let transferred_tokens = &ctx.accounts.transferred_tokens.load()?;
+        let transferred_tokens_length = transferred_tokens.length as usize;
+        let swapped = transferred_tokens.transferred_tokens[..transferred_tokens_length]
+            .iter()
+            .filter(|transferred_token| **transferred_token)
+            .count();
+
+        let cancelled = swapped == 0;
+        if !cancelled && swapped != transferred_tokens_length {
+            return Err(BeamsplitterErrors::StillPending.into());
+        }
+
+        if !cancelled {
+            if order_state.amount < order_state.min_amount_out {
+                return Err(BeamsplitterErrors::MinAmountOutNotMet.into());
+            }
+            if order_state.amount == 0 {
+                return Err(BeamsplitterErrors::ZeroOrder.into());
+            }
+
+            // The minted amount is only known once every swap is done, so the limits are checked here
+            ctx.accounts.prism_etf.check_order_limits(
+                OrderType::CONSTRUCTION,
+                order_state.amount,
+                ctx.accounts.prism_etf_mint.supply,
+            )?;
+        }
+
+        let id_bytes = (order_state.id as u64).to_le_bytes();
+        let seeds = &[
+            ORDER_STATE_PDA_SEED,
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.orderer.key().to_bytes(),
+            &id_bytes,
+            &[order_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // Refund whatever input was not swapped
+        let refund = ctx.accounts.order_swap_ata.amount;
+        if refund > 0 {
+            let transfer_accounts = Transfer {
+                to: ctx.accounts.orderer_swap_ata.to_account_info(),
+                authority: order_state.to_account_info(),
+                from: ctx.accounts.order_swap_ata.to_account_info(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer(transfer_ctx, refund)?;
+        }
+
+        let close_token_account = CloseAccount {
+            account: ctx.accounts.order_swap_ata.to_account_info(),
+            destination: ctx.accounts.orderer.to_account_info(),
+            authority: order_state.to_account_info(),
+        };
+
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_token_account,
+            signer_seeds,
+        );
+        close_account(close_ctx)?;
+
+        order_state.status = OrderStatus::SUCCEEDED;
+
+        if cancelled {
+            emit!(OrderCancelled {
+                prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+                orderer: order_state.orderer,
+                order_state: order_state.key(),
+                order_type: order_state.order_type,
+                amount: 0,
+                orderer_amount: 0,
+                owner_fee: 0,
+                manager_fee: 0,
+            });
+            return Ok(());
+        }
+
+        // Split the minted tokens between orderer, owner and manager
+        let fee_split = match ctx.accounts.prism_etf.fee_mode {
+            FeeMode::DILUTION => split_construction_fee(
+                order_state.amount,
+                exempt_construction_bps(
+                    ctx.remaining_accounts,
+                    &ctx.accounts.prism_etf.key(),
+                    &order_state.orderer,
+                    ctx.accounts
+                        .prism_etf
+                        .construction_bps_for(order_state.amount),
+                )?,
+                ctx.accounts.prism_etf.manager_cut,
+            )?,
+            // Fees were already charged in the underlying assets
+            FeeMode::UNDERLYING => FeeSplit {
+                orderer: order_state.amount,
+                owner: 0,
+                manager: 0,
+            },
+        };
+
+        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        // Mint the owner's and manager's fees to the fee vault, where they accrue until withdrawn
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.protocol_fees = fee_vault
+            .protocol_fees
+            .checked_add(fee_split.owner)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+        fee_vault.manager_fees = fee_vault
+            .manager_fees
+            .checked_add(fee_split.manager)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+
+        // UNDERLYING fees swap_cohere took are only owed now the order can't be unwound
+        fee_vault.accrue_asset_fees(
+            &transferred_tokens.fees[..transferred_tokens_length],
+            ctx.accounts.prism_etf.manager_cut,
+        )?;
+
+        for (to, mint_amount) in [
+            (&ctx.accounts.orderer_etf_ata, fee_split.orderer),
+            (
+                &ctx.accounts.fee_vault_etf_ata,
+                fee_split.owner + fee_split.manager,
+            ),
+        ] {
+            let mint_accounts = MintTo {
+                mint: ctx.accounts.prism_etf_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: ctx.accounts.beamsplitter.to_account_info(),
+            };
+
+            let mint_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                mint_accounts,
+                signer_seeds,
+            );
+
+            mint_to(mint_ctx, mint_amount)?;
+        }
+
+        emit!(OrderFinalized {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            order_type: order_state.order_type,
+            amount: order_state.amount,
+            orderer_amount: fee_split.orderer,
+            owner_fee: fee_split.owner,
+            manager_fee: fee_split.manager,
+        });
+
+        Ok(())
+    }
+
+    /*
+    Start a DECONSTRUCTION order paid out in a single asset. Each weighted token is decohered into a vault owned by
+    the order state and swapped into swap_mint with swap_decohere
+
+    Failure cases:
+    - new orders are paused
+    - prism_etf is not FINISHED
+    - order_state.status = PENDING
+    - no swap program has been allowed by the Beamsplitter owner
+    - amount is 0 or above the ETF's maximum order amount
+
+    Flow:
+    1. Set order_state.status = PENDING, order_state.swap_mint = swap_mint
+    2. if PERCENTAGE mode, snapshot each asset's pro rata share into transferred_tokens.amounts
+    3. Burn <amount> of etf tokens
+    */
+    pub fn start_single_asset_redemption(
+        ctx: Context<StartSingleAssetRedemption>,
+        amount: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        if ctx.accounts.beamsplitter.paused {
+            return Err(BeamsplitterErrors::Paused.into());
+        }
+
+        let order_state = &mut ctx.accounts.order_state;
+        let prism_etf = &ctx.accounts.prism_etf;
+        let prism_etf_mint = &ctx.accounts.prism_etf_mint;
+
+        if prism_etf.status != PrismEtfStatus::FINISHED {
+            return Err(BeamsplitterErrors::PrismEtfNotFinished.into());
+        }
+
+        if ctx.accounts.beamsplitter.swap_program == Pubkey::default() {
+            return Err(BeamsplitterErrors::SwapProgramNotAllowed.into());
+        }
+
+        if order_state.status == OrderStatus::PENDING {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        if amount == 0 {
+            return Err(BeamsplitterErrors::ZeroOrder.into());
+        }
+
+        prism_etf.check_order_limits(OrderType::DECONSTRUCTION, amount, prism_etf_mint.supply)?;
+
+        order_state.amount = amount;
+        order_state.order_type = OrderType::DECONSTRUCTION;
+        order_state.status = OrderStatus::PENDING;
+        order_state.swap_mint = ctx.accounts.swap_mint.key();
+        order_state.min_amount_out = min_amount_out;
+
+        let weighted_tokens = &mut ctx.accounts.weighted_tokens.load_mut()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+        transferred_tokens.length = weighted_tokens.length;
+
+        // Set all all switches to transferred
+        let transferred_tokens_length = transferred_tokens.length as usize;
+        for transferred_token in
+            transferred_tokens.transferred_tokens[..transferred_tokens_length].iter_mut()
+        {
+            *transferred_token = true;
+        }
+
+        // No UNDERLYING fee has been taken for this order
+        for fee in transferred_tokens.fees[..transferred_tokens_length].iter_mut() {
+            *fee = 0;
+        }
+
+        // Pay out a pro rata share of the backing, which then no longer backs any supply
+        if prism_etf.weight_mode == WeightMode::PERCENTAGE {
+            let amounts =
+                percentage_deconstruction_amounts(weighted_tokens, amount, prism_etf_mint.supply)?;
+
+            for (idx, &share) in amounts.iter().enumerate() {
+                transferred_tokens.amounts[idx] = share;
+                weighted_tokens.backing[idx] = weighted_tokens.backing[idx]
+                    .checked_sub(share)
+                    .ok_or(BeamsplitterErrors::PotentialUnderflow)?;
+            }
+        }
+
+        let burn_accounts = Burn {
+            mint: prism_etf_mint.to_account_info(),
+            to: ctx.accounts.orderer_etf_ata.to_account_info(),
+            authority: ctx.accounts.orderer.to_account_info(),
+        };
+
+        let burn_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_accounts);
+
+        burn(burn_ctx, amount)?;
+
+        emit!(SingleAssetOrderStarted {
+            prism_etf_mint: prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            order_type: OrderType::DECONSTRUCTION,
+            swap_mint: order_state.swap_mint,
+            amount,
+            min_amount_out,
+        });
+
+        Ok(())
+    }
+
+    /*
+    Decohere one weighted token of a single asset DECONSTRUCTION into the order's vault and swap it into swap_mint.
+    Anything the swap leaves behind is refunded to the orderer. A weighted token can instead be taken as is with
+    decohere, which gives up the order's min_amount_out
+
+    Failure cases:
+    - order_state is not a PENDING single asset DECONSTRUCTION
+    - swap_program is not the one allowed by the Beamsplitter
+    - this is the last weighted token and the order's total output is below order_state.min_amount_out
+
+    Flow:
+    1. Transfer the weighted token's share from the ETF to the order's vault
+    2. Swap (or transfer, if the weighted token is swap_mint) it into the order's swap_mint ATA
+    3. Refund what is left in the vault to the orderer and close the vault
+    */
+    pub fn swap_decohere(ctx: Context<SwapDecohere>, index: u16, data: Vec<u8>) -> Result<()> {
+        let order_state = &mut ctx.accounts.order_state;
+        let index_usize = index as usize;
+
+        if order_state.status != OrderStatus::PENDING
+            || order_state.order_type != OrderType::DECONSTRUCTION
+        {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
+        }
+
+        if order_state.swap_mint == Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
+        }
+
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let transferred_tokens = &mut ctx.accounts.transferred_tokens.load_mut()?;
+
+        if index >= weighted_tokens.length {
+            return Err(BeamsplitterErrors::IndexPassedBound.into());
+        }
+
+        // Already decohered
+        if !transferred_tokens.transferred_tokens[index_usize] {
+            return Ok(());
+        }
+
+        if weighted_tokens.weighted_tokens[index_usize].mint != ctx.accounts.transfer_mint.key() {
+            return Err(BeamsplitterErrors::WrongIndexMint.into());
+        }
+
+        // We need to account for the decimals of both the etf and the output
+        let required_amount = match ctx.accounts.prism_etf.weight_mode {
+            WeightMode::QUANTITY => weighted_amount(
+                order_state.amount,
+                weighted_tokens.weighted_tokens[index_usize].weight,
+                ctx.accounts.prism_etf_mint.decimals,
+                ctx.accounts.transfer_mint.decimals,
+            )?,
+            WeightMode::PERCENTAGE => transferred_tokens.amounts[index_usize],
+        };
+
+        let prism_etf_seeds = &[
+            PRISM_ETF_PDA_SEED,
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &[ctx.accounts.prism_etf.bump],
+        ];
+        let prism_etf_signer_seeds = &[&prism_etf_seeds[..]];
+
+        let transfer_accounts = Transfer {
+            to: ctx.accounts.order_vault.to_account_info(),
+            authority: ctx.accounts.prism_etf.to_account_info(),
+            from: ctx.accounts.beamsplitter_transfer_ata.to_account_info(),
+        };
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            prism_etf_signer_seeds,
+        );
+
+        transfer(transfer_ctx, required_amount)?;
+
+        let output_before = ctx.accounts.order_swap_ata.amount;
+
+        let id_bytes = (order_state.id as u64).to_le_bytes();
+        let seeds = &[
+            ORDER_STATE_PDA_SEED,
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.orderer.key().to_bytes(),
+            &id_bytes,
+            &[order_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        if ctx.accounts.transfer_mint.key() == order_state.swap_mint {
+            let transfer_accounts = Transfer {
+                to: ctx.accounts.order_swap_ata.to_account_info(),
+                authority: order_state.to_account_info(),
+                from: ctx.accounts.order_vault.to_account_info(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer(transfer_ctx, required_amount)?;
+        } else {
+            invoke_swap(
+                &ctx.accounts.swap_program,
+                ctx.remaining_accounts,
+                data,
+                &order_state.key(),
+                signer_seeds,
+            )?;
+        }
+
+        ctx.accounts.order_vault.reload()?;
+        ctx.accounts.order_swap_ata.reload()?;
+
+        let amount_received = ctx
+            .accounts
+            .order_swap_ata
+            .amount
+            .checked_sub(output_before)
+            .ok_or(BeamsplitterErrors::PotentialUnderflow)?;
+
+        // Refund whatever the swap did not take
+        let refund = ctx.accounts.order_vault.amount;
+        if refund > 0 {
+            let transfer_accounts = Transfer {
+                to: ctx.accounts.orderer_transfer_ata.to_account_info(),
+                authority: order_state.to_account_info(),
+                from: ctx.accounts.order_vault.to_account_info(),
+            };
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+
+            transfer(transfer_ctx, refund)?;
+        }
 
-        let transfer_ctx = CpiContext::new_with_signer(
+        let close_token_account = CloseAccount {
+            account: ctx.accounts.order_vault.to_account_info(),
+            destination: ctx.accounts.orderer.to_account_info(),
+            authority: order_state.to_account_info(),
+        };
+
+        let close_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            transfer_accounts,
+            close_token_account,
             signer_seeds,
         );
+        close_account(close_ctx)?;
 
         // Mark this token as successfully transferred
         transferred_tokens.transferred_tokens[index_usize] = false;
 
-        let weighted_token = &weighted_tokens.weighted_tokens[index_usize];
-        let required_amount = &mut Decimal::from(order_state.amount);
-        let weight = &mut Decimal::from(weighted_token.weight);
-        let prism_etf_decimals = ctx.accounts.prism_etf_mint.decimals;
-
-        required_amount.mul_assign(*weight);
-
-        // We need to account for the decimals of the input
-        match required_amount.set_scale(prism_etf_decimals.into()) {
-            Err(_error) => return Err(BeamsplitterErrors::ScaleFailure.into()),
-            _ => (),
+        // The last swap is where the order's total output is known
+        let transferred_tokens_length = transferred_tokens.length as usize;
+        let finished = transferred_tokens.transferred_tokens[..transferred_tokens_length]
+            .iter()
+            .all(|transferred_token| !*transferred_token);
+        if finished && ctx.accounts.order_swap_ata.amount < order_state.min_amount_out {
+            return Err(BeamsplitterErrors::MinAmountOutNotMet.into());
         }
 
-        transfer(
-            transfer_ctx,
-            required_amount
-                .to_u64()
-                .ok_or(BeamsplitterErrors::U64Failure)?,
-        )?;
-
-        /*order_state.timeout =
-        ctx.accounts.clock.slot + ctx.accounts.beamsplitter.timeout_slots as u64;*/
+        emit!(AssetSwapped {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            swap_mint: order_state.swap_mint,
+            mint: ctx.accounts.transfer_mint.key(),
+            index,
+            amount_in: required_amount.saturating_sub(refund),
+            amount_out: amount_received,
+        });
 
         Ok(())
     }
 
     /*
-    Finalize a Prism ETF CONSTRUCTION or DECONSTRUCTION order
+    Finalize a single asset DECONSTRUCTION order, paying out everything swapped into swap_mint
 
     Failure cases:
-    - prism_etf.weighted_tokens_at != weighted_tokens.key()
-    - order_state.status = CANCELLED || SUCCEEDED (at instruction start)
-    - prism_etf is not owned by Beamsplitter
-    - order_state is not owned by Beamsplitter
-    - the amount of etf tokens being constructed or deconstructed is invalid
+    - order_state is not a PENDING single asset DECONSTRUCTION
+    - a weighted token has not been decohered yet
 
     Flow:
-    1. Set order_state.status = SUCCEEDED
-    2. if order_state.type == CONSTRUCTION, mint order_state.amount of tokens
+    1. Transfer the order's swap_mint to the orderer and close the order's ATA
+    2. Set order_state.status = SUCCEEDED
     */
-    pub fn finalize_order(ctx: Context<FinalizeOrder>) -> Result<()> {
+    pub fn finalize_single_asset_redemption(
+        ctx: Context<FinalizeSingleAssetRedemption>,
+    ) -> Result<()> {
         let order_state = &mut ctx.accounts.order_state;
 
-        if order_state.status != OrderStatus::PENDING {
-            return Err(ProgramError::InvalidArgument.into());
+        if order_state.status != OrderStatus::PENDING
+            || order_state.order_type != OrderType::DECONSTRUCTION
+        {
+            return Err(BeamsplitterErrors::IncorrectOrderStatus.into());
         }
 
-        let transferred_tokens = &ctx.accounts.transferred_tokens.load()?;
-        let transferred_tokens_index = transferred_tokens.length as usize;
-
-        // If all transferred tokens are false (0) than we can finalize knowing all assets were removed (or never added)
-        if !transferred_tokens.transferred_tokens[0] {
-            for transferred_token in
-                transferred_tokens.transferred_tokens[..transferred_tokens_index].iter()
-            {
-                if *transferred_token {
-                    return Err(BeamsplitterErrors::StillPending.into());
-                }
-            }
-            order_state.status = OrderStatus::SUCCEEDED;
-            return Ok(());
+        if order_state.swap_mint == Pubkey::default() {
+            return Err(BeamsplitterErrors::WrongOrderFlow.into());
         }
 
-        // Otherwise we need to make sure all were transferred to Beamsplitter (or were never removed)
+        let transferred_tokens = &ctx.accounts.transferred_tokens.load()?;
+        let transferred_tokens_length = transferred_tokens.length as usize;
         for transferred_token in
-            transferred_tokens.transferred_tokens[..transferred_tokens_index].iter()
+            transferred_tokens.transferred_tokens[..transferred_tokens_length].iter()
         {
-            if !*transferred_token {
+            if *transferred_token {
                 return Err(BeamsplitterErrors::StillPending.into());
             }
         }
 
-        let amount = order_state.amount;
-        let mut mint_amount = Decimal::from(amount);
+        let id_bytes = (order_state.id as u64).to_le_bytes();
+        let seeds = &[
+            ORDER_STATE_PDA_SEED,
+            &ctx.accounts.beamsplitter.key().to_bytes(),
+            &ctx.accounts.prism_etf_mint.key().to_bytes(),
+            &ctx.accounts.orderer.key().to_bytes(),
+            &id_bytes,
+            &[order_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
 
-        // The amount of tokens for manager and program owner
-        let mut fee_portion =
-            Decimal::from(ctx.accounts.prism_etf.construction_bps).mul(mint_amount);
+        let amount_out = ctx.accounts.order_swap_ata.amount;
+        if amount_out > 0 {
+            let transfer_accounts = Transfer {
+                to: ctx.accounts.orderer_swap_ata.to_account_info(),
+                authority: order_state.to_account_info(),
+                from: ctx.accounts.order_swap_ata.to_account_info(),
+            };
 
-        // Need to adjust scale after multiplying
-        match fee_portion.set_scale(BASIS_POINT_DECIMALS.into()) {
-            Err(_error) => return Err(BeamsplitterErrors::ScaleFailure.into()),
-            _ => (),
-        }
+            let transfer_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
 
-        // Owner gets at least 1 minimum unit of etf
-        if fee_portion < Decimal::from(2u8) {
-            fee_portion = Decimal::from(2u8);
+            transfer(transfer_ctx, amount_out)?;
         }
 
-        // The amount just for manager
-        let mut manager_portion =
-            Decimal::from(ctx.accounts.prism_etf.manager_cut).mul(fee_portion);
+        let close_token_account = CloseAccount {
+            account: ctx.accounts.order_swap_ata.to_account_info(),
+            destination: ctx.accounts.orderer.to_account_info(),
+            authority: order_state.to_account_info(),
+        };
 
-        match manager_portion.set_scale((2 * BASIS_POINT_DECIMALS).into()) {
-            Err(_error) => return Err(BeamsplitterErrors::ScaleFailure.into()),
-            _ => (),
-        }
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_token_account,
+            signer_seeds,
+        );
+        close_account(close_ctx)?;
 
-        // Manager gets at least 1 minimum unit of etf
-        if manager_portion < Decimal::from(1u8) {
-            fee_portion = Decimal::from(1u8);
-        }
+        order_state.status = OrderStatus::SUCCEEDED;
 
-        if mint_amount <= fee_portion {
-            return err!(BeamsplitterErrors::PotentialUnderflow);
+        emit!(OrderFinalized {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: order_state.orderer,
+            order_state: order_state.key(),
+            order_type: order_state.order_type,
+            amount: order_state.amount,
+            orderer_amount: 0,
+            owner_fee: 0,
+            manager_fee: 0,
+        });
+
+        Ok(())
+    }
+
+    /*
+    Quote a CONSTRUCTION or DECONSTRUCTION order without changing any state. The [OrderQuote] is
+    returned through return data so other programs can CPI into this and clients can simulate it
+
+    Remaining accounts:
+    - QUANTITY: each weighted token's mint in index order, optionally followed by a ([PriceFeed], feed) pair
+      per weighted token to compute NAV
+    - PERCENTAGE: a ([PriceFeed], feed) pair per weighted token in index order
+    */
+    pub fn quote_order(ctx: Context<QuoteOrder>, order_type: OrderType, amount: u64) -> Result<()> {
+        let prism_etf = &ctx.accounts.prism_etf;
+        let prism_etf_mint = &ctx.accounts.prism_etf_mint;
+        let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+        let now = ctx.accounts.clock.unix_timestamp;
+        let length = weighted_tokens.length as usize;
+
+        if amount == 0 {
+            return Err(BeamsplitterErrors::ZeroOrder.into());
         }
 
-        // Subtract out the construction fee from orderer amount
-        mint_amount.sub_assign(fee_portion);
+        let (nav, mut amounts) = match prism_etf.weight_mode {
+            WeightMode::QUANTITY => {
+                if ctx.remaining_accounts.len() < length {
+                    return Err(BeamsplitterErrors::IndexPassedBound.into());
+                }
+
+                let mut amounts = Vec::with_capacity(length);
+                for idx in 0..length {
+                    let weighted_token = weighted_tokens.weighted_tokens[idx];
+                    let mint = load_mint(&ctx.remaining_accounts[idx])?;
+                    if mint.key != weighted_token.mint {
+                        return Err(BeamsplitterErrors::WrongIndexMint.into());
+                    }
+                    amounts.push(weighted_amount(
+                        amount,
+                        weighted_token.weight,
+                        prism_etf_mint.decimals,
+                        mint.decimals,
+                    )?);
+                }
 
-        // Subtract out the manager portion from fee portion
-        fee_portion.sub_assign(manager_portion);
+                let price_accounts = &ctx.remaining_accounts[length..];
+                let nav = if price_accounts.is_empty() {
+                    None
+                } else {
+                    let prices = load_prices(price_accounts, weighted_tokens, now)?;
+                    Some(quantity_nav(weighted_tokens, &prices)?)
+                };
 
-        let mut mint_amount_u64 = mint_amount.to_u64().ok_or(BeamsplitterErrors::U64Failure)?;
-        let fee_portion_u64 = fee_portion.to_u64().ok_or(BeamsplitterErrors::U64Failure)?;
-        let manager_portion_u64 = manager_portion
-            .to_u64()
-            .ok_or(BeamsplitterErrors::U64Failure)?;
+                (nav, amounts)
+            }
+            WeightMode::PERCENTAGE => {
+                let prices = load_prices(ctx.remaining_accounts, weighted_tokens, now)?;
+                let nav = percentage_nav(
+                    weighted_tokens,
+                    &prices,
+                    prism_etf_mint.supply,
+                    prism_etf.initial_nav,
+                    prism_etf_mint.decimals,
+                )?;
+
+                let amounts = match order_type {
+                    OrderType::CONSTRUCTION => percentage_construction_amounts(
+                        weighted_tokens,
+                        &prices,
+                        amount,
+                        nav,
+                        prism_etf_mint.decimals,
+                    )?,
+                    OrderType::DECONSTRUCTION => percentage_deconstruction_amounts(
+                        weighted_tokens,
+                        amount,
+                        prism_etf_mint.supply,
+                    )?,
+                };
+
+                (Some(nav), amounts)
+            }
+        };
 
-        // Add back any lost to rounding
-        mint_amount_u64 += amount - (mint_amount_u64 + fee_portion_u64 + manager_portion_u64);
+        let nav = nav.map(to_u64).transpose()?;
+
+        let quote = match order_type {
+            OrderType::CONSTRUCTION => {
+                // Cohere always takes one extra minimum unit of each asset, plus the fee in UNDERLYING fee mode
+                for required in amounts.iter_mut() {
+                    let fee = match prism_etf.fee_mode {
+                        FeeMode::DILUTION => AssetFee::default(),
+                        FeeMode::UNDERLYING => split_asset_fee(
+                            *required,
+                            prism_etf.construction_bps_for(amount),
+                            prism_etf.manager_cut,
+                        )?,
+                    };
+                    *required = required
+                        .checked_add(fee.total())
+                        .and_then(|required| required.checked_add(1))
+                        .ok_or(BeamsplitterErrors::MathOverflow)?;
+                }
 
-        // Mint tokens to the orderer
-        let mint_accounts_orderer = MintTo {
-            mint: ctx.accounts.prism_etf_mint.to_account_info(),
-            to: ctx.accounts.orderer_etf_ata.to_account_info(),
-            authority: ctx.accounts.beamsplitter.to_account_info(),
+                let fee_split = match prism_etf.fee_mode {
+                    FeeMode::DILUTION => split_construction_fee(
+                        amount,
+                        prism_etf.construction_bps_for(amount),
+                        prism_etf.manager_cut,
+                    )?,
+                    FeeMode::UNDERLYING => FeeSplit {
+                        orderer: amount,
+                        owner: 0,
+                        manager: 0,
+                    },
+                };
+
+                OrderQuote {
+                    nav,
+                    amounts,
+                    orderer_amount: fee_split.orderer,
+                    owner_fee: fee_split.owner,
+                    manager_fee: fee_split.manager,
+                }
+            }
+            OrderType::DECONSTRUCTION => OrderQuote {
+                nav,
+                amounts,
+                orderer_amount: amount,
+                owner_fee: 0,
+                manager_fee: 0,
+            },
         };
 
-        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
-        let signer_seeds = &[&seeds[..]];
+        let quote_data = quote
+            .try_to_vec()
+            .map_err(|error| ProgramError::BorshIoError(error.to_string()))?;
+        set_return_data(&quote_data);
 
-        let mint_ctx_orderer = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            mint_accounts_orderer,
-            signer_seeds,
-        );
+        Ok(())
+    }
+
+    /// Creates the vault an ETF's fees accrue in, required before its orders can be finalized
+    pub fn init_fee_vault(ctx: Context<InitFeeVault>, bump: u8) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        fee_vault.prism_etf = ctx.accounts.prism_etf.key();
+        fee_vault.protocol_fees = 0;
+        fee_vault.manager_fees = 0;
+        fee_vault.bump = bump;
+        fee_vault.protocol_asset_fees = [0; state::MAX_WEIGHTED_TOKENS];
+        fee_vault.manager_asset_fees = [0; state::MAX_WEIGHTED_TOKENS];
+
+        emit!(FeeVaultCreated {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            fee_vault: fee_vault.key(),
+        });
+        Ok(())
+    }
 
-        mint_to(mint_ctx_orderer, mint_amount_u64)?;
+    /// Withdraws `amount` of the protocol's accrued fees in `fee_mint`, the etf or one of its weighted tokens
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        let fee_mint = ctx.accounts.fee_mint.key();
 
-        // Mint tokens to Program owner
-        let mint_accounts_owner = MintTo {
-            mint: ctx.accounts.prism_etf_mint.to_account_info(),
-            to: ctx.accounts.owner_etf_ata.to_account_info(),
-            authority: ctx.accounts.beamsplitter.to_account_info(),
+        // Etf fees accrue from DILUTION fee mode, asset fees from UNDERLYING fee mode
+        let accrued = if fee_mint == ctx.accounts.prism_etf_mint.key() {
+            &mut fee_vault.protocol_fees
+        } else {
+            let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+            // push_tokens rejects duplicate mints, so this is the only index the fee can have accrued at
+            let index = weighted_tokens.weighted_tokens[..weighted_tokens.length as usize]
+                .iter()
+                .position(|weighted_token| weighted_token.mint == fee_mint)
+                .ok_or(BeamsplitterErrors::WrongIndexMint)?;
+            &mut fee_vault.protocol_asset_fees[index]
         };
 
-        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        *accrued = accrued
+            .checked_sub(amount)
+            .ok_or(BeamsplitterErrors::InsufficientFees)?;
+        let remaining = *accrued;
+
+        let transfer_accounts = Transfer {
+            to: ctx.accounts.destination.to_account_info(),
+            authority: fee_vault.to_account_info(),
+            from: ctx.accounts.fee_vault_ata.to_account_info(),
+        };
+
+        let prism_etf_key = ctx.accounts.prism_etf.key();
+        let seeds = &[
+            FEE_VAULT_PDA_SEED,
+            prism_etf_key.as_ref(),
+            &[fee_vault.bump],
+        ];
         let signer_seeds = &[&seeds[..]];
 
-        let mint_ctx_owner = CpiContext::new_with_signer(
+        let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            mint_accounts_owner,
+            transfer_accounts,
             signer_seeds,
         );
 
-        mint_to(mint_ctx_owner, fee_portion_u64)?;
+        transfer(transfer_ctx, amount)?;
 
-        // Mint tokens to Manager of ETF
-        let mint_accounts_manager = MintTo {
-            mint: ctx.accounts.prism_etf_mint.to_account_info(),
-            to: ctx.accounts.manager_etf_ata.to_account_info(),
-            authority: ctx.accounts.beamsplitter.to_account_info(),
+        emit!(ProtocolFeesWithdrawn {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            owner: ctx.accounts.owner.key(),
+            mint: fee_mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining,
+        });
+        Ok(())
+    }
+
+    /// Withdraws `amount` of the manager's accrued fees in `fee_mint`, the etf or one of its weighted tokens
+    pub fn withdraw_manager_fees(ctx: Context<WithdrawManagerFees>, amount: u64) -> Result<()> {
+        let fee_vault = &mut ctx.accounts.fee_vault;
+        let fee_mint = ctx.accounts.fee_mint.key();
+
+        // Etf fees accrue from DILUTION fee mode, asset fees from UNDERLYING fee mode
+        let accrued = if fee_mint == ctx.accounts.prism_etf_mint.key() {
+            &mut fee_vault.manager_fees
+        } else {
+            let weighted_tokens = &ctx.accounts.weighted_tokens.load()?;
+            // push_tokens rejects duplicate mints, so this is the only index the fee can have accrued at
+            let index = weighted_tokens.weighted_tokens[..weighted_tokens.length as usize]
+                .iter()
+                .position(|weighted_token| weighted_token.mint == fee_mint)
+                .ok_or(BeamsplitterErrors::WrongIndexMint)?;
+            &mut fee_vault.manager_asset_fees[index]
         };
 
-        let seeds = &[BEAMSPLITTER_PDA_SEED, &[ctx.accounts.beamsplitter.bump]];
+        *accrued = accrued
+            .checked_sub(amount)
+            .ok_or(BeamsplitterErrors::InsufficientFees)?;
+        let remaining = *accrued;
+
+        let transfer_accounts = Transfer {
+            to: ctx.accounts.destination.to_account_info(),
+            authority: fee_vault.to_account_info(),
+            from: ctx.accounts.fee_vault_ata.to_account_info(),
+        };
+
+        let prism_etf_key = ctx.accounts.prism_etf.key();
+        let seeds = &[
+            FEE_VAULT_PDA_SEED,
+            prism_etf_key.as_ref(),
+            &[fee_vault.bump],
+        ];
         let signer_seeds = &[&seeds[..]];
 
-        let mint_ctx_manager = CpiContext::new_with_signer(
+        let transfer_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            mint_accounts_manager,
+            transfer_accounts,
             signer_seeds,
         );
 
-        mint_to(mint_ctx_manager, manager_portion_u64)?;
-
-        order_state.status = OrderStatus::SUCCEEDED;
+        transfer(transfer_ctx, amount)?;
 
+        emit!(ManagerFeesWithdrawn {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            mint: fee_mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining,
+        });
         Ok(())
     }
 
@@ -600,6 +3178,8 @@ pub mod coherence_beamsplitter {
         }
         ctx.accounts.prism_etf.status = PrismEtfStatus::CLOSED;
 
+        let mut crumbs = 0;
+
         let seeds = &[
             PRISM_ETF_PDA_SEED,
             &ctx.accounts.prism_etf_mint.key().to_bytes(),
@@ -621,6 +3201,7 @@ pub mod coherence_beamsplitter {
                 signer_seeds,
             );
             transfer(transfer_ctx, ctx.accounts.prism_asset_ata.amount)?;
+            crumbs = ctx.accounts.prism_asset_ata.amount;
         }
 
         let close_token_account = CloseAccount {
@@ -635,6 +3216,14 @@ pub mod coherence_beamsplitter {
             signer_seeds,
         );
         close_account(close_ctx)?;
+
+        emit!(PrismAtaClosed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            prism_asset_ata: ctx.accounts.prism_asset_ata.key(),
+            destination: ctx.accounts.dest_asset_ata.key(),
+            crumbs,
+        });
         Ok(())
     }
 
@@ -642,15 +3231,41 @@ pub mod coherence_beamsplitter {
         if ctx.accounts.prism_etf_mint.supply != 0 {
             return Err(BeamsplitterErrors::NonZeroSupply.into());
         }
+
+        emit!(PrismEtfClosed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+        });
         Ok(())
     }
 
-    pub fn close_order_state(_ctx: Context<CloseOrderState>) -> Result<()> {
+    pub fn close_order_state(ctx: Context<CloseOrderState>) -> Result<()> {
+        emit!(OrderStateClosed {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            orderer: ctx.accounts.orderer.key(),
+            order_state: ctx.accounts.order_state.key(),
+        });
         Ok(())
     }
 
     pub fn set_owner(ctx: Context<SetOwner>) -> Result<()> {
         ctx.accounts.beamsplitter.owner = ctx.accounts.new_owner.key();
+
+        emit!(OwnerChanged {
+            old_owner: ctx.accounts.owner.key(),
+            new_owner: ctx.accounts.new_owner.key(),
+        });
+        Ok(())
+    }
+
+    /// Allows single asset orders to swap through `swap_program`
+    pub fn set_swap_program(ctx: Context<SetSwapProgram>) -> Result<()> {
+        ctx.accounts.beamsplitter.swap_program = ctx.accounts.swap_program.key();
+
+        emit!(SwapProgramChanged {
+            authority: ctx.accounts.authority.key(),
+            swap_program: ctx.accounts.swap_program.key(),
+        });
         Ok(())
     }
 
@@ -659,6 +3274,14 @@ pub mod coherence_beamsplitter {
         new_default_manager_cut: u16,
     ) -> Result<()> {
         ctx.accounts.beamsplitter.default_manager_cut = new_default_manager_cut;
+
+        let beamsplitter = &ctx.accounts.beamsplitter;
+        emit!(DefaultFeesChanged {
+            authority: ctx.accounts.authority.key(),
+            default_construction_bps: beamsplitter.default_construction_bps,
+            default_deconstruction_bps: beamsplitter.default_deconstruction_bps,
+            default_manager_cut: beamsplitter.default_manager_cut,
+        });
         Ok(())
     }
 
@@ -667,6 +3290,14 @@ pub mod coherence_beamsplitter {
         new_construction_bps: u16,
     ) -> Result<()> {
         ctx.accounts.beamsplitter.default_construction_bps = new_construction_bps;
+
+        let beamsplitter = &ctx.accounts.beamsplitter;
+        emit!(DefaultFeesChanged {
+            authority: ctx.accounts.authority.key(),
+            default_construction_bps: beamsplitter.default_construction_bps,
+            default_deconstruction_bps: beamsplitter.default_deconstruction_bps,
+            default_manager_cut: beamsplitter.default_manager_cut,
+        });
         Ok(())
     }
 
@@ -675,35 +3306,299 @@ pub mod coherence_beamsplitter {
         new_deconstruction_bps: u16,
     ) -> Result<()> {
         ctx.accounts.beamsplitter.default_deconstruction_bps = new_deconstruction_bps;
+
+        let beamsplitter = &ctx.accounts.beamsplitter;
+        emit!(DefaultFeesChanged {
+            authority: ctx.accounts.authority.key(),
+            default_construction_bps: beamsplitter.default_construction_bps,
+            default_deconstruction_bps: beamsplitter.default_deconstruction_bps,
+            default_manager_cut: beamsplitter.default_manager_cut,
+        });
         Ok(())
     }
 
+    /// Queues a new manager, which takes over once the pending changes are applied
     pub fn set_manager(ctx: Context<SetManager>) -> Result<()> {
-        ctx.accounts.prism_etf.manager = ctx.accounts.new_manager.key();
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        prism_etf.pending_manager_changes.manager = Some(ctx.accounts.new_manager.key());
+
+        prism_etf.queue_manager_changes(
+            ctx.accounts.clock.unix_timestamp,
+            ctx.accounts.beamsplitter.change_delay,
+        );
+
+        emit!(PrismEtfManagerChangesQueued {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            pending_manager_changes: prism_etf.pending_manager_changes,
+        });
         Ok(())
     }
 
+    /// Queues a new manager cut, which takes effect once the pending changes are applied
     pub fn set_manager_cut(
         ctx: Context<SetManagerCut>,
         new_default_manager_cut: u16,
     ) -> Result<()> {
-        ctx.accounts.prism_etf.manager_cut = new_default_manager_cut;
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        prism_etf.pending_manager_changes.manager_cut = Some(new_default_manager_cut);
+
+        prism_etf.queue_manager_changes(
+            ctx.accounts.clock.unix_timestamp,
+            ctx.accounts.beamsplitter.change_delay,
+        );
+
+        emit!(PrismEtfManagerChangesQueued {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            pending_manager_changes: prism_etf.pending_manager_changes,
+        });
         Ok(())
     }
 
+    /// Queues a new construction fee, which takes effect once the pending changes are applied
     pub fn set_construction_bps(
         ctx: Context<SetConstruction>,
         new_construction_bps: u16,
     ) -> Result<()> {
-        ctx.accounts.prism_etf.construction_bps = new_construction_bps;
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        // Fee tiers may only discount the construction fee
+        if !valid_fee_tiers(prism_etf.next_fee_tiers(), new_construction_bps) {
+            return Err(BeamsplitterErrors::InvalidFeeTiers.into());
+        }
+
+        prism_etf.pending_fee_changes.construction_bps = Some(new_construction_bps);
+
+        prism_etf.queue_fee_changes(
+            ctx.accounts.clock.unix_timestamp,
+            ctx.accounts.beamsplitter.change_delay,
+        );
+
+        emit!(PrismEtfFeeChangesQueued {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            pending_fee_changes: prism_etf.pending_fee_changes,
+        });
+        Ok(())
+    }
+
+    /// Queues new volume fee tiers for an ETF, orders reaching a tier's min_amount are charged its construction fee
+    /// once the pending changes are applied
+    pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        let construction_bps = prism_etf
+            .pending_fee_changes
+            .construction_bps
+            .unwrap_or(prism_etf.construction_bps);
+        if fee_tiers.len() > state::MAX_FEE_TIERS || !valid_fee_tiers(&fee_tiers, construction_bps)
+        {
+            return Err(BeamsplitterErrors::InvalidFeeTiers.into());
+        }
+
+        let pending_fee_changes = &mut prism_etf.pending_fee_changes;
+        pending_fee_changes.fee_tiers = [FeeTier::default(); state::MAX_FEE_TIERS];
+        pending_fee_changes.fee_tiers[..fee_tiers.len()].copy_from_slice(&fee_tiers);
+        pending_fee_changes.fee_tiers_length = Some(fee_tiers.len() as u8);
+
+        prism_etf.queue_fee_changes(
+            ctx.accounts.clock.unix_timestamp,
+            ctx.accounts.beamsplitter.change_delay,
+        );
+
+        emit!(PrismEtfFeeChangesQueued {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            pending_fee_changes: prism_etf.pending_fee_changes,
+        });
         Ok(())
     }
 
+    /// Queues a new deconstruction fee, which takes effect once the pending changes are applied
     pub fn set_deconstruction_bps(
         ctx: Context<SetDeconstruction>,
         new_deconstruction_bps: u16,
     ) -> Result<()> {
-        ctx.accounts.prism_etf.deconstruction_bps = new_deconstruction_bps;
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        prism_etf.pending_fee_changes.deconstruction_bps = Some(new_deconstruction_bps);
+
+        prism_etf.queue_fee_changes(
+            ctx.accounts.clock.unix_timestamp,
+            ctx.accounts.beamsplitter.change_delay,
+        );
+
+        emit!(PrismEtfFeeChangesQueued {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            pending_fee_changes: prism_etf.pending_fee_changes,
+        });
+        Ok(())
+    }
+
+    /// Applies an ETF's queued fee and manager changes whose delay has passed, anyone may crank this
+    pub fn apply_pending_changes(ctx: Context<ApplyPendingChanges>) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+        let now = ctx.accounts.clock.unix_timestamp;
+
+        if prism_etf.pending_fee_changes.is_empty() && prism_etf.pending_manager_changes.is_empty()
+        {
+            return Err(BeamsplitterErrors::NoPendingChanges.into());
+        }
+
+        // The owner's and the manager's changes wait out their own delays
+        let fee_changes_effective = !prism_etf.pending_fee_changes.is_empty()
+            && now >= prism_etf.pending_fee_changes.effective_at;
+        let manager_changes_effective = !prism_etf.pending_manager_changes.is_empty()
+            && now >= prism_etf.pending_manager_changes.effective_at;
+
+        if !fee_changes_effective && !manager_changes_effective {
+            return Err(BeamsplitterErrors::ChangesNotEffective.into());
+        }
+
+        let applied_fee_changes = if fee_changes_effective {
+            prism_etf.apply_fee_changes()
+        } else {
+            PendingFeeChanges::default()
+        };
+        let applied_manager_changes = if manager_changes_effective {
+            prism_etf.apply_manager_changes()
+        } else {
+            PendingManagerChanges::default()
+        };
+
+        // Fee tiers and the construction fee are checked against each other when queued, this guards the result
+        if !valid_fee_tiers(
+            &prism_etf.fee_tiers[..prism_etf.fee_tiers_length as usize],
+            prism_etf.construction_bps,
+        ) {
+            return Err(BeamsplitterErrors::InvalidFeeTiers.into());
+        }
+
+        emit!(PrismEtfChangesApplied {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            applied_fee_changes,
+            applied_manager_changes,
+            construction_bps: prism_etf.construction_bps,
+            deconstruction_bps: prism_etf.deconstruction_bps,
+            manager_cut: prism_etf.manager_cut,
+            manager: prism_etf.manager,
+        });
+        Ok(())
+    }
+
+    /// Drops an ETF's queued fee changes, only the Beamsplitter owner or its fee admin may cancel them
+    pub fn cancel_pending_fee_changes(ctx: Context<CancelPendingFeeChanges>) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.pending_fee_changes.is_empty() {
+            return Err(BeamsplitterErrors::NoPendingChanges.into());
+        }
+
+        let cancelled_fee_changes = std::mem::take(&mut prism_etf.pending_fee_changes);
+
+        emit!(PrismEtfFeeChangesCancelled {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            authority: ctx.accounts.authority.key(),
+            cancelled_fee_changes,
+        });
+        Ok(())
+    }
+
+    /// Drops an ETF's queued manager changes, only the manager may cancel them
+    pub fn cancel_pending_manager_changes(ctx: Context<CancelPendingManagerChanges>) -> Result<()> {
+        let prism_etf = &mut ctx.accounts.prism_etf;
+
+        if prism_etf.pending_manager_changes.is_empty() {
+            return Err(BeamsplitterErrors::NoPendingChanges.into());
+        }
+
+        let cancelled_manager_changes = std::mem::take(&mut prism_etf.pending_manager_changes);
+
+        emit!(PrismEtfManagerChangesCancelled {
+            prism_etf_mint: ctx.accounts.prism_etf_mint.key(),
+            manager: ctx.accounts.manager.key(),
+            cancelled_manager_changes,
+        });
+        Ok(())
+    }
+
+    /// Gives `grantee` a role, replacing its previous holder
+    pub fn grant_role(ctx: Context<GrantRole>, role: Role) -> Result<()> {
+        *ctx.accounts.beamsplitter.role_holder_mut(role) = ctx.accounts.grantee.key();
+
+        emit!(RoleGranted {
+            owner: ctx.accounts.owner.key(),
+            role,
+            grantee: ctx.accounts.grantee.key(),
+        });
+        Ok(())
+    }
+
+    /// Takes a role away from its holder, leaving it to the owner alone
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: Role) -> Result<()> {
+        let role_holder = ctx.accounts.beamsplitter.role_holder_mut(role);
+        let revoked = *role_holder;
+        *role_holder = Pubkey::default();
+
+        emit!(RoleRevoked {
+            owner: ctx.accounts.owner.key(),
+            role,
+            revoked,
+        });
+        Ok(())
+    }
+
+    /// Stops (or resumes) new orders from being started, orders already pending can still be finished
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.beamsplitter.paused = paused;
+
+        emit!(PausedChanged {
+            authority: ctx.accounts.authority.key(),
+            paused,
+        });
+        Ok(())
+    }
+
+    /// Queues how many seconds fee and manager changes of finished ETFs are queued for. The new delay waits out the
+    /// current one, so it can't be used to rush changes through
+    pub fn set_change_delay(ctx: Context<SetChangeDelay>, change_delay: u32) -> Result<()> {
+        let beamsplitter = &mut ctx.accounts.beamsplitter;
+        beamsplitter.pending_change_delay = Some(change_delay);
+        beamsplitter.change_delay_effective_at = ctx
+            .accounts
+            .clock
+            .unix_timestamp
+            .saturating_add(beamsplitter.change_delay.into());
+
+        emit!(ChangeDelayQueued {
+            owner: ctx.accounts.owner.key(),
+            change_delay,
+            effective_at: beamsplitter.change_delay_effective_at,
+        });
+        Ok(())
+    }
+
+    /// Applies the queued change delay once the previous delay has passed, anyone may crank this
+    pub fn apply_change_delay(ctx: Context<ApplyChangeDelay>) -> Result<()> {
+        let beamsplitter = &mut ctx.accounts.beamsplitter;
+
+        let change_delay = match beamsplitter.pending_change_delay {
+            Some(change_delay) => change_delay,
+            None => return Err(BeamsplitterErrors::NoPendingChanges.into()),
+        };
+
+        if ctx.accounts.clock.unix_timestamp < beamsplitter.change_delay_effective_at {
+            return Err(BeamsplitterErrors::ChangesNotEffective.into());
+        }
+
+        beamsplitter.change_delay = change_delay;
+        beamsplitter.pending_change_delay = None;
+
+        emit!(ChangeDelayChanged {
+            owner: beamsplitter.owner,
+            change_delay,
+        });
         Ok(())
     }
 }
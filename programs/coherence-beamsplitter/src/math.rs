@@ -0,0 +1,635 @@
+use std::convert::TryFrom;
+
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::BeamsplitterErrors, state::FeeTier, BASIS_POINT_DECIMALS, PRICE_DECIMALS,
+    WEIGHT_DECIMALS,
+};
+
+// The value of 100% in basis points
+pub const BPS_DENOMINATOR: u128 = 10u128.pow(BASIS_POINT_DECIMALS as u32);
+
+/// 10^decimals as a u128, fails if decimals is too large to be represented
+pub fn pow10(decimals: u8) -> Result<u128> {
+    10u128
+        .checked_pow(decimals.into())
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))
+}
+
+/// (a * b) / denominator, rounded down
+pub fn mul_div_floor(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return err!(BeamsplitterErrors::MathOverflow);
+    }
+    a.checked_mul(b)
+        .map(|product| product / denominator)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))
+}
+
+/// (a * b) / denominator, rounded up
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return err!(BeamsplitterErrors::MathOverflow);
+    }
+    let product = a
+        .checked_mul(b)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    let quotient = product / denominator;
+    if product % denominator == 0 {
+        Ok(quotient)
+    } else {
+        quotient
+            .checked_add(1)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))
+    }
+}
+
+/// Narrows a u128 back to the u64 used by token amounts
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(BeamsplitterErrors::U64Failure))
+}
+
+/// Moves `value` from `from_decimals` to `to_decimals`, rounded down
+pub fn rescale_floor(value: u128, from_decimals: u8, to_decimals: u8) -> Result<u128> {
+    if to_decimals >= from_decimals {
+        value
+            .checked_mul(pow10(to_decimals - from_decimals)?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))
+    } else {
+        Ok(value / pow10(from_decimals - to_decimals)?)
+    }
+}
+
+/// The amount of an underlying asset (in its own base units) that backs `amount` etf base units at `weight`.
+/// `weight` is whole asset tokens per whole etf token with WEIGHT_DECIMALS of precision, so it is independent of either mint's decimals
+pub fn weighted_amount(
+    amount: u64,
+    weight: u64,
+    prism_etf_decimals: u8,
+    asset_decimals: u8,
+) -> Result<u64> {
+    let product = (amount as u128)
+        .checked_mul(weight.into())
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    let product_decimals = prism_etf_decimals
+        .checked_add(WEIGHT_DECIMALS)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    to_u64(rescale_floor(product, product_decimals, asset_decimals)?)
+}
+
+/// The largest etf amount that `asset_amount` backs at `weight`, given cohere takes weighted_amount + 1 of the asset
+pub fn backed_amount(
+    asset_amount: u64,
+    weight: u64,
+    prism_etf_decimals: u8,
+    asset_decimals: u8,
+) -> Result<u64> {
+    if asset_amount == 0 {
+        return Ok(0);
+    }
+    if weight == 0 {
+        return err!(BeamsplitterErrors::ZeroWeight);
+    }
+
+    let product_decimals = prism_etf_decimals
+        .checked_add(WEIGHT_DECIMALS)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    let backed = if asset_decimals >= product_decimals {
+        // weighted_amount(a) = a * weight * 10^k, which must not exceed asset_amount - 1
+        let per_etf_unit = (weight as u128)
+            .checked_mul(pow10(asset_decimals - product_decimals)?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+        (asset_amount as u128 - 1) / per_etf_unit
+    } else {
+        // weighted_amount(a) = floor(a * weight / 10^k), which must not exceed asset_amount - 1
+        let limit = (asset_amount as u128)
+            .checked_mul(pow10(product_decimals - asset_decimals)?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+        (limit - 1) / weight as u128
+    };
+
+    Ok(u64::try_from(backed).unwrap_or(u64::MAX))
+}
+
+/// The portion of `amount` represented by `bps` basis points, rounded down
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    to_u64(mul_div_floor(amount.into(), bps.into(), BPS_DENOMINATOR)?)
+}
+
+/// How the etf tokens minted by a CONSTRUCTION are split, `orderer + owner + manager` always equals the amount minted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub orderer: u64,
+    pub owner: u64,
+    pub manager: u64,
+}
+
+impl FeeSplit {
+    pub fn total(&self) -> Option<u64> {
+        self.orderer
+            .checked_add(self.owner)?
+            .checked_add(self.manager)
+    }
+}
+
+/// Splits `amount` minted etf tokens between orderer, Beamsplitter owner and ETF manager.
+///
+/// The fee is `construction_bps` of `amount` rounded down, of which the manager takes `manager_cut`. So small
+/// orders can't avoid fees through rounding, while `construction_bps` is non zero the owner gets at least 1
+/// minimum unit unless the manager's cut is 100%, and the manager gets at least 1 minimum unit unless their cut
+/// is 0%. A zero `construction_bps` charges nothing. Fails if the fee would leave the orderer nothing
+pub fn split_construction_fee(
+    amount: u64,
+    construction_bps: u16,
+    manager_cut: u16,
+) -> Result<FeeSplit> {
+    if u128::from(manager_cut) > BPS_DENOMINATOR {
+        return err!(BeamsplitterErrors::PotentialUnderflow);
+    }
+
+    if construction_bps == 0 {
+        return Ok(FeeSplit {
+            orderer: amount,
+            owner: 0,
+            manager: 0,
+        });
+    }
+
+    let fee_portion = bps_of(amount, construction_bps)?;
+
+    let manager_portion = bps_of(fee_portion, manager_cut)?;
+
+    // Cannot underflow, manager_cut is at most 100% of fee_portion
+    let mut owner = fee_portion - manager_portion;
+    if u128::from(manager_cut) < BPS_DENOMINATOR {
+        owner = owner.max(1);
+    }
+
+    let mut manager = manager_portion;
+    if manager_cut > 0 {
+        manager = manager.max(1);
+    }
+
+    let fee = owner
+        .checked_add(manager)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    if amount <= fee {
+        return err!(BeamsplitterErrors::PotentialUnderflow);
+    }
+
+    Ok(FeeSplit {
+        orderer: amount - fee,
+        owner,
+        manager,
+    })
+}
+
+/// The construction fee of the highest tier `amount` reaches, `base_bps` below the first tier
+pub fn tiered_bps(fee_tiers: &[FeeTier], base_bps: u16, amount: u64) -> u16 {
+    fee_tiers
+        .iter()
+        .take_while(|fee_tier| fee_tier.min_amount <= amount)
+        .last()
+        .map_or(base_bps, |fee_tier| fee_tier.construction_bps)
+}
+
+/// Fee tiers must start above zero with strictly increasing thresholds, and only ever discount the fee
+pub fn valid_fee_tiers(fee_tiers: &[FeeTier], base_bps: u16) -> bool {
+    let mut min_amount = 0;
+    let mut bps = base_bps;
+    for fee_tier in fee_tiers {
+        if fee_tier.min_amount <= min_amount || fee_tier.construction_bps > bps {
+            return false;
+        }
+        min_amount = fee_tier.min_amount;
+        bps = fee_tier.construction_bps;
+    }
+    true
+}
+
+/// Copies `value` into a null padded byte array, None if it does not fit
+pub fn pad_bytes<const N: usize>(value: &str) -> Option<[u8; N]> {
+    let bytes = value.as_bytes();
+    if bytes.len() > N {
+        return None;
+    }
+    let mut padded = [0u8; N];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    Some(padded)
+}
+
+/// Construction fee charged in an underlying asset, split between Beamsplitter owner and ETF manager
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct AssetFee {
+    pub owner: u64,
+    pub manager: u64,
+}
+
+impl AssetFee {
+    pub fn total(&self) -> u64 {
+        self.owner + self.manager
+    }
+}
+
+/// The construction fee on `amount` of an underlying asset. Rounds down, no minimum is charged
+pub fn split_asset_fee(amount: u64, construction_bps: u16, manager_cut: u16) -> Result<AssetFee> {
+    split_fee(bps_of(amount, construction_bps)?, manager_cut)
+}
+
+/// Splits an already charged `fee` between the owner and the manager, the manager's share rounding down
+pub fn split_fee(fee: u64, manager_cut: u16) -> Result<AssetFee> {
+    let manager = bps_of(fee, manager_cut)?;
+    Ok(AssetFee {
+        owner: fee
+            .checked_sub(manager)
+            .ok_or_else(|| error!(BeamsplitterErrors::PotentialUnderflow))?,
+        manager,
+    })
+}
+
+/// Converts `value * 10^expo` into PRICE_DECIMALS fixed point, rounded down
+pub fn to_fixed(value: u128, expo: i32) -> Result<u128> {
+    let target_expo = -i32::from(PRICE_DECIMALS);
+    if expo >= target_expo {
+        let shift = u8::try_from(expo - target_expo)
+            .map_err(|_| error!(BeamsplitterErrors::MathOverflow))?;
+        value
+            .checked_mul(pow10(shift)?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))
+    } else {
+        let shift = u8::try_from(target_expo - expo)
+            .map_err(|_| error!(BeamsplitterErrors::MathOverflow))?;
+        // Anything shifted further than u128 can hold rounds to zero
+        Ok(value / pow10(shift).unwrap_or(u128::MAX))
+    }
+}
+
+/// Converts an oracle price of `price * 10^expo` into PRICE_DECIMALS fixed point, rejecting non-positive prices
+pub fn normalize_price(price: i128, expo: i32) -> Result<u128> {
+    if price <= 0 {
+        return err!(BeamsplitterErrors::PriceUnavailable);
+    }
+
+    let normalized = to_fixed(price as u128, expo)?;
+
+    if normalized == 0 {
+        return err!(BeamsplitterErrors::PriceUnavailable);
+    }
+
+    Ok(normalized)
+}
+
+/// True if the confidence interval is at most `max_confidence_bps` of the price (both in the same fixed point)
+pub fn within_confidence(price: u128, confidence: u128, max_confidence_bps: u16) -> Result<bool> {
+    let scaled_confidence = confidence
+        .checked_mul(BPS_DENOMINATOR)
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    let allowed = price
+        .checked_mul(max_confidence_bps.into())
+        .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    Ok(scaled_confidence <= allowed)
+}
+
+/// The value (PRICE_DECIMALS) of `amount` base units of an asset priced at `price` per whole token
+pub fn asset_value(amount: u64, price: u128, asset_decimals: u8) -> Result<u128> {
+    mul_div_floor(amount.into(), price, pow10(asset_decimals)?)
+}
+
+/// The value (PRICE_DECIMALS) of one whole etf token when `supply` base units share `total_value`
+pub fn nav_per_token(total_value: u128, supply: u64, prism_etf_decimals: u8) -> Result<u128> {
+    mul_div_floor(total_value, pow10(prism_etf_decimals)?, supply.into())
+}
+
+/// The amount of an asset (in its own base units) that fills `allocation_bps` of the value of `amount` etf base units at `nav`
+pub fn allocated_amount(
+    amount: u64,
+    nav: u128,
+    allocation_bps: u64,
+    price: u128,
+    prism_etf_decimals: u8,
+    asset_decimals: u8,
+) -> Result<u64> {
+    let order_value = mul_div_floor(amount.into(), nav, pow10(prism_etf_decimals)?)?;
+    let allocated_value = mul_div_floor(order_value, allocation_bps.into(), BPS_DENOMINATOR)?;
+    to_u64(mul_div_floor(
+        allocated_value,
+        pow10(asset_decimals)?,
+        price,
+    )?)
+}
+
+/// The share of `backing` owed to `amount` out of `supply`, rounded down
+pub fn pro_rata(backing: u64, amount: u64, supply: u64) -> Result<u64> {
+    to_u64(mul_div_floor(backing.into(), amount.into(), supply.into())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pow10_bounds() {
+        assert_eq!(pow10(0).unwrap(), 1);
+        assert_eq!(pow10(9).unwrap(), 1_000_000_000);
+        assert_eq!(pow10(38).unwrap(), 10u128.pow(38));
+        assert!(pow10(39).is_err());
+    }
+
+    #[test]
+    fn mul_div_rounding() {
+        assert_eq!(mul_div_floor(7, 3, 2).unwrap(), 10);
+        assert_eq!(mul_div_ceil(7, 3, 2).unwrap(), 11);
+        assert_eq!(mul_div_floor(8, 3, 2).unwrap(), 12);
+        assert_eq!(mul_div_ceil(8, 3, 2).unwrap(), 12);
+        assert_eq!(mul_div_ceil(0, 3, 2).unwrap(), 0);
+        assert!(mul_div_floor(1, 1, 0).is_err());
+        assert!(mul_div_ceil(1, 1, 0).is_err());
+        assert!(mul_div_floor(u128::MAX, 2, 1).is_err());
+    }
+
+    #[test]
+    fn to_u64_overflow() {
+        assert_eq!(to_u64(u64::MAX as u128).unwrap(), u64::MAX);
+        assert!(to_u64(u64::MAX as u128 + 1).is_err());
+    }
+
+    #[test]
+    fn rescale_both_directions() {
+        assert_eq!(rescale_floor(1_234, 3, 3).unwrap(), 1_234);
+        assert_eq!(rescale_floor(1_234, 3, 1).unwrap(), 12);
+        assert_eq!(rescale_floor(1_234, 3, 6).unwrap(), 1_234_000);
+        assert!(rescale_floor(u128::MAX, 0, 1).is_err());
+    }
+
+    #[test]
+    fn weighted_amount_uses_both_decimals() {
+        // 1 whole etf token (9 decimals) at a weight of 2.5 whole asset tokens
+        let one_etf = 1_000_000_000;
+        let weight = 2_500_000_000;
+        assert_eq!(weighted_amount(one_etf, weight, 9, 6).unwrap(), 2_500_000);
+        assert_eq!(
+            weighted_amount(one_etf, weight, 9, 11).unwrap(),
+            250_000_000_000
+        );
+        assert_eq!(weighted_amount(one_etf, weight, 9, 0).unwrap(), 2);
+
+        // The same weight behaves identically for an etf mint with different decimals
+        assert_eq!(weighted_amount(1_000_000, weight, 6, 6).unwrap(), 2_500_000);
+
+        // Values taken from the construct test in tests/unit-tests
+        assert_eq!(weighted_amount(1_800_266, 3_246_753, 9, 6).unwrap(), 5);
+        assert_eq!(weighted_amount(1_800_266, 7, 9, 11).unwrap(), 1);
+
+        assert!(weighted_amount(u64::MAX, u64::MAX, 0, 38).is_err());
+        assert!(weighted_amount(1, 1, 255, 0).is_err());
+    }
+
+    #[test]
+    fn backed_amount_inverts_cohere() {
+        let cases = [
+            (2_500_001, 2_500_000_000, 9, 6),
+            (2_500_000, 2_500_000_000, 9, 6),
+            (3, 2_500_000_000, 9, 0),
+            (250_000_000_001, 2_500_000_000, 9, 30),
+            (6, 3_246_753, 9, 6),
+            (1, 7, 9, 11),
+        ];
+        for (asset_amount, weight, prism_etf_decimals, asset_decimals) in cases {
+            let backed =
+                backed_amount(asset_amount, weight, prism_etf_decimals, asset_decimals).unwrap();
+            // Cohering the backed amount fits within the asset amount, one more unit does not
+            assert!(
+                weighted_amount(backed, weight, prism_etf_decimals, asset_decimals).unwrap()
+                    < asset_amount
+            );
+            assert!(
+                weighted_amount(backed + 1, weight, prism_etf_decimals, asset_decimals).unwrap()
+                    >= asset_amount
+            );
+        }
+
+        assert_eq!(
+            backed_amount(2_500_001, 2_500_000_000, 9, 6).unwrap(),
+            1_000_000_000
+        );
+        assert_eq!(backed_amount(0, 2_500_000_000, 9, 6).unwrap(), 0);
+        assert!(backed_amount(1, 0, 9, 6).is_err());
+    }
+
+    #[test]
+    fn normalize_oracle_prices() {
+        // $23.45 with a pyth style negative exponent
+        assert_eq!(normalize_price(2_345, -2).unwrap(), 23_450_000_000);
+        assert_eq!(
+            normalize_price(2_345_000_000_000, -11).unwrap(),
+            23_450_000_000
+        );
+        assert_eq!(normalize_price(3, 2).unwrap(), 300_000_000_000);
+        assert!(normalize_price(0, -8).is_err());
+        assert!(normalize_price(-5, -8).is_err());
+        // Too small to represent
+        assert!(normalize_price(1, -12).is_err());
+        assert!(normalize_price(1, -200).is_err());
+        assert!(normalize_price(i64::MAX.into(), 100).is_err());
+        assert_eq!(to_fixed(0, -8).unwrap(), 0);
+        assert_eq!(to_fixed(5, -10).unwrap(), 0);
+    }
+
+    #[test]
+    fn confidence_limits() {
+        let price = 100_000_000_000;
+        assert!(within_confidence(price, 1_000_000_000, 100).unwrap());
+        assert!(!within_confidence(price, 1_000_000_001, 100).unwrap());
+        assert!(within_confidence(price, 0, 0).unwrap());
+        assert!(within_confidence(price, price, 10_000).unwrap());
+        assert!(within_confidence(1, u128::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn percentage_allocation() {
+        let one_etf = 1_000_000_000;
+        // $100 per etf token, 40% in an asset at $20 with 6 decimals -> 2 tokens
+        let nav = 100_000_000_000;
+        let price = 20_000_000_000;
+        assert_eq!(
+            allocated_amount(one_etf, nav, 4_000, price, 9, 6).unwrap(),
+            2_000_000
+        );
+        assert_eq!(asset_value(2_000_000, price, 6).unwrap(), 40_000_000_000);
+        assert_eq!(nav_per_token(nav * 3, 3 * one_etf, 9).unwrap(), nav);
+        assert!(nav_per_token(nav, 0, 9).is_err());
+        assert!(allocated_amount(one_etf, nav, 4_000, 0, 9, 6).is_err());
+    }
+
+    #[test]
+    fn pro_rata_share() {
+        assert_eq!(pro_rata(1_000, 25, 100).unwrap(), 250);
+        assert_eq!(pro_rata(999, 1, 2).unwrap(), 499);
+        assert_eq!(pro_rata(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert!(pro_rata(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn bps_of_amount() {
+        assert_eq!(bps_of(1_000_000, 90).unwrap(), 9_000);
+        assert_eq!(bps_of(1_000_000, 0).unwrap(), 0);
+        assert_eq!(bps_of(1_000_000, 10_000).unwrap(), 1_000_000);
+        assert_eq!(bps_of(111, 90).unwrap(), 0);
+        assert_eq!(bps_of(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn construction_fee_split() {
+        // (amount, construction_bps, manager_cut, orderer, owner, manager)
+        let cases: [(u64, u16, u16, u64, u64, u64); 12] = [
+            // Regular fees, 0.9% split 80/20
+            (1_000_000, 90, 2_000, 991_000, 7_200, 1_800),
+            (1_000_000_000, 90, 2_000, 991_000_000, 7_200_000, 1_800_000),
+            // No fees charges nothing, however small the order
+            (1, 0, 2_000, 1, 0, 0),
+            (1_000_000, 0, 2_000, 1_000_000, 0, 0),
+            (1_000_000, 0, 0, 1_000_000, 0, 0),
+            // Fees rounding to nothing still charge each party with a share 1 minimum unit
+            (100, 90, 2_000, 98, 1, 1),
+            (3, 90, 2_000, 1, 1, 1),
+            // The manager's share rounding to nothing is raised to 1 on top of the owner's
+            (500, 90, 2_000, 495, 4, 1),
+            // Only the party with a share is charged the minimum
+            (100, 90, 0, 99, 1, 0),
+            (100, 90, 10_000, 99, 0, 1),
+            (1_000_000, 90, 10_000, 991_000, 0, 9_000),
+            // Large amounts at the highest fee that still leaves the orderer something
+            (
+                u64::MAX,
+                9_999,
+                5_000,
+                1_844_674_407_370_956,
+                9_222_449_699_651_090_330,
+                9_222_449_699_651_090_329,
+            ),
+        ];
+
+        for (amount, construction_bps, manager_cut, orderer, owner, manager) in cases {
+            let split = split_construction_fee(amount, construction_bps, manager_cut).unwrap();
+            assert_eq!(
+                split,
+                FeeSplit {
+                    orderer,
+                    owner,
+                    manager
+                },
+                "amount {} construction_bps {} manager_cut {}",
+                amount,
+                construction_bps,
+                manager_cut
+            );
+            assert_eq!(split.total(), Some(amount));
+        }
+    }
+
+    #[test]
+    fn construction_fee_split_failures() {
+        // (amount, construction_bps, manager_cut)
+        let cases: [(u64, u16, u16); 5] = [
+            // Minimum fees would take the whole order
+            (2, 90, 2_000),
+            (1, 90, 0),
+            (1, 90, 10_000),
+            // A 100% fee leaves the orderer nothing
+            (1_000_000, 10_000, 2_000),
+            // Manager cut above 100%
+            (1_000_000, 90, 10_001),
+        ];
+
+        for (amount, construction_bps, manager_cut) in cases {
+            assert!(split_construction_fee(amount, construction_bps, manager_cut).is_err());
+        }
+    }
+
+    #[test]
+    fn fee_tier_lookup() {
+        let fee_tiers = [
+            FeeTier {
+                min_amount: 1_000,
+                construction_bps: 60,
+            },
+            FeeTier {
+                min_amount: 10_000,
+                construction_bps: 30,
+            },
+        ];
+
+        // (amount, construction_bps)
+        let cases: [(u64, u16); 6] = [
+            (0, 90),
+            (999, 90),
+            (1_000, 60),
+            (9_999, 60),
+            (10_000, 30),
+            (u64::MAX, 30),
+        ];
+        for (amount, construction_bps) in cases {
+            assert_eq!(tiered_bps(&fee_tiers, 90, amount), construction_bps);
+        }
+
+        assert_eq!(tiered_bps(&[], 90, u64::MAX), 90);
+    }
+
+    #[test]
+    fn fee_tier_validation() {
+        let tier = |min_amount, construction_bps| FeeTier {
+            min_amount,
+            construction_bps,
+        };
+
+        assert!(valid_fee_tiers(&[], 90));
+        assert!(valid_fee_tiers(&[tier(1_000, 60), tier(10_000, 30)], 90));
+        // Equal rates are allowed, thresholds must still increase
+        assert!(valid_fee_tiers(&[tier(1_000, 90), tier(10_000, 90)], 90));
+
+        assert!(!valid_fee_tiers(&[tier(0, 60)], 90));
+        assert!(!valid_fee_tiers(&[tier(1_000, 100)], 90));
+        assert!(!valid_fee_tiers(&[tier(10_000, 60), tier(1_000, 30)], 90));
+        assert!(!valid_fee_tiers(&[tier(1_000, 60), tier(1_000, 30)], 90));
+        assert!(!valid_fee_tiers(&[tier(1_000, 30), tier(10_000, 60)], 90));
+    }
+
+    #[test]
+    fn padded_bytes() {
+        assert_eq!(pad_bytes::<4>("ab"), Some([b'a', b'b', 0, 0]));
+        assert_eq!(pad_bytes::<4>("abcd"), Some(*b"abcd"));
+        assert_eq!(pad_bytes::<4>(""), Some([0; 4]));
+        assert_eq!(pad_bytes::<4>("abcde"), None);
+        // Length is counted in bytes, not characters
+        assert_eq!(pad_bytes::<4>("ééé"), None);
+    }
+
+    #[test]
+    fn asset_fee_split() {
+        let fee = split_asset_fee(1_000_000, 90, 2_000).unwrap();
+        assert_eq!(
+            fee,
+            AssetFee {
+                owner: 7_200,
+                manager: 1_800
+            }
+        );
+        assert_eq!(fee.total(), 9_000);
+
+        // Small amounts round down to no fee rather than charging a minimum
+        assert_eq!(split_asset_fee(100, 90, 2_000).unwrap().total(), 0);
+
+        assert!(split_asset_fee(1_000_000, 90, 10_001).is_err());
+
+        // An already charged fee is split the same way, the manager's share rounding down
+        assert_eq!(split_fee(9_000, 2_000).unwrap(), fee);
+        assert_eq!(
+            split_fee(9, 2_000).unwrap(),
+            AssetFee {
+                owner: 8,
+                manager: 1
+            }
+        );
+    }
+}
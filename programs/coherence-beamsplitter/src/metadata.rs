@@ -0,0 +1,155 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, program::invoke_signed},
+};
+
+/// Metaplex Token Metadata program. Its instructions are built by hand because the mpl-token-metadata crate
+/// requires a newer solana-program than anchor-lang 0.23 allows
+pub mod token_metadata_program {
+    use anchor_lang::declare_id;
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+// Instruction indexes of MetadataInstruction. CreateMetadataAccountV2 (16) has been removed from the deployed program
+const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// Borsh layout of the metadata program's DataV2. Creators, collection and uses are never set, so only their
+/// Option tag is serialized and the inner types are placeholders
+#[derive(AnchorSerialize)]
+struct DataV2 {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<Pubkey>>,
+    collection: Option<Pubkey>,
+    uses: Option<u64>,
+}
+
+impl DataV2 {
+    fn new(name: String, symbol: String, uri: String) -> DataV2 {
+        DataV2 {
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+}
+
+/// Borsh layout of CreateMetadataAccountArgsV3, collection_details is never set
+#[derive(AnchorSerialize)]
+struct CreateMetadataAccountArgsV3 {
+    data: DataV2,
+    is_mutable: bool,
+    collection_details: Option<u64>,
+}
+
+/// Borsh layout of UpdateMetadataAccountArgsV2
+#[derive(AnchorSerialize)]
+struct UpdateMetadataAccountArgsV2 {
+    data: Option<DataV2>,
+    update_authority: Option<Pubkey>,
+    primary_sale_happened: Option<bool>,
+    is_mutable: Option<bool>,
+}
+
+fn instruction_data<T: AnchorSerialize>(index: u8, args: &T) -> Result<Vec<u8>> {
+    let mut data = vec![index];
+    args.serialize(&mut data)
+        .map_err(|error| ProgramError::BorshIoError(error.to_string()))?;
+    Ok(data)
+}
+
+/// Creates Metaplex metadata for `mint`. `authority` is both the mint authority and the update authority of the
+/// metadata, and signs with `signer_seeds`
+#[allow(clippy::too_many_arguments)]
+pub fn create_metadata<'info>(
+    token_metadata_program: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let args = CreateMetadataAccountArgsV3 {
+        data: DataV2::new(name, symbol, uri),
+        is_mutable: true,
+        collection_details: None,
+    };
+    let instruction = Instruction {
+        program_id: token_metadata_program.key(),
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(rent.key(), false),
+        ],
+        data: instruction_data(CREATE_METADATA_ACCOUNT_V3, &args)?,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata.clone(),
+            mint.clone(),
+            authority.clone(),
+            payer.clone(),
+            authority.clone(),
+            system_program.clone(),
+            rent.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Replaces the name, symbol and uri of metadata whose update authority is `authority`, signing with `signer_seeds`
+pub fn update_metadata<'info>(
+    token_metadata_program: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let args = UpdateMetadataAccountArgsV2 {
+        data: Some(DataV2::new(name, symbol, uri)),
+        update_authority: None,
+        primary_sale_happened: None,
+        is_mutable: None,
+    };
+    let instruction = Instruction {
+        program_id: token_metadata_program.key(),
+        accounts: vec![
+            AccountMeta::new(metadata.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+        ],
+        data: instruction_data(UPDATE_METADATA_ACCOUNT_V2, &args)?,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            metadata.clone(),
+            authority.clone(),
+            token_metadata_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
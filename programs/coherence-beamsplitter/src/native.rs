@@ -0,0 +1,25 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, system_instruction},
+};
+
+/// Wraps `amount` lamports of `from` into `native_ata`, a wSOL [TokenAccount], by sending the lamports to it and
+/// syncing its token balance
+pub fn wrap_lamports<'info>(
+    from: &AccountInfo<'info>,
+    native_ata: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    invoke(
+        &system_instruction::transfer(from.key, native_ata.key, amount),
+        &[from.clone(), native_ata.clone(), system_program.clone()],
+    )?;
+
+    invoke(
+        &spl_token::instruction::sync_native(token_program.key, native_ata.key)?,
+        &[native_ata.clone(), token_program.clone()],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use std::convert::{TryFrom, TryInto};
+
+use anchor_lang::{prelude::*, solana_program::hash::hash};
+
+use crate::{
+    enums::PriceFeedKind,
+    errors::BeamsplitterErrors,
+    math::{normalize_price, to_fixed, within_confidence},
+    state::{PriceFeed, WeightedTokens},
+};
+
+// Pyth price account layout (v2)
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_VERSION: u32 = 2;
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+
+const PYTH_MAGIC_OFFSET: usize = 0;
+const PYTH_VERSION_OFFSET: usize = 4;
+const PYTH_ACCOUNT_TYPE_OFFSET: usize = 8;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_AGG_PRICE_OFFSET: usize = 208;
+const PYTH_AGG_CONF_OFFSET: usize = 216;
+const PYTH_AGG_STATUS_OFFSET: usize = 224;
+
+// Switchboard v2 AggregatorAccountData layout (offsets include the 8 byte discriminator)
+const SWITCHBOARD_DISCRIMINATOR_PREIMAGE: &[u8] = b"account:AggregatorAccountData";
+const SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+const SWITCHBOARD_RESULT_OFFSET: usize = 366;
+const SWITCHBOARD_STD_DEVIATION_OFFSET: usize = 386;
+
+/// Price of a weighted token along with the decimals of its mint
+#[derive(Debug, Copy, Clone)]
+pub struct AssetPrice {
+    /// Quote units per whole token (PRICE_DECIMALS)
+    pub price: u128,
+    pub decimals: u8,
+}
+
+/// A price as reported by a feed, before it is checked against the limits of its [PriceFeed]
+#[derive(Debug, Copy, Clone)]
+pub struct FeedPrice {
+    /// Quote units per whole token (PRICE_DECIMALS)
+    pub price: u128,
+    /// Width of the confidence interval (PRICE_DECIMALS)
+    pub confidence: u128,
+    /// Unix timestamp the price was published at
+    pub publish_time: i64,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| error!(BeamsplitterErrors::InvalidPriceFeed))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    Ok(u32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Result<i32> {
+    Ok(i32::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    Ok(u64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Result<i64> {
+    Ok(i64::from_le_bytes(read_bytes(data, offset)?))
+}
+
+fn read_i128(data: &[u8], offset: usize) -> Result<i128> {
+    Ok(i128::from_le_bytes(read_bytes(data, offset)?))
+}
+
+/// Reads the aggregate price out of a Pyth-format price account
+pub fn read_pyth_price(data: &[u8]) -> Result<FeedPrice> {
+    if read_u32(data, PYTH_MAGIC_OFFSET)? != PYTH_MAGIC
+        || read_u32(data, PYTH_VERSION_OFFSET)? != PYTH_VERSION
+        || read_u32(data, PYTH_ACCOUNT_TYPE_OFFSET)? != PYTH_PRICE_ACCOUNT_TYPE
+    {
+        return err!(BeamsplitterErrors::InvalidPriceFeed);
+    }
+
+    if read_u32(data, PYTH_AGG_STATUS_OFFSET)? != PYTH_STATUS_TRADING {
+        return err!(BeamsplitterErrors::PriceUnavailable);
+    }
+
+    let expo = read_i32(data, PYTH_EXPO_OFFSET)?;
+    Ok(FeedPrice {
+        price: normalize_price(read_i64(data, PYTH_AGG_PRICE_OFFSET)?.into(), expo)?,
+        confidence: to_fixed(read_u64(data, PYTH_AGG_CONF_OFFSET)?.into(), expo)?,
+        publish_time: read_i64(data, PYTH_TIMESTAMP_OFFSET)?,
+    })
+}
+
+/// Reads the latest confirmed round out of a Switchboard-format aggregator account
+pub fn read_switchboard_price(data: &[u8]) -> Result<FeedPrice> {
+    let discriminator: [u8; 8] = read_bytes(data, 0)?;
+    if discriminator[..] != hash(SWITCHBOARD_DISCRIMINATOR_PREIMAGE).to_bytes()[..8] {
+        return err!(BeamsplitterErrors::InvalidPriceFeed);
+    }
+
+    // Switchboard decimals are a mantissa followed by a u32 scale (value = mantissa / 10^scale)
+    let result = read_i128(data, SWITCHBOARD_RESULT_OFFSET)?;
+    let result_scale = read_u32(data, SWITCHBOARD_RESULT_OFFSET + 16)?;
+    let std_deviation = read_i128(data, SWITCHBOARD_STD_DEVIATION_OFFSET)?;
+    let std_deviation_scale = read_u32(data, SWITCHBOARD_STD_DEVIATION_OFFSET + 16)?;
+
+    let result_expo =
+        -i32::try_from(result_scale).map_err(|_| error!(BeamsplitterErrors::InvalidPriceFeed))?;
+    let std_deviation_expo = -i32::try_from(std_deviation_scale)
+        .map_err(|_| error!(BeamsplitterErrors::InvalidPriceFeed))?;
+
+    Ok(FeedPrice {
+        price: normalize_price(result, result_expo)?,
+        confidence: to_fixed(std_deviation.unsigned_abs(), std_deviation_expo)?,
+        publish_time: read_i64(data, SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET)?,
+    })
+}
+
+/// Loads the price of `mint` given its registered [PriceFeed] and the feed account it points to,
+/// rejecting prices older than or less certain than the registered limits
+pub fn load_price(
+    price_feed_info: &AccountInfo,
+    feed_info: &AccountInfo,
+    mint: &Pubkey,
+    now: i64,
+) -> Result<AssetPrice> {
+    if price_feed_info.owner != &crate::ID {
+        return err!(BeamsplitterErrors::InvalidPriceFeed);
+    }
+
+    let price_feed = PriceFeed::try_deserialize(&mut &price_feed_info.try_borrow_data()?[..])?;
+
+    if price_feed.mint != *mint {
+        return err!(BeamsplitterErrors::WrongPriceFeed);
+    }
+
+    if price_feed.feed != feed_info.key() {
+        return err!(BeamsplitterErrors::WrongPriceFeed);
+    }
+
+    let data = feed_info.try_borrow_data()?;
+    let feed_price = match price_feed.kind {
+        PriceFeedKind::PYTH => read_pyth_price(&data)?,
+        PriceFeedKind::SWITCHBOARD => read_switchboard_price(&data)?,
+    };
+
+    let age = now.saturating_sub(feed_price.publish_time);
+    if age > price_feed.max_staleness.into() {
+        return err!(BeamsplitterErrors::StalePrice);
+    }
+
+    if !within_confidence(
+        feed_price.price,
+        feed_price.confidence,
+        price_feed.max_confidence_bps,
+    )? {
+        return err!(BeamsplitterErrors::PriceTooUncertain);
+    }
+
+    Ok(AssetPrice {
+        price: feed_price.price,
+        decimals: price_feed.decimals,
+    })
+}
+
+/// Loads a price for every weighted token, `remaining_accounts` holds a ([PriceFeed], feed account) pair per token in index order
+pub fn load_prices(
+    remaining_accounts: &[AccountInfo],
+    weighted_tokens: &WeightedTokens,
+    now: i64,
+) -> Result<Vec<AssetPrice>> {
+    let length = weighted_tokens.length as usize;
+
+    if remaining_accounts.len() < length * 2 {
+        return err!(BeamsplitterErrors::MissingPriceFeeds);
+    }
+
+    let mut prices = Vec::with_capacity(length);
+    for idx in 0..length {
+        let mint = weighted_tokens.weighted_tokens[idx].mint;
+        prices.push(load_price(
+            &remaining_accounts[idx * 2],
+            &remaining_accounts[idx * 2 + 1],
+            &mint,
+            now,
+        )?);
+    }
+
+    Ok(prices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyth_feed(price: i64, conf: u64, expo: i32, status: u32, timestamp: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 240];
+        data[PYTH_MAGIC_OFFSET..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[PYTH_VERSION_OFFSET..8].copy_from_slice(&PYTH_VERSION.to_le_bytes());
+        data[PYTH_ACCOUNT_TYPE_OFFSET..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[PYTH_EXPO_OFFSET..24].copy_from_slice(&expo.to_le_bytes());
+        data[PYTH_TIMESTAMP_OFFSET..104].copy_from_slice(&timestamp.to_le_bytes());
+        data[PYTH_AGG_PRICE_OFFSET..216].copy_from_slice(&price.to_le_bytes());
+        data[PYTH_AGG_CONF_OFFSET..224].copy_from_slice(&conf.to_le_bytes());
+        data[PYTH_AGG_STATUS_OFFSET..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    fn switchboard_feed(result: i128, std_deviation: i128, scale: u32, timestamp: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 500];
+        data[..8].copy_from_slice(&hash(SWITCHBOARD_DISCRIMINATOR_PREIMAGE).to_bytes()[..8]);
+        data[SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET..366]
+            .copy_from_slice(&timestamp.to_le_bytes());
+        data[SWITCHBOARD_RESULT_OFFSET..382].copy_from_slice(&result.to_le_bytes());
+        data[382..386].copy_from_slice(&scale.to_le_bytes());
+        data[SWITCHBOARD_STD_DEVIATION_OFFSET..402].copy_from_slice(&std_deviation.to_le_bytes());
+        data[402..406].copy_from_slice(&scale.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn reads_pyth_feed() {
+        let feed_price = read_pyth_price(&pyth_feed(2_345_000, 1_000, -5, 1, 42)).unwrap();
+        assert_eq!(feed_price.price, 23_450_000_000);
+        assert_eq!(feed_price.confidence, 10_000_000);
+        assert_eq!(feed_price.publish_time, 42);
+
+        // Not trading
+        assert!(read_pyth_price(&pyth_feed(2_345_000, 1_000, -5, 0, 42)).is_err());
+        // Wrong magic
+        let mut data = pyth_feed(2_345_000, 1_000, -5, 1, 42);
+        data[0] = 0;
+        assert!(read_pyth_price(&data).is_err());
+        // Truncated
+        assert!(read_pyth_price(&data[..100]).is_err());
+    }
+
+    #[test]
+    fn reads_switchboard_feed() {
+        let feed_price = read_switchboard_price(&switchboard_feed(2_345, 12, 2, 42)).unwrap();
+        assert_eq!(feed_price.price, 23_450_000_000);
+        assert_eq!(feed_price.confidence, 120_000_000);
+        assert_eq!(feed_price.publish_time, 42);
+
+        assert!(read_switchboard_price(&switchboard_feed(-2_345, 12, 2, 42)).is_err());
+        let mut data = switchboard_feed(2_345, 12, 2, 42);
+        data[0] ^= 1;
+        assert!(read_switchboard_price(&data).is_err());
+        // A pyth account is not a switchboard account
+        assert!(read_switchboard_price(&pyth_feed(2_345_000, 1_000, -5, 1, 42)).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::BeamsplitterErrors,
+    math::{allocated_amount, asset_value, mul_div_floor, nav_per_token, pow10, pro_rata},
+    oracle::AssetPrice,
+    state::WeightedTokens,
+    WEIGHT_DECIMALS,
+};
+
+/// Net asset value (PRICE_DECIMALS) of one PERCENTAGE etf token, from the backing of the current supply
+pub fn percentage_nav(
+    weighted_tokens: &WeightedTokens,
+    prices: &[AssetPrice],
+    supply: u64,
+    initial_nav: u64,
+    prism_etf_decimals: u8,
+) -> Result<u128> {
+    if supply == 0 {
+        return Ok(initial_nav.into());
+    }
+
+    let mut total_value: u128 = 0;
+    for (idx, asset_price) in prices.iter().enumerate() {
+        total_value = total_value
+            .checked_add(asset_value(
+                weighted_tokens.backing[idx],
+                asset_price.price,
+                asset_price.decimals,
+            )?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    }
+
+    nav_per_token(total_value, supply, prism_etf_decimals)
+}
+
+/// Net asset value (PRICE_DECIMALS) of one QUANTITY etf token, the value of the assets its weights describe
+pub fn quantity_nav(weighted_tokens: &WeightedTokens, prices: &[AssetPrice]) -> Result<u128> {
+    let mut nav: u128 = 0;
+    for (idx, asset_price) in prices.iter().enumerate() {
+        nav = nav
+            .checked_add(mul_div_floor(
+                weighted_tokens.weighted_tokens[idx].weight.into(),
+                asset_price.price,
+                pow10(WEIGHT_DECIMALS)?,
+            )?)
+            .ok_or_else(|| error!(BeamsplitterErrors::MathOverflow))?;
+    }
+
+    Ok(nav)
+}
+
+/// Amount of each asset a PERCENTAGE CONSTRUCTION of `amount` requires, so each fills its target allocation of the order's value at `nav`
+pub fn percentage_construction_amounts(
+    weighted_tokens: &WeightedTokens,
+    prices: &[AssetPrice],
+    amount: u64,
+    nav: u128,
+    prism_etf_decimals: u8,
+) -> Result<Vec<u64>> {
+    if nav == 0 {
+        return err!(BeamsplitterErrors::ZeroNav);
+    }
+
+    let mut amounts = Vec::with_capacity(prices.len());
+    for (idx, asset_price) in prices.iter().enumerate() {
+        amounts.push(allocated_amount(
+            amount,
+            nav,
+            weighted_tokens.weighted_tokens[idx].weight,
+            asset_price.price,
+            prism_etf_decimals,
+            asset_price.decimals,
+        )?);
+    }
+
+    Ok(amounts)
+}
+
+/// Amount of each asset a PERCENTAGE DECONSTRUCTION of `amount` pays out. This is a pro rata share of the backing
+/// (worth amount * NAV at any set of prices), so drift from the target allocation can never drain one asset ahead of the others
+pub fn percentage_deconstruction_amounts(
+    weighted_tokens: &WeightedTokens,
+    amount: u64,
+    supply: u64,
+) -> Result<Vec<u64>> {
+    let length = weighted_tokens.length as usize;
+    let mut amounts = Vec::with_capacity(length);
+    for idx in 0..length {
+        amounts.push(pro_rata(weighted_tokens.backing[idx], amount, supply)?);
+    }
+
+    Ok(amounts)
+}
@@ -2,12 +2,29 @@ use std::mem::size_of;
 
 use anchor_lang::prelude::*;
 
-use crate::enums::*;
+use crate::{
+    enums::*,
+    errors::BeamsplitterErrors,
+    math::{pad_bytes, split_fee, tiered_bps},
+};
 
 #[constant]
 pub const _PRISM_ETF_SIZE: usize = size_of::<PrismEtf>();
 #[constant]
 pub const MAX_WEIGHTED_TOKENS: usize = 100;
+#[constant]
+pub const MAX_FEE_TIERS: usize = 8;
+/// Most levels of ETFs nested inside each other, each level is another round of inner orders to construct
+#[constant]
+pub const MAX_NESTING_DEPTH: u8 = 3;
+#[constant]
+pub const MAX_DESCRIPTOR_NAME_LENGTH: usize = 32;
+#[constant]
+pub const MAX_DESCRIPTOR_SYMBOL_LENGTH: usize = 10;
+#[constant]
+pub const MAX_DESCRIPTOR_TAGS: usize = 8;
+#[constant]
+pub const MAX_DESCRIPTOR_TAG_LENGTH: usize = 16;
 
 /// Contains the info of the prism etf.
 #[account]
@@ -39,8 +56,219 @@ pub struct PrismEtf {
     pub autorebalancing_schedule: AutorebalancingSchedule,
     /// How often the manager collects fee
     pub manager_schedule: ManagerSchedule,
+    /// How weights of the [WeightedTokens] are interpreted
+    pub weight_mode: WeightMode,
+    /// Value of one etf token (PRICE_DECIMALS) used for the first PERCENTAGE construction, before any supply exists
+    pub initial_nav: u64,
+    /// What construction fees are charged in
+    pub fee_mode: FeeMode,
+    /// Discounted construction fees for large orders, only the first `fee_tiers_length` are used
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    /// Number of [FeeTier]s in use
+    pub fee_tiers_length: u8,
+    /// Fee changes queued by the Beamsplitter owner or fee admin, waiting out the [Beamsplitter]'s change_delay
+    pub pending_fee_changes: PendingFeeChanges,
+    /// Manager changes queued by the manager, waiting out the [Beamsplitter]'s change_delay
+    pub pending_manager_changes: PendingManagerChanges,
+    /// Levels of ETFs nested inside this one, 0 if no weighted token is an ETF
+    pub nesting_depth: u8,
+    /// Bit `i` is set if weighted token `i` was pushed as a nested ETF, which cohere_nested can construct
+    pub nested_tokens: u128,
+    /// Most etf tokens that may exist, 0 for no cap
+    pub max_supply: u64,
+    /// Fewest etf tokens a CONSTRUCTION order may create
+    pub min_order_amount: u64,
+    /// Most etf tokens a single order may create or redeem, 0 for no limit
+    pub max_order_amount: u64,
+    /// If true, only orderers with an [AllowlistEntry] can construct
+    pub permissioned: bool,
+    /// If true, the manager can freeze and thaw holders' token accounts. Only set before the ETF is finished, so
+    /// holders know from the start
+    pub freezable: bool,
+}
+
+impl PrismEtf {
+    /// A fresh, UNFINISHED [PrismEtf] using the [Beamsplitter]'s default fees
+    pub fn new(
+        manager: Pubkey,
+        weighted_tokens: Pubkey,
+        bump: u8,
+        beamsplitter: &Beamsplitter,
+    ) -> Self {
+        PrismEtf {
+            manager,
+            weighted_tokens,
+            status: PrismEtfStatus::UNFINISHED,
+            bump,
+            total_shared_order_states: 0,
+            construction_bps: beamsplitter.default_construction_bps,
+            deconstruction_bps: beamsplitter.default_deconstruction_bps,
+            manager_cut: beamsplitter.default_manager_cut,
+            manager_fee: beamsplitter.default_manager_fee,
+            rebalancing_mode: RebalancingMode::OFF,
+            autorebalancing_schedule: AutorebalancingSchedule::NEVER,
+            manager_schedule: ManagerSchedule::NEVER,
+            referer: manager,
+            weight_mode: WeightMode::QUANTITY,
+            initial_nav: 0,
+            fee_mode: FeeMode::DILUTION,
+            fee_tiers: [FeeTier::default(); MAX_FEE_TIERS],
+            fee_tiers_length: 0,
+            pending_fee_changes: PendingFeeChanges::default(),
+            pending_manager_changes: PendingManagerChanges::default(),
+            nesting_depth: 0,
+            nested_tokens: 0,
+            max_supply: 0,
+            min_order_amount: 0,
+            max_order_amount: 0,
+            permissioned: false,
+            freezable: false,
+        }
+    }
+
+    /// Whether the weighted token at `index` was pushed as a nested ETF
+    pub fn is_nested(&self, index: u16) -> bool {
+        index < 128 && self.nested_tokens & (1 << index) != 0
+    }
+
+    /// Checks an order of `amount` etf tokens against the manager's limits. Redemptions are never held to the minimum,
+    /// so holders of less can still leave
+    pub fn check_order_limits(
+        &self,
+        order_type: OrderType,
+        amount: u64,
+        supply: u64,
+    ) -> Result<()> {
+        if self.max_order_amount != 0 && amount > self.max_order_amount {
+            return err!(BeamsplitterErrors::OrderTooLarge);
+        }
+
+        if order_type == OrderType::CONSTRUCTION {
+            if amount < self.min_order_amount {
+                return err!(BeamsplitterErrors::OrderTooSmall);
+            }
+
+            let new_supply = supply
+                .checked_add(amount)
+                .ok_or(BeamsplitterErrors::MathOverflow)?;
+            if self.max_supply != 0 && new_supply > self.max_supply {
+                return err!(BeamsplitterErrors::SupplyCapExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Construction fee in basis points for an order of `amount` etf tokens
+    pub fn construction_bps_for(&self, amount: u64) -> u16 {
+        tiered_bps(
+            &self.fee_tiers[..self.fee_tiers_length as usize],
+            self.construction_bps,
+            amount,
+        )
+    }
+
+    /// When changes queued now can be applied. ETFs that are still being designed have no holders to protect, so
+    /// their changes can be applied right away
+    fn changes_effective_at(&self, now: i64, change_delay: u32) -> i64 {
+        match self.status {
+            PrismEtfStatus::UNFINISHED => now,
+            _ => now.saturating_add(change_delay.into()),
+        }
+    }
+
+    /// Starts the delay before the pending fee changes can be applied over again
+    pub fn queue_fee_changes(&mut self, now: i64, change_delay: u32) {
+        self.pending_fee_changes.effective_at = self.changes_effective_at(now, change_delay);
+    }
+
+    /// Starts the delay before the pending manager changes can be applied over again
+    pub fn queue_manager_changes(&mut self, now: i64, change_delay: u32) {
+        self.pending_manager_changes.effective_at = self.changes_effective_at(now, change_delay);
+    }
+
+    /// The fee tiers in use once the pending fee changes are applied
+    pub fn next_fee_tiers(&self) -> &[FeeTier] {
+        match self.pending_fee_changes.fee_tiers_length {
+            Some(length) => &self.pending_fee_changes.fee_tiers[..length as usize],
+            None => &self.fee_tiers[..self.fee_tiers_length as usize],
+        }
+    }
+
+    /// Moves the pending fee changes onto the ETF, returning what was applied
+    pub fn apply_fee_changes(&mut self) -> PendingFeeChanges {
+        let pending_fee_changes = std::mem::take(&mut self.pending_fee_changes);
+        if let Some(construction_bps) = pending_fee_changes.construction_bps {
+            self.construction_bps = construction_bps;
+        }
+        if let Some(deconstruction_bps) = pending_fee_changes.deconstruction_bps {
+            self.deconstruction_bps = deconstruction_bps;
+        }
+        if let Some(fee_tiers_length) = pending_fee_changes.fee_tiers_length {
+            self.fee_tiers = pending_fee_changes.fee_tiers;
+            self.fee_tiers_length = fee_tiers_length;
+        }
+        pending_fee_changes
+    }
+
+    /// Moves the pending manager changes onto the ETF, returning what was applied
+    pub fn apply_manager_changes(&mut self) -> PendingManagerChanges {
+        let pending_manager_changes = std::mem::take(&mut self.pending_manager_changes);
+        if let Some(manager_cut) = pending_manager_changes.manager_cut {
+            self.manager_cut = manager_cut;
+        }
+        if let Some(manager) = pending_manager_changes.manager {
+            self.manager = manager;
+        }
+        pending_manager_changes
+    }
 }
 
+/// Fee changes queued on a [PrismEtf] by the Beamsplitter owner or fee admin, so holders can exit before they take
+/// effect
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AnchorDeserialize, AnchorSerialize)]
+pub struct PendingFeeChanges {
+    pub construction_bps: Option<u16>,
+    pub deconstruction_bps: Option<u16>,
+    /// Replacement fee tiers, only the first `fee_tiers_length` are used
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+    /// Set if the fee tiers are being replaced
+    pub fee_tiers_length: Option<u8>,
+    /// Unix timestamp from which the changes can be applied
+    pub effective_at: i64,
+}
+
+impl PendingFeeChanges {
+    pub fn is_empty(&self) -> bool {
+        self.construction_bps.is_none()
+            && self.deconstruction_bps.is_none()
+            && self.fee_tiers_length.is_none()
+    }
+}
+
+/// Manager changes queued on a [PrismEtf] by its manager, so holders can exit before they take effect
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AnchorDeserialize, AnchorSerialize)]
+pub struct PendingManagerChanges {
+    pub manager_cut: Option<u16>,
+    pub manager: Option<Pubkey>,
+    /// Unix timestamp from which the changes can be applied
+    pub effective_at: i64,
+}
+
+impl PendingManagerChanges {
+    pub fn is_empty(&self) -> bool {
+        self.manager_cut.is_none() && self.manager.is_none()
+    }
+}
+
+/// Construction fee charged to orders of at least `min_amount` etf tokens
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, AnchorDeserialize, AnchorSerialize)]
+pub struct FeeTier {
+    pub min_amount: u64,
+    pub construction_bps: u16,
+}
+
+/// Grew by `backing` for PERCENTAGE mode. Accounts allocated with the old size can't be resized in place, so ETFs
+/// created before it was added must be closed and recreated
 #[repr(packed)]
 #[account(zero_copy)]
 #[derive(Debug)]
@@ -51,6 +279,8 @@ pub struct WeightedTokens {
     pub capacity: u16,
     /// [WeightedToken] array
     pub weighted_tokens: [WeightedToken; 100], // TODO find better name
+    /// Amount of each weighted token backing the current etf supply (only tracked in PERCENTAGE mode)
+    pub backing: [u64; 100],
 }
 
 /// Contains the info of the prism etf.
@@ -73,8 +303,14 @@ pub struct OrderState {
     pub amount: u64,
     /// The bump for this PDA account
     pub bump: u8,
+    /// The single asset the orderer pays in (CONSTRUCTION) or is paid out in (DECONSTRUCTION), default [Pubkey] for regular orders
+    pub swap_mint: Pubkey,
+    /// The least a single asset order may produce, etf tokens (CONSTRUCTION) or `swap_mint` tokens (DECONSTRUCTION)
+    pub min_amount_out: u64,
 }
 
+/// Grew by `amounts` for PERCENTAGE mode and by `fees` for UNDERLYING fee mode. Order states created before they were
+/// added must be closed and recreated
 #[account(zero_copy)]
 #[derive(Debug)]
 pub struct TransferredTokens {
@@ -84,15 +320,176 @@ pub struct TransferredTokens {
     pub capacity: u16,
     /// Each bool is true if the corresponding weight_token was transferred succesfully in the order
     pub transferred_tokens: [bool; 100], // TODO find better name
+    /// Amount of each weighted token snapshotted at start_order, then what cohere actually transferred (only used in
+    /// PERCENTAGE mode)
+    pub amounts: [u64; 100],
+    /// UNDERLYING fee each cohere sent to the fee vault. Accrued to the vault at finalize_order, or refunded if the
+    /// token is decohered first
+    pub fees: [u64; 100],
 }
 
 #[zero_copy]
 #[derive(Debug, Default, AnchorDeserialize, AnchorSerialize)]
 pub struct WeightedToken {
     pub mint: Pubkey,
+    /// QUANTITY mode: whole tokens of `mint` per whole etf token, with WEIGHT_DECIMALS of precision
+    /// PERCENTAGE mode: target allocation of the etf's value in bps
     pub weight: u64,
 }
 
+/// Result of quote_order, returned through return data
+#[derive(Debug, Clone, Default, AnchorDeserialize, AnchorSerialize)]
+pub struct OrderQuote {
+    /// Value of one etf token (PRICE_DECIMALS), if prices were available
+    pub nav: Option<u64>,
+    /// Amount of each weighted token taken from (CONSTRUCTION) or paid to (DECONSTRUCTION) the orderer, in index order.
+    /// Includes the fee in UNDERLYING fee mode
+    pub amounts: Vec<u64>,
+    /// Etf tokens minted to (CONSTRUCTION) or burned from (DECONSTRUCTION) the orderer
+    pub orderer_amount: u64,
+    /// Etf tokens minted to the Beamsplitter owner
+    pub owner_fee: u64,
+    /// Etf tokens minted to the ETF manager
+    pub manager_fee: u64,
+}
+
+/// Holds the fees an ETF has accrued until the Beamsplitter owner and ETF manager withdraw them
+#[account]
+#[derive(Copy, Debug, Default)]
+pub struct FeeVault {
+    /// The [PrismEtf] whose fees this vault holds
+    pub prism_etf: Pubkey,
+    /// Etf tokens in the vault owed to the Beamsplitter owner
+    pub protocol_fees: u64,
+    /// Etf tokens in the vault owed to the ETF manager
+    pub manager_fees: u64,
+    /// The bump for this PDA account
+    pub bump: u8,
+    /// Amount of each weighted token in the vault owed to the Beamsplitter owner (UNDERLYING fee mode)
+    pub protocol_asset_fees: [u64; 100],
+    /// Amount of each weighted token in the vault owed to the ETF manager (UNDERLYING fee mode)
+    pub manager_asset_fees: [u64; 100],
+}
+
+impl FeeVault {
+    /// Accrues the UNDERLYING fees an order took, split with the manager's current cut
+    pub fn accrue_asset_fees(&mut self, fees: &[u64], manager_cut: u16) -> Result<()> {
+        for (index, fee) in fees.iter().enumerate() {
+            if *fee == 0 {
+                continue;
+            }
+            let fee = split_fee(*fee, manager_cut)?;
+            self.protocol_asset_fees[index] = self.protocol_asset_fees[index]
+                .checked_add(fee.owner)
+                .ok_or(BeamsplitterErrors::MathOverflow)?;
+            self.manager_asset_fees[index] = self.manager_asset_fees[index]
+                .checked_add(fee.manager)
+                .ok_or(BeamsplitterErrors::MathOverflow)?;
+        }
+        Ok(())
+    }
+}
+
+/// Human-readable description of a [PrismEtf], so registries can be rebuilt from chain data alone. Text fields are
+/// utf8 padded with null bytes
+#[account]
+#[derive(Copy, Debug, Default)]
+pub struct PrismEtfDescriptor {
+    /// The [PrismEtf] being described
+    pub prism_etf: Pubkey,
+    /// Display name of the ETF
+    pub name: [u8; MAX_DESCRIPTOR_NAME_LENGTH],
+    /// Ticker of the ETF
+    pub symbol: [u8; MAX_DESCRIPTOR_SYMBOL_LENGTH],
+    /// Hash of the full off-chain description, so it can be verified wherever it is hosted
+    pub description_hash: [u8; 32],
+    /// Category tags, only the first `tags_length` are used
+    pub tags: [[u8; MAX_DESCRIPTOR_TAG_LENGTH]; MAX_DESCRIPTOR_TAGS],
+    /// Number of tags in use
+    pub tags_length: u8,
+    /// Unix timestamp the descriptor was created at
+    pub created_at: i64,
+    /// The bump for this PDA account
+    pub bump: u8,
+}
+
+impl PrismEtfDescriptor {
+    /// Replaces the editable fields, failing if any text does not fit
+    pub fn describe(
+        &mut self,
+        name: &str,
+        symbol: &str,
+        description_hash: [u8; 32],
+        tags: &[String],
+    ) -> Result<()> {
+        if tags.len() > MAX_DESCRIPTOR_TAGS {
+            return err!(BeamsplitterErrors::InvalidDescriptor);
+        }
+
+        let mut padded_tags = [[0u8; MAX_DESCRIPTOR_TAG_LENGTH]; MAX_DESCRIPTOR_TAGS];
+        for (padded_tag, tag) in padded_tags.iter_mut().zip(tags) {
+            *padded_tag = pad_bytes(tag).ok_or(BeamsplitterErrors::InvalidDescriptor)?;
+        }
+
+        self.name = pad_bytes(name).ok_or(BeamsplitterErrors::InvalidDescriptor)?;
+        self.symbol = pad_bytes(symbol).ok_or(BeamsplitterErrors::InvalidDescriptor)?;
+        self.description_hash = description_hash;
+        self.tags = padded_tags;
+        self.tags_length = tags.len() as u8;
+        Ok(())
+    }
+}
+
+/// Lets `holder` construct a permissioned [PrismEtf], added by its manager (e.g. once KYC is done)
+#[account]
+#[derive(Copy, Debug, Default)]
+pub struct AllowlistEntry {
+    /// The [PrismEtf] `holder` may construct
+    pub prism_etf: Pubkey,
+    /// The allowlisted wallet
+    pub holder: Pubkey,
+    /// Paid for this account, and is refunded its rent when it is removed
+    pub payer: Pubkey,
+    /// The bump for this PDA account
+    pub bump: u8,
+}
+
+/// Negotiated construction fee of a market maker or integrator on a [PrismEtf]
+#[account]
+#[derive(Copy, Debug, Default)]
+pub struct FeeExemption {
+    /// The [PrismEtf] whose construction fee is lowered
+    pub prism_etf: Pubkey,
+    /// The orderer whose fees are lowered
+    pub exempt: Pubkey,
+    /// Paid for this account, and is refunded its rent when it is removed
+    pub payer: Pubkey,
+    /// Construction fee charged to `exempt` when lower than the ETF's, 0 waives construction fees
+    pub construction_bps: u16,
+    /// The bump for this PDA account
+    pub bump: u8,
+}
+
+/// Registered price feed for a weighted token mint
+#[account]
+#[derive(Copy, Debug, Default)]
+pub struct PriceFeed {
+    /// The mint being priced
+    pub mint: Pubkey,
+    /// The price account for the mint
+    pub feed: Pubkey,
+    /// The format of the `feed` account
+    pub kind: PriceFeedKind,
+    /// Maximum age (s) of a price before it is rejected
+    pub max_staleness: u32,
+    /// Maximum width of the confidence interval, in bps of the price
+    pub max_confidence_bps: u16,
+    /// Decimals of the mint, cached so orders don't need to pass every mint
+    pub decimals: u8,
+    /// The bump for this PDA account
+    pub bump: u8,
+}
+
 #[account]
 #[derive(Copy, Debug, Default)]
 pub struct Beamsplitter {
@@ -114,4 +511,46 @@ pub struct Beamsplitter {
     pub timeout_slots: u16,
     /// The account with rights to autorebalance prism etfs with non NEVER schedule
     pub autorebalancer: Pubkey,
+    /// The only program single asset orders may swap through (default [Pubkey] disables them)
+    pub swap_program: Pubkey,
+    /// Seconds fee and manager changes of finished ETFs are queued for before they can be applied
+    pub change_delay: u32,
+    /// Change delay queued by the owner, so it can't shorten the delay of changes that are already pending
+    pub pending_change_delay: Option<u32>,
+    /// Unix timestamp from which `pending_change_delay` can be applied
+    pub change_delay_effective_at: i64,
+    /// Holder of the FEE_ADMIN role
+    pub fee_admin: Pubkey,
+    /// Holder of the PAUSER role
+    pub pauser: Pubkey,
+    /// Holder of the CURATOR role
+    pub curator: Pubkey,
+    /// If true, no new orders can be started
+    pub paused: bool,
+}
+
+impl Beamsplitter {
+    /// The key holding `role`, default [Pubkey] if it was never granted or was revoked
+    pub fn role_holder(&self, role: Role) -> Pubkey {
+        match role {
+            Role::FEE_ADMIN => self.fee_admin,
+            Role::PAUSER => self.pauser,
+            Role::AUTOREBALANCER => self.autorebalancer,
+            Role::CURATOR => self.curator,
+        }
+    }
+
+    pub fn role_holder_mut(&mut self, role: Role) -> &mut Pubkey {
+        match role {
+            Role::FEE_ADMIN => &mut self.fee_admin,
+            Role::PAUSER => &mut self.pauser,
+            Role::AUTOREBALANCER => &mut self.autorebalancer,
+            Role::CURATOR => &mut self.curator,
+        }
+    }
+
+    /// The owner holds every role
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.role_holder(role)
+    }
 }
@@ -0,0 +1,36 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+/// Invokes `swap_program` with an instruction built from `accounts` and `data`. `authority` is marked as a signer
+/// and signs with `signer_seeds`, so the swap can spend from token accounts it owns
+pub fn invoke_swap<'info>(
+    swap_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+    authority: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: swap_program.key(),
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer || account.key == authority,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+
+    let mut account_infos = accounts.to_vec();
+    account_infos.push(swap_program.clone());
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+    Ok(())
+}
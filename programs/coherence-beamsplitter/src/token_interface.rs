@@ -0,0 +1,384 @@
+use crate::{
+    errors::BeamsplitterErrors,
+    math::{mul_div_ceil, to_u64, BPS_DENOMINATOR},
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        program_option::COption,
+        program_pack::Pack,
+    },
+};
+use anchor_spl::associated_token;
+use std::convert::TryInto;
+
+/// SPL Token-2022. Its accounts and instructions are read and built by hand because anchor-spl 0.23 only knows the
+/// original token program, and the spl-token-2022 crate requires a newer solana-program than anchor-lang 0.23 allows
+pub mod token_2022 {
+    use anchor_lang::declare_id;
+    declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+// Instruction index of TokenInstruction::TransferChecked, the same in both token programs
+const TRANSFER_CHECKED: u8 = 12;
+
+// Token-2022 mints and accounts keep the original layouts, padded to the account length and followed by an account
+// type byte and then their extensions as (type: u16, length: u16, value) entries
+const ACCOUNT_TYPE_OFFSET: usize = spl_token::state::Account::LEN;
+const EXTENSIONS_OFFSET: usize = ACCOUNT_TYPE_OFFSET + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// ExtensionType values of the mint extensions Beamsplitter reads or rejects
+const TRANSFER_FEE_CONFIG: u16 = 1;
+const NON_TRANSFERABLE: u16 = 9;
+const PERMANENT_DELEGATE: u16 = 12;
+const TRANSFER_HOOK: u16 = 14;
+
+// TransferFeeConfig is two optional authorities and the withheld amount, followed by the older and newer fees
+const TRANSFER_FEES_OFFSET: usize = 32 + 32 + 8;
+const TRANSFER_FEE_LENGTH: usize = 8 + 8 + 2;
+
+/// A transfer fee of a Token-2022 mint, effective from `epoch`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    fn unpack(data: &[u8]) -> TransferFee {
+        TransferFee {
+            epoch: u64::from_le_bytes(data[..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            basis_points: u16::from_le_bytes(data[16..18].try_into().unwrap()),
+        }
+    }
+
+    /// The fee Token-2022 withholds from a transfer of `amount`, rounded up and capped at `maximum_fee`
+    pub fn fee(&self, amount: u64) -> Result<u64> {
+        let fee = mul_div_ceil(amount.into(), self.basis_points.into(), BPS_DENOMINATOR)?;
+        Ok(to_u64(fee)?.min(self.maximum_fee))
+    }
+
+    /// The smallest transfer that still credits `amount` once the fee is withheld
+    pub fn gross_amount(&self, amount: u64) -> Result<u64> {
+        if amount == 0 || self.basis_points == 0 {
+            return Ok(amount);
+        }
+
+        let capped = amount
+            .checked_add(self.maximum_fee)
+            .ok_or(BeamsplitterErrors::MathOverflow)?;
+        if u128::from(self.basis_points) >= BPS_DENOMINATOR {
+            return Ok(capped);
+        }
+
+        // gross - ceil(gross * bps / 10^4) >= amount whenever gross >= amount * 10^4 / (10^4 - bps)
+        let uncapped = mul_div_ceil(
+            amount.into(),
+            BPS_DENOMINATOR,
+            BPS_DENOMINATOR - u128::from(self.basis_points),
+        )?;
+        Ok(to_u64(uncapped)?.min(capped))
+    }
+}
+
+/// A mint of either token program, with what Beamsplitter needs of its extensions
+#[derive(Clone, Copy, Debug)]
+pub struct InterfaceMint {
+    pub key: Pubkey,
+    /// The token program that owns the mint
+    pub token_program: Pubkey,
+    pub mint_authority: COption<Pubkey>,
+    pub decimals: u8,
+    /// The older and newer fees of a TransferFeeConfig extension
+    pub transfer_fees: Option<(TransferFee, TransferFee)>,
+}
+
+impl InterfaceMint {
+    /// The transfer fee in effect during `epoch`, which is none for mints without a TransferFeeConfig
+    pub fn transfer_fee(&self, epoch: u64) -> TransferFee {
+        match self.transfer_fees {
+            Some((_, newer)) if epoch >= newer.epoch => newer,
+            Some((older, _)) => older,
+            None => TransferFee::default(),
+        }
+    }
+}
+
+/// Loads a mint of either token program. Token-2022 mints that are non-transferable, have a permanent delegate or a
+/// transfer hook are rejected, as Beamsplitter could not move them, could have them taken from the ETF or could not
+/// pass their hook's accounts
+pub fn load_mint(info: &AccountInfo) -> Result<InterfaceMint> {
+    if info.owner != &spl_token::ID && info.owner != &token_2022::ID {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+
+    let data = info.try_borrow_data()?;
+    if data.len() < spl_token::state::Mint::LEN {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    let mint = spl_token::state::Mint::unpack_from_slice(&data[..spl_token::state::Mint::LEN])?;
+    if !mint.is_initialized {
+        return Err(ErrorCode::AccountNotInitialized.into());
+    }
+
+    Ok(InterfaceMint {
+        key: info.key(),
+        token_program: *info.owner,
+        mint_authority: mint.mint_authority,
+        decimals: mint.decimals,
+        transfer_fees: mint_transfer_fees(&data)?,
+    })
+}
+
+/// Reads the transfer fees of a Token-2022 mint's extensions, failing on the extensions Beamsplitter rejects
+fn mint_transfer_fees(data: &[u8]) -> Result<Option<(TransferFee, TransferFee)>> {
+    if data.len() == spl_token::state::Mint::LEN {
+        return Ok(None);
+    }
+    if data.len() < EXTENSIONS_OFFSET || data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+
+    let mut transfer_fees = None;
+    let mut offset = EXTENSIONS_OFFSET;
+    while offset + 4 <= data.len() {
+        let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+
+        match extension_type {
+            // The rest of the account is unused
+            0 => break,
+            TRANSFER_FEE_CONFIG => {
+                let fees = value
+                    .get(TRANSFER_FEES_OFFSET..TRANSFER_FEES_OFFSET + 2 * TRANSFER_FEE_LENGTH)
+                    .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+                transfer_fees = Some((
+                    TransferFee::unpack(&fees[..TRANSFER_FEE_LENGTH]),
+                    TransferFee::unpack(&fees[TRANSFER_FEE_LENGTH..]),
+                ));
+            }
+            NON_TRANSFERABLE | PERMANENT_DELEGATE | TRANSFER_HOOK => {
+                return err!(BeamsplitterErrors::IncompatibleMint);
+            }
+            _ => {}
+        }
+        offset += 4 + length;
+    }
+    Ok(transfer_fees)
+}
+
+/// The amount in `info`, after checking it is a token account of `mint` owned by `owner`. Transfer fees withheld in a
+/// Token-2022 account are kept apart from its amount, so this is only what the owner can move
+pub fn token_amount(info: &AccountInfo, mint: &InterfaceMint, owner: &Pubkey) -> Result<u64> {
+    if info.owner != &mint.token_program {
+        return Err(ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+
+    let data = info.try_borrow_data()?;
+    if data.len() < spl_token::state::Account::LEN {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    let account =
+        spl_token::state::Account::unpack_from_slice(&data[..spl_token::state::Account::LEN])?;
+    if account.mint != mint.key || account.owner != *owner {
+        return err!(BeamsplitterErrors::WrongTokenAccount);
+    }
+    Ok(account.amount)
+}
+
+/// Like [token_amount], but `info` must also be `owner`'s associated token account
+pub fn associated_token_amount(
+    info: &AccountInfo,
+    mint: &InterfaceMint,
+    owner: &Pubkey,
+) -> Result<u64> {
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            &owner.to_bytes(),
+            &mint.token_program.to_bytes(),
+            &mint.key.to_bytes(),
+        ],
+        &associated_token::ID,
+    );
+    if info.key() != address {
+        return err!(BeamsplitterErrors::WrongTokenAccount);
+    }
+    token_amount(info, mint, owner)
+}
+
+/// Transfers `amount` with TransferChecked, which Token-2022 requires for mints with a transfer fee. `authority`
+/// signs with `signer_seeds`
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = vec![TRANSFER_CHECKED];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(decimals);
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: vec![
+            AccountMeta::new(from.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new(to.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+        ],
+        data,
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            from.clone(),
+            mint.clone(),
+            to.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extension(extension_type: u16, value: &[u8]) -> Vec<u8> {
+        let mut data = extension_type.to_le_bytes().to_vec();
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+        data
+    }
+
+    fn mint_data(extensions: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = vec![0; ACCOUNT_TYPE_OFFSET];
+        data.push(ACCOUNT_TYPE_MINT);
+        for extension in extensions {
+            data.extend_from_slice(extension);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+        let mut value = vec![0; TRANSFER_FEES_OFFSET];
+        for fee in [older, newer] {
+            value.extend_from_slice(&fee.epoch.to_le_bytes());
+            value.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+            value.extend_from_slice(&fee.basis_points.to_le_bytes());
+        }
+        extension(TRANSFER_FEE_CONFIG, &value)
+    }
+
+    #[test]
+    fn transfer_fee_rounding() {
+        let fee = TransferFee {
+            epoch: 0,
+            maximum_fee: 1_000,
+            basis_points: 100,
+        };
+        assert_eq!(fee.fee(0).unwrap(), 0);
+        assert_eq!(fee.fee(1).unwrap(), 1);
+        assert_eq!(fee.fee(10_000).unwrap(), 100);
+        assert_eq!(fee.fee(10_001).unwrap(), 101);
+        assert_eq!(fee.fee(1_000_000).unwrap(), 1_000);
+        assert_eq!(TransferFee::default().fee(u64::MAX).unwrap(), 0);
+    }
+
+    #[test]
+    fn gross_amount_credits_at_least_the_amount() {
+        for fee in [
+            TransferFee::default(),
+            TransferFee {
+                epoch: 0,
+                maximum_fee: 1_000,
+                basis_points: 100,
+            },
+            TransferFee {
+                epoch: 0,
+                maximum_fee: 7,
+                basis_points: 9_999,
+            },
+            TransferFee {
+                epoch: 0,
+                maximum_fee: 5,
+                basis_points: 10_000,
+            },
+        ] {
+            for amount in [0, 1, 99, 10_000, 123_456, 1_000_000_000] {
+                let gross = fee.gross_amount(amount).unwrap();
+                assert!(gross - fee.fee(gross).unwrap() >= amount);
+                // One less would not be enough
+                if gross > amount {
+                    assert!(gross - 1 - fee.fee(gross - 1).unwrap() < amount);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reads_transfer_fees() {
+        assert_eq!(
+            mint_transfer_fees(&[0; spl_token::state::Mint::LEN]).unwrap(),
+            None
+        );
+
+        let older = TransferFee {
+            epoch: 0,
+            maximum_fee: 10,
+            basis_points: 50,
+        };
+        let newer = TransferFee {
+            epoch: 5,
+            maximum_fee: 20,
+            basis_points: 100,
+        };
+        let data = mint_data(&[transfer_fee_config(older, newer)]);
+        assert_eq!(mint_transfer_fees(&data).unwrap(), Some((older, newer)));
+
+        let mut mint = InterfaceMint {
+            key: Pubkey::default(),
+            token_program: token_2022::ID,
+            mint_authority: COption::None,
+            decimals: 6,
+            transfer_fees: Some((older, newer)),
+        };
+        assert_eq!(mint.transfer_fee(4), older);
+        assert_eq!(mint.transfer_fee(5), newer);
+        mint.transfer_fees = None;
+        assert_eq!(mint.transfer_fee(5), TransferFee::default());
+    }
+
+    #[test]
+    fn rejects_incompatible_extensions() {
+        for extension_type in [NON_TRANSFERABLE, PERMANENT_DELEGATE, TRANSFER_HOOK] {
+            let data = mint_data(&[extension(extension_type, &[0; 32])]);
+            assert!(mint_transfer_fees(&data).is_err());
+        }
+
+        // Other extensions are skipped over
+        let data = mint_data(&[extension(3, &[0; 32]), extension(10, &[0; 52])]);
+        assert_eq!(mint_transfer_fees(&data).unwrap(), None);
+
+        // Extensions must fit in the account
+        let mut data = mint_data(&[extension(3, &[0; 32])]);
+        data.truncate(data.len() - 1);
+        assert!(mint_transfer_fees(&data).is_err());
+    }
+}
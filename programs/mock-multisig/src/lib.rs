@@ -0,0 +1,65 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+    },
+};
+
+declare_id!("8uSoS1Fp2iCQCzBwUqUitKWLme5NXuJoFriMBvdQdGC2");
+
+/// Stand-in for a multisig or governance program, used by the tests to drive Beamsplitter with a PDA authority.
+/// There are no members or approvals, anyone may execute
+#[program]
+pub mod mock_multisig {
+    use super::*;
+
+    /// Invokes `program` with `data` and the remaining accounts, signing as the multisig's PDA
+    pub fn execute(ctx: Context<Execute>, data: Vec<u8>) -> Result<()> {
+        let multisig_signer = ctx.accounts.multisig_signer.key();
+
+        let accounts = ctx
+            .remaining_accounts
+            .iter()
+            .map(|account| {
+                let is_signer = account.is_signer || account.key() == multisig_signer;
+                if account.is_writable {
+                    AccountMeta::new(account.key(), is_signer)
+                } else {
+                    AccountMeta::new_readonly(account.key(), is_signer)
+                }
+            })
+            .collect();
+
+        let instruction = Instruction {
+            program_id: ctx.accounts.program.key(),
+            accounts,
+            data,
+        };
+
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.program.to_account_info());
+
+        let bump = *ctx
+            .bumps
+            .get("multisig_signer")
+            .ok_or(ProgramError::InvalidSeeds)?;
+        let seeds = &[MULTISIG_SIGNER_SEED, &[bump]];
+
+        invoke_signed(&instruction, &account_infos, &[&seeds[..]])?;
+        Ok(())
+    }
+}
+
+const MULTISIG_SIGNER_SEED: &[u8] = b"MultisigSigner" as &[u8];
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    /// The PDA that holds whatever authority the multisig was given
+    #[account(seeds = [b"MultisigSigner".as_ref()], bump)]
+    pub multisig_signer: AccountInfo<'info>,
+
+    /// The program being invoked
+    #[account(executable)]
+    pub program: AccountInfo<'info>,
+}
@@ -1,21 +1,41 @@
 import type { TransactionEnvelope } from "@saberhq/solana-contrib";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createInitMintInstructions,
+  getATAAddress,
   getMintInfo,
   getOrCreateATA,
+  TOKEN_PROGRAM_ID,
 } from "@saberhq/token-utils";
 import type { PublicKey } from "@solana/web3.js";
-import { Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  Keypair,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
 import BN from "bn.js";
 
 import type { CoherenceLoader } from "./CoherenceLoader";
-import { generateBeamsplitterAddress, generatePrismEtfAddress } from "./pda";
+import { TOKEN_2022_PROGRAM_ID } from "./constants";
+import {
+  generateBeamsplitterAddress,
+  generateFeeVaultAddress,
+  generatePrismEtfAddress,
+} from "./pda";
 import type { BeamsplitterData, WeightedToken } from "./types";
 import { WEIGHTED_TOKENS_SIZE } from "./types";
+import {
+  createToken2022AtaInstruction,
+  getToken2022AtaAddress,
+} from "./utils";
 
 // Number of decimals used by prism etf by default
 export const PRISM_ETF_DECIMALS = 9;
 
+// Weights are whole asset tokens per whole prism etf token with this many decimals of precision
+export const WEIGHT_DECIMALS = 9;
+
 export class CoherenceBeamsplitter {
   constructor(
     readonly loader: CoherenceLoader,
@@ -83,9 +103,11 @@ export class CoherenceBeamsplitter {
   async initPrismEtf({
     prismEtfMint,
     manager = this.loader.getUserPublicKey(),
+    payer = this.loader.getUserPublicKey(),
   }: {
     prismEtfMint?: PublicKey;
     manager?: PublicKey;
+    payer?: PublicKey;
   }): Promise<[TransactionEnvelope, PublicKey, PublicKey, PublicKey, number]> {
     const weightedTokensKP = Keypair.generate();
 
@@ -134,6 +156,7 @@ export class CoherenceBeamsplitter {
         prismEtfMint,
         weightedTokens: weightedTokensKP.publicKey,
         manager,
+        payer,
         beamsplitter: this.beamsplitter,
         systemProgram: SystemProgram.programId,
       },
@@ -170,16 +193,58 @@ export class CoherenceBeamsplitter {
 
     for (const token of weightedTokens) {
       const { mint } = token;
+      const mintAccount =
+        await this.loader.provider.connection.getAccountInfo(mint);
+      const isToken2022 =
+        mintAccount?.owner.equals(TOKEN_2022_PROGRAM_ID) ?? false;
 
       // Setup ATA's for PDA
-      const { instruction: createATATx } = await getOrCreateATA({
-        provider: this.loader.provider,
-        mint,
-        owner: prismEtfPda,
-      });
+      if (isToken2022) {
+        // getOrCreateATA only derives accounts of the original token program
+        const ata = await getToken2022AtaAddress(mint, prismEtfPda);
+        if (
+          shouldCreateAtas &&
+          (await this.loader.provider.connection.getAccountInfo(ata)) === null
+        ) {
+          pushTokenTxChunk.append(
+            createToken2022AtaInstruction({
+              payer: this.loader.getUserPublicKey(),
+              ata,
+              owner: prismEtfPda,
+              mint,
+            })
+          );
+        }
+      } else {
+        const { instruction: createATATx } = await getOrCreateATA({
+          provider: this.loader.provider,
+          mint,
+          owner: prismEtfPda,
+        });
+
+        if (shouldCreateAtas && createATATx !== null) {
+          pushTokenTxChunk.append(createATATx);
+        }
+      }
 
-      if (shouldCreateAtas && createATATx !== null) {
-        pushTokenTxChunk.append(createATATx);
+      // The program reads the mint, and the PrismEtf of mints that belong to a Prism ETF, to check nesting
+      const remainingAccounts = [
+        { pubkey: mint, isSigner: false, isWritable: false },
+      ];
+      // Prism ETF mints, the only nested ones, always belong to the original token program
+      const mintData = isToken2022
+        ? null
+        : await getMintInfo(this.loader.provider, mint);
+      if (mintData?.mintAuthority?.equals(this.beamsplitter)) {
+        const [nestedPrismEtfPda] = await generatePrismEtfAddress(
+          mint,
+          this.beamsplitter
+        );
+        remainingAccounts.push({
+          pubkey: nestedPrismEtfPda,
+          isSigner: false,
+          isWritable: false,
+        });
       }
 
       pushTokenTxChunk.append(
@@ -192,6 +257,7 @@ export class CoherenceBeamsplitter {
             manager,
             systemProgram: SystemProgram.programId,
           },
+          remainingAccounts,
         })
       );
     }
@@ -219,23 +285,16 @@ export class CoherenceBeamsplitter {
       );
     }
 
-    const beamsplitterOwner = this.beamsplitterData.owner;
-
-    const owners = [
-      beamsplitterOwner,
-      ...(beamsplitterOwner.equals(manager) ? [] : [manager]),
-    ];
-
-    for (const owner of owners) {
-      const { instruction: createOwnerAtaTx } = await getOrCreateATA({
-        provider: this.loader.provider,
-        mint: prismEtfMint,
-        owner,
-      });
-
-      if (shouldCreateAtas && createOwnerAtaTx !== null) {
-        finalizeTx.append(createOwnerAtaTx);
-      }
+    // Fees of every order accrue in the fee vault, so it is created along with the finished ETF
+    if (shouldCreateAtas) {
+      finalizeTx.append(
+        ...(
+          await this.initFeeVault({
+            prismEtfMint,
+            prismEtfPda,
+          })
+        ).instructions
+      );
     }
 
     finalizeTx.append(
@@ -252,6 +311,40 @@ export class CoherenceBeamsplitter {
     return finalizeTx;
   }
 
+  // Create the vault a Prism ETF's fees accrue in, required before its orders can be finalized
+  async initFeeVault({
+    prismEtfMint,
+    prismEtfPda,
+    payer = this.loader.getUserPublicKey(),
+  }: {
+    prismEtfMint: PublicKey;
+    prismEtfPda: PublicKey;
+    payer?: PublicKey;
+  }): Promise<TransactionEnvelope> {
+    const [feeVault, bump] = await generateFeeVaultAddress(prismEtfPda);
+    const feeVaultEtfAta = await getATAAddress({
+      mint: prismEtfMint,
+      owner: feeVault,
+    });
+
+    return this.loader.makeProviderEnvelope([
+      this.loader.program.instruction.initFeeVault(bump, {
+        accounts: {
+          prismEtfMint,
+          payer,
+          feeVaultEtfAta,
+          feeVault,
+          prismEtf: prismEtfPda,
+          beamsplitter: this.beamsplitter,
+          rent: SYSVAR_RENT_PUBKEY,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        },
+      }),
+    ]);
+  }
+
   setOwner({ newOwner }: { newOwner: PublicKey }): TransactionEnvelope {
     return this.loader.makeProviderEnvelope([
       this.loader.program.instruction.setOwner({
@@ -264,6 +357,34 @@ export class CoherenceBeamsplitter {
     ]);
   }
 
+  // Queues a new change delay, which can only be applied once the current delay has passed
+  setChangeDelay({
+    changeDelay,
+  }: {
+    changeDelay: number;
+  }): TransactionEnvelope {
+    return this.loader.makeProviderEnvelope([
+      this.loader.program.instruction.setChangeDelay(changeDelay, {
+        accounts: {
+          owner: this.loader.getUserPublicKey(),
+          beamsplitter: this.beamsplitter,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  applyChangeDelay(): TransactionEnvelope {
+    return this.loader.makeProviderEnvelope([
+      this.loader.program.instruction.applyChangeDelay({
+        accounts: {
+          beamsplitter: this.beamsplitter,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
   async fetchBeamsplitterData(
     key: PublicKey
   ): Promise<BeamsplitterData | null> {
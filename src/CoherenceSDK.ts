@@ -18,7 +18,7 @@ import type {
 import { CoherenceClient } from "./CoherenceClient";
 import { CoherenceLoader } from "./CoherenceLoader";
 import { generatePrismEtfAddress } from "./pda";
-import { PrismEtf } from "./PrismEtf";
+import { getNativeWeight, PrismEtf } from "./PrismEtf";
 import { TxTag } from "./TxTag";
 import type { WeightedToken } from "./types";
 import { enumLikeToString, OrderStatus, OrderType } from "./types";
@@ -536,7 +536,10 @@ export class CoherenceSDK extends CoherenceClient {
         ),
         inputMint,
         outputMint: mint,
-        nativeWeight: weight.toNumber(),
+        nativeWeight: getNativeWeight(
+          weight,
+          prismEtf.getAssetDecimals(mint)
+        ).toNumber(),
         slippage,
       };
     });
@@ -577,7 +580,10 @@ export class CoherenceSDK extends CoherenceClient {
     );
 
     const sources: SourceProps[] = weightedTokens.map(({ mint, weight }) => {
-      const nativeWeight = weight.toNumber();
+      const nativeWeight = getNativeWeight(
+        weight,
+        prismEtf.getAssetDecimals(mint)
+      ).toNumber();
       return {
         nativeAmount: Math.floor(decimalAmount * nativeWeight),
         inputMint: mint,
@@ -7,7 +7,11 @@ import {
   TOKEN_PROGRAM_ID,
 } from "@saberhq/token-utils";
 import { Token, u64 } from "@solana/spl-token";
-import type { Signer, TransactionInstruction } from "@solana/web3.js";
+import type {
+  AccountMeta,
+  Signer,
+  TransactionInstruction,
+} from "@solana/web3.js";
 import {
   Keypair,
   PublicKey,
@@ -20,19 +24,30 @@ import BN from "bn.js";
 
 import type { UserPrismEtf } from "./CoherenceApi";
 import type { CoherenceBeamsplitter } from "./CoherenceBeamsplitter";
-import { generateOrderStateAddress, generatePrismEtfAddress } from "./pda";
+import { WEIGHT_DECIMALS } from "./CoherenceBeamsplitter";
+import {
+  generateAllowlistEntryAddress,
+  generateFeeVaultAddress,
+  generateOrderStateAddress,
+  generatePriceFeedAddress,
+  generatePrismEtfAddress,
+} from "./pda";
 import type {
   BeamsplitterData,
+  OrderQuote,
   OrderStateData,
   PrismEtfData,
   TransferredTokensData,
   WeightedTokensData,
 } from "./types";
 import {
+  BPS_DENOMINATOR,
   enumLikeToString,
+  FeeMode,
   OrderType,
   stringToEnumLike,
   TRANSFERRED_TOKENS_SIZE,
+  WeightMode,
 } from "./types";
 
 export type MintToDecimal = { [key: string]: number };
@@ -57,6 +72,24 @@ const getDecimalsForMints = async (
   return mintToDecimal;
 };
 
+// Asset native units per whole prism etf token for a weight (mirrors program's weighted_amount with a whole etf token)
+export const getNativeWeight = (weight: BN, assetDecimals: number): BN =>
+  weight
+    .mul(new BN(10).pow(new BN(assetDecimals)))
+    .div(new BN(10).pow(new BN(WEIGHT_DECIMALS)));
+
+// Asset native units backing etfNativeAmount of prism etf (mirrors program's weighted_amount, rounded down)
+export const getWeightedAmount = (
+  etfNativeAmount: BN,
+  weight: BN,
+  prismEtfDecimals: number,
+  assetDecimals: number
+): BN =>
+  etfNativeAmount
+    .mul(weight)
+    .mul(new BN(10).pow(new BN(assetDecimals)))
+    .div(new BN(10).pow(new BN(prismEtfDecimals + WEIGHT_DECIMALS)));
+
 export class PrismEtf {
   transferredTokensAcct: PublicKey | undefined;
 
@@ -74,7 +107,9 @@ export class PrismEtf {
     readonly orderStateData: OrderStateData | null,
     transferredTokensAcct: PublicKey | undefined,
     readonly transferredTokensData: TransferredTokensData | null,
-    readonly mintToDecimal: MintToDecimal
+    readonly mintToDecimal: MintToDecimal,
+    readonly feeVaultPda: PublicKey,
+    readonly feeVaultBump: number
   ) {
     this.transferredTokensAcct = transferredTokensAcct;
   }
@@ -129,6 +164,10 @@ export class PrismEtf {
         .map((t) => t.mint) ?? []
     );
 
+    const [feeVaultPda, feeVaultBump] = await generateFeeVaultAddress(
+      prismEtfPda
+    );
+
     const prismEtfMintInfo = await getMintInfo(
       beamsplitter.loader.provider,
       prismEtfMint
@@ -148,10 +187,94 @@ export class PrismEtf {
       orderStateData,
       transferredTokensAcct,
       transferredTokensData,
-      mintToDecimal
+      mintToDecimal,
+      feeVaultPda,
+      feeVaultBump
     );
   }
 
+  // Create the vault this etf's fees accrue in, required before its orders can be finalized
+  async initFeeVault(): Promise<TransactionEnvelope> {
+    return this.beamsplitter.initFeeVault({
+      prismEtfMint: this.prismEtfMint,
+      prismEtfPda: this.prismEtfPda,
+    });
+  }
+
+  // Construction fee (bps) of an order of etfNativeAmount (mirrors program's construction_bps_for)
+  getConstructionBps(etfNativeAmount: BN): number {
+    if (this.prismEtfData === null) {
+      throw new Error("You must create the prismEtf first.");
+    }
+
+    let constructionBps = this.prismEtfData.constructionBps;
+    for (const feeTier of this.prismEtfData.feeTiers.slice(
+      0,
+      this.prismEtfData.feeTiersLength
+    )) {
+      if (feeTier.minAmount.gt(etfNativeAmount)) {
+        break;
+      }
+      constructionBps = feeTier.constructionBps;
+    }
+    return constructionBps;
+  }
+
+  // How finalizeOrder splits a CONSTRUCTION of etfNativeAmount between orderer and fee vault (mirrors program's
+  // split_construction_fee). Fees are charged in the assets instead in UNDERLYING fee mode
+  getConstructionFeeSplit(etfNativeAmount: BN): {
+    orderer: BN;
+    owner: BN;
+    manager: BN;
+  } {
+    if (this.prismEtfData === null) {
+      throw new Error("You must create the prismEtf first.");
+    }
+
+    const constructionBps = this.getConstructionBps(etfNativeAmount);
+    if (
+      enumLikeToString(this.prismEtfData.feeMode) === FeeMode.UNDERLYING ||
+      constructionBps === 0
+    ) {
+      return { orderer: etfNativeAmount, owner: new BN(0), manager: new BN(0) };
+    }
+
+    const managerCut = this.prismEtfData.managerCut;
+    const feePortion = etfNativeAmount
+      .mul(new BN(constructionBps))
+      .div(new BN(BPS_DENOMINATOR));
+    let manager = feePortion
+      .mul(new BN(managerCut))
+      .div(new BN(BPS_DENOMINATOR));
+    let owner = feePortion.sub(manager);
+    if (managerCut < BPS_DENOMINATOR) {
+      owner = BN.max(owner, new BN(1));
+    }
+    if (managerCut > 0) {
+      manager = BN.max(manager, new BN(1));
+    }
+
+    return {
+      orderer: etfNativeAmount.sub(owner).sub(manager),
+      owner,
+      manager,
+    };
+  }
+
+  // Fee charged on top of assetAmount by cohere in UNDERLYING fee mode (mirrors program's split_asset_fee)
+  getAssetFee(assetAmount: BN, etfNativeAmount: BN): BN {
+    if (
+      this.prismEtfData === null ||
+      enumLikeToString(this.prismEtfData.feeMode) !== FeeMode.UNDERLYING
+    ) {
+      return new BN(0);
+    }
+
+    return assetAmount
+      .mul(new BN(this.getConstructionBps(etfNativeAmount)))
+      .div(new BN(BPS_DENOMINATOR));
+  }
+
   async initTransferredTokens({
     transferredTokensKP = Keypair.generate(),
   }: {
@@ -246,6 +369,24 @@ export class PrismEtf {
       initOrderStateEnvelope.append(createATATx);
     }
 
+    // Constructing a permissioned etf needs the orderer's allowlist entry
+    const remainingAccounts: AccountMeta[] = [];
+    if (type === OrderType.CONSTRUCTION && this.prismEtfData.permissioned) {
+      const [allowlistEntry] = await generateAllowlistEntryAddress(
+        this.prismEtfPda,
+        this.getUserPublicKey()
+      );
+      remainingAccounts.push({
+        pubkey: allowlistEntry,
+        isSigner: false,
+        isWritable: false,
+      });
+    }
+
+    if (type === OrderType.CONSTRUCTION && this.isPercentageMode()) {
+      remainingAccounts.push(...(await this.getPriceFeedAccounts()));
+    }
+
     initOrderStateEnvelope.append(
       this.getProgramInstructions().startOrder(stringToEnumLike(type), amount, {
         accounts: {
@@ -263,12 +404,21 @@ export class PrismEtf {
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         },
+        remainingAccounts,
       })
     );
 
     return initOrderStateEnvelope;
   }
 
+  getAssetDecimals(mint: PublicKey): number {
+    const decimals = this.mintToDecimal[mint.toString()];
+    if (decimals === undefined) {
+      throw new Error(`Could not load decimals for ${mint.toString()}`);
+    }
+    return decimals;
+  }
+
   async cohere({
     orderStateAmount,
     shouldCreateAtas = true, // If false, the instruction doesn't setup Ata's for you (careful with this, it may fail if you don't do it)
@@ -292,6 +442,12 @@ export class PrismEtf {
       throw new Error("User wallet is not connected.");
     }
 
+    // PERCENTAGE amounts are priced and snapshotted when the order starts
+    const snapshotAmounts = this.transferredTokensData?.amounts;
+    if (this.isPercentageMode() && snapshotAmounts === undefined) {
+      throw new Error("Transferred tokens was not initalized.");
+    }
+
     const { weightedTokens, length } = this.weightedTokensData;
     const constructTxChunks: TransactionEnvelope[] = [];
 
@@ -319,10 +475,33 @@ export class PrismEtf {
         constructEnvelope.append(createOrdererAta);
       }
 
-      const approvedAmount = orderStateAmount
-        .mul(new BN(weight))
-        .div(new BN(10 ** this.prismEtfDecimals))
-        .add(new BN(1));
+      const { address: feeVaultTransferAta, instruction: createFeeVaultAta } =
+        await this.getOrCreateATA({
+          mint,
+          owner: this.feeVaultPda,
+        });
+
+      const requiredAmount =
+        snapshotAmounts !== undefined && this.isPercentageMode()
+          ? new BN(snapshotAmounts[i])
+          : getWeightedAmount(
+              orderStateAmount,
+              new BN(weight),
+              this.prismEtfDecimals,
+              this.getAssetDecimals(mint)
+            );
+      const assetFee = this.getAssetFee(requiredAmount, orderStateAmount);
+
+      // The fee vault only receives assets in UNDERLYING fee mode
+      if (
+        shouldCreateAtas &&
+        createFeeVaultAta !== null &&
+        !assetFee.isZero()
+      ) {
+        constructEnvelope.append(createFeeVaultAta);
+      }
+
+      const approvedAmount = requiredAmount.add(new BN(1)).add(assetFee);
 
       constructEnvelope.append(
         Token.createApproveInstruction(
@@ -349,6 +528,8 @@ export class PrismEtf {
               ordererTransferAta,
               beamsplitterTransferAta: prismEtfTransferAta,
               beamsplitter: this.getBeamsplitter(),
+              feeVault: this.feeVaultPda,
+              feeVaultTransferAta,
               rent: SYSVAR_RENT_PUBKEY,
               clock: SYSVAR_CLOCK_PUBKEY,
               associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -411,6 +592,12 @@ export class PrismEtf {
         constructEnvelope.append(createOrdererAta);
       }
 
+      // Only read if cohere took an UNDERLYING fee for this asset, which is then refunded
+      const feeVaultTransferAta = await getATAAddress({
+        mint,
+        owner: this.feeVaultPda,
+      });
+
       constructTxChunks.push(
         constructEnvelope.append(
           this.getProgramInstructions().decohere(i, {
@@ -425,6 +612,8 @@ export class PrismEtf {
               ordererTransferAta,
               beamsplitterTransferAta: prismEtfTransferAta,
               beamsplitter: this.getBeamsplitter(),
+              feeVault: this.feeVaultPda,
+              feeVaultTransferAta,
               rent: SYSVAR_RENT_PUBKEY,
               clock: SYSVAR_CLOCK_PUBKEY,
               associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -466,40 +655,19 @@ export class PrismEtf {
       throw new Error("User wallet is not connected.");
     }
 
-    const beamsplitterOwner = beamsplitterData.owner;
-
-    const { address: ownerEtfAta, instruction: createOwnerAtaTx } =
-      await this.getOrCreateATA({
-        mint: this.prismEtfMint,
-        owner: beamsplitterOwner,
-      });
-
-    if (
-      shouldCreateAtas &&
-      createOwnerAtaTx !== null &&
-      !beamsplitterOwner.equals(this.getUserPublicKey())
-    ) {
-      resultEnvelope.append(createOwnerAtaTx);
-    }
-
-    const { address: managerEtfAta, instruction: createManagerEtfAtaTx } =
+    const { address: ordererEtfAta, instruction: createOrdererEtfAtaTx } =
       await this.getOrCreateATA({
         mint: this.prismEtfMint,
-        owner: this.prismEtfData.manager,
       });
 
-    if (
-      shouldCreateAtas &&
-      createManagerEtfAtaTx !== null &&
-      !this.prismEtfData.manager.equals(this.getUserPublicKey()) &&
-      !beamsplitterOwner.equals(this.prismEtfData.manager)
-    ) {
-      resultEnvelope.append(createManagerEtfAtaTx);
+    if (shouldCreateAtas && createOrdererEtfAtaTx !== null) {
+      resultEnvelope.append(createOrdererEtfAtaTx);
     }
 
-    const transferredTokensAta = await getATAAddress({
+    // Fees are minted to the fee vault, created along with its etf account by initFeeVault
+    const feeVaultEtfAta = await getATAAddress({
       mint: this.prismEtfMint,
-      owner: this.getUserPublicKey(),
+      owner: this.feeVaultPda,
     });
 
     resultEnvelope.append(
@@ -510,11 +678,9 @@ export class PrismEtf {
           orderState: this.orderStatePda,
           transferredTokens: this.transferredTokensAcct,
           orderer: this.getUserPublicKey(),
-          ordererEtfAta: transferredTokensAta,
-          owner: beamsplitterOwner,
-          ownerEtfAta,
-          manager: this.prismEtfData.manager,
-          managerEtfAta,
+          ordererEtfAta,
+          feeVault: this.feeVaultPda,
+          feeVaultEtfAta,
           beamsplitter: this.getBeamsplitter(),
           rent: SYSVAR_RENT_PUBKEY,
           weightedTokens: this.prismEtfData.weightedTokens,
@@ -528,6 +694,68 @@ export class PrismEtf {
     return resultEnvelope;
   }
 
+  // Quote an order without sending it, by simulating quote_order and decoding the quote it returns
+  async quoteOrder({
+    type,
+    amount,
+  }: {
+    type: OrderType;
+    amount: BN;
+  }): Promise<OrderQuote> {
+    if (this.prismEtfData === null) {
+      throw new Error("You must create the prismEtf first.");
+    }
+
+    if (this.weightedTokensData === null) {
+      throw new Error("Weighted tokens was not initalized.");
+    }
+
+    // QUANTITY quotes read each mint's decimals, PERCENTAGE quotes price each mint
+    const remainingAccounts: AccountMeta[] = this.isPercentageMode()
+      ? await this.getPriceFeedAccounts()
+      : this.weightedTokensData.weightedTokens
+          .slice(0, this.weightedTokensData.length)
+          .map(({ mint }) => ({
+            pubkey: mint,
+            isSigner: false,
+            isWritable: false,
+          }));
+
+    const simulation = await this.makeProviderEnvelope([
+      this.getProgramInstructions().quoteOrder(stringToEnumLike(type), amount, {
+        accounts: {
+          prismEtfMint: this.prismEtfMint,
+          prismEtf: this.prismEtfPda,
+          beamsplitter: this.getBeamsplitter(),
+          weightedTokens: this.prismEtfData.weightedTokens,
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+        remainingAccounts,
+      }),
+    ]).simulate();
+
+    if (simulation.value.err) {
+      throw new Error(
+        `Could not quote order: ${JSON.stringify(simulation.value.err)}`
+      );
+    }
+
+    // The runtime logs return data as "Program return: <program id> <base64 data>"
+    const programId = this.beamsplitter.loader.program.programId;
+    const returnPrefix = `Program return: ${programId.toString()} `;
+    const returnLog = simulation.value.logs?.find((log) =>
+      log.startsWith(returnPrefix)
+    );
+    if (returnLog === undefined) {
+      throw new Error("quote_order did not return a quote.");
+    }
+
+    return this.beamsplitter.loader.program.coder.types.decode<OrderQuote>(
+      "OrderQuote",
+      Buffer.from(returnLog.slice(returnPrefix.length), "base64")
+    );
+  }
+
   // Cancel pending order
   async cancel(): Promise<TransactionEnvelope[]> {
     if (this.orderStateData === null) {
@@ -674,6 +902,97 @@ export class PrismEtf {
     ]);
   }
 
+  // Withdraw the Beamsplitter owner's accrued fees in feeMint, the etf itself or one of its weighted tokens
+  async withdrawProtocolFees({
+    feeMint = this.prismEtfMint,
+    amount,
+    shouldCreateAtas = true,
+  }: {
+    feeMint?: PublicKey;
+    amount: BN;
+    shouldCreateAtas?: boolean;
+  }): Promise<TransactionEnvelope> {
+    return this.withdrawFees({
+      feeMint,
+      amount,
+      shouldCreateAtas,
+      instruction: "withdrawProtocolFees",
+    });
+  }
+
+  // Withdraw the manager's accrued fees in feeMint, the etf itself or one of its weighted tokens
+  async withdrawManagerFees({
+    feeMint = this.prismEtfMint,
+    amount,
+    shouldCreateAtas = true,
+  }: {
+    feeMint?: PublicKey;
+    amount: BN;
+    shouldCreateAtas?: boolean;
+  }): Promise<TransactionEnvelope> {
+    return this.withdrawFees({
+      feeMint,
+      amount,
+      shouldCreateAtas,
+      instruction: "withdrawManagerFees",
+    });
+  }
+
+  private async withdrawFees({
+    feeMint,
+    amount,
+    shouldCreateAtas,
+    instruction,
+  }: {
+    feeMint: PublicKey;
+    amount: BN;
+    shouldCreateAtas: boolean;
+    instruction: "withdrawProtocolFees" | "withdrawManagerFees";
+  }): Promise<TransactionEnvelope> {
+    if (this.prismEtfData === null) {
+      throw new Error("You must create the prismEtf first.");
+    }
+
+    const withdrawEnvelope = this.makeProviderEnvelope([]);
+
+    const { address: destination, instruction: createDestinationTx } =
+      await this.getOrCreateATA({
+        mint: feeMint,
+      });
+
+    if (shouldCreateAtas && createDestinationTx !== null) {
+      withdrawEnvelope.append(createDestinationTx);
+    }
+
+    const feeVaultAta = await getATAAddress({
+      mint: feeMint,
+      owner: this.feeVaultPda,
+    });
+
+    const accounts = {
+      prismEtfMint: this.prismEtfMint,
+      feeMint,
+      destination,
+      feeVaultAta,
+      feeVault: this.feeVaultPda,
+      prismEtf: this.prismEtfPda,
+      beamsplitter: this.getBeamsplitter(),
+      weightedTokens: this.prismEtfData.weightedTokens,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    return withdrawEnvelope.append(
+      instruction === "withdrawProtocolFees"
+        ? this.getProgramInstructions().withdrawProtocolFees(amount, {
+            accounts: { ...accounts, owner: this.getUserPublicKey() },
+          })
+        : this.getProgramInstructions().withdrawManagerFees(amount, {
+            accounts: { ...accounts, manager: this.getUserPublicKey() },
+          })
+    );
+  }
+
+  // Queues a new manager, who takes over once applyPendingChanges is called after the change delay
   setManager({ newManager }: { newManager: PublicKey }): TransactionEnvelope {
     return this.makeProviderEnvelope([
       this.getProgramInstructions().setManager({
@@ -683,11 +1002,85 @@ export class PrismEtf {
           manager: this.getUserPublicKey(),
           newManager,
           beamsplitter: this.getBeamsplitter(),
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  // Applies the queued fee and manager changes whose delay has passed, anyone may call this
+  applyPendingChanges(): TransactionEnvelope {
+    return this.makeProviderEnvelope([
+      this.getProgramInstructions().applyPendingChanges({
+        accounts: {
+          prismEtfMint: this.prismEtfMint,
+          prismEtf: this.prismEtfPda,
+          beamsplitter: this.getBeamsplitter(),
+          clock: SYSVAR_CLOCK_PUBKEY,
+        },
+      }),
+    ]);
+  }
+
+  // Drops the fee changes queued by the Beamsplitter owner or its fee admin
+  cancelPendingFeeChanges(): TransactionEnvelope {
+    return this.makeProviderEnvelope([
+      this.getProgramInstructions().cancelPendingFeeChanges({
+        accounts: {
+          prismEtfMint: this.prismEtfMint,
+          authority: this.getUserPublicKey(),
+          prismEtf: this.prismEtfPda,
+          beamsplitter: this.getBeamsplitter(),
         },
       }),
     ]);
   }
 
+  // Drops the manager changes queued by the manager
+  cancelPendingManagerChanges(): TransactionEnvelope {
+    return this.makeProviderEnvelope([
+      this.getProgramInstructions().cancelPendingManagerChanges({
+        accounts: {
+          prismEtfMint: this.prismEtfMint,
+          manager: this.getUserPublicKey(),
+          prismEtf: this.prismEtfPda,
+          beamsplitter: this.getBeamsplitter(),
+        },
+      }),
+    ]);
+  }
+
+  isPercentageMode(): boolean {
+    return (
+      this.prismEtfData !== null &&
+      enumLikeToString(this.prismEtfData.weightMode) === WeightMode.PERCENTAGE
+    );
+  }
+
+  // The (PriceFeed, feed) account pair of each weighted token, which PERCENTAGE constructions are priced with
+  async getPriceFeedAccounts(): Promise<AccountMeta[]> {
+    if (this.weightedTokensData === null) {
+      throw new Error("Weighted tokens was not initalized.");
+    }
+
+    const { weightedTokens, length } = this.weightedTokensData;
+    const priceFeedAccounts: AccountMeta[] = [];
+    for (const { mint } of weightedTokens.slice(0, length)) {
+      const [priceFeed] = await generatePriceFeedAddress(
+        mint,
+        this.getBeamsplitter()
+      );
+      const { feed } = await this.getProgramAccounts().priceFeed.fetch(
+        priceFeed
+      );
+      priceFeedAccounts.push(
+        { pubkey: priceFeed, isSigner: false, isWritable: false },
+        { pubkey: feed, isSigner: false, isWritable: false }
+      );
+    }
+    return priceFeedAccounts;
+  }
+
   getBeamsplitter(): PublicKey {
     return this.beamsplitter.beamsplitter;
   }
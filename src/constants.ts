@@ -8,3 +8,8 @@ export const USDC_MINT_ADDRESS = new PublicKey(
   "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
 );
 export const USDC_MINT_DECIMALS = 6;
+
+// SPL Token-2022, whose mints Beamsplitter supports as weighted tokens
+export const TOKEN_2022_PROGRAM_ID = new PublicKey(
+  "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+);
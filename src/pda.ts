@@ -67,3 +67,54 @@ export const generateWeightedTokensAddress = (
     PROGRAM_ID
   );
 };
+
+export const generatePriceFeedAddress = (
+  mint: PublicKey,
+  beamsplitter: PublicKey
+): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [
+      utils.bytes.utf8.encode("PriceFeed"),
+      beamsplitter.toBuffer(),
+      mint.toBuffer(),
+    ],
+    PROGRAM_ID
+  );
+};
+
+export const generateFeeVaultAddress = (
+  prismEtf: PublicKey
+): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [utils.bytes.utf8.encode("FeeVault"), prismEtf.toBuffer()],
+    PROGRAM_ID
+  );
+};
+
+export const generateFeeExemptionAddress = (
+  prismEtf: PublicKey,
+  exempt: PublicKey
+): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [
+      utils.bytes.utf8.encode("FeeExemption"),
+      prismEtf.toBuffer(),
+      exempt.toBuffer(),
+    ],
+    PROGRAM_ID
+  );
+};
+
+export const generateAllowlistEntryAddress = (
+  prismEtf: PublicKey,
+  holder: PublicKey
+): Promise<[PublicKey, number]> => {
+  return PublicKey.findProgramAddress(
+    [
+      utils.bytes.utf8.encode("AllowlistEntry"),
+      prismEtf.toBuffer(),
+      holder.toBuffer(),
+    ],
+    PROGRAM_ID
+  );
+};
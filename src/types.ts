@@ -30,6 +30,7 @@ export type BeamsplitterProgram = BeamsplitterTypes["Program"];
 
 export type Defined = BeamsplitterTypes["Defined"];
 export type WeightedToken = Defined["WeightedToken"];
+export type OrderQuote = Defined["OrderQuote"];
 
 export const WEIGHTED_TOKENS_CAPACITY = parseInt(
   (CoherenceBeamsplitterIDL as unknown as CoherenceBeamsplitter).constants.find(
@@ -49,16 +50,20 @@ export const PRISM_ETF_SIZE = parseInt(
 
 export const WEIGHTED_TOKEN_BYTE_SIZE = 40; // WeightedToken Struct size in bytes, u64 (8 bytes) + Pubkey (32 bytes)
 export const WEIGHTED_TOKENS_BYTE_SIZE = 4; // Weighted tokens metadata size in bytes, u16 + u16
+export const BACKING_BYTE_SIZE = 8; // Backing amount per weighted token in bytes, u64
 
 export const WEIGHTED_TOKENS_SIZE =
-  WEIGHTED_TOKENS_CAPACITY * WEIGHTED_TOKEN_BYTE_SIZE +
+  WEIGHTED_TOKENS_CAPACITY * (WEIGHTED_TOKEN_BYTE_SIZE + BACKING_BYTE_SIZE) +
   WEIGHTED_TOKENS_BYTE_SIZE +
   ACCOUNT_DISCRIMINATOR_SIZE; // Bytes
 
 export const TRANSFERRED_TOKENS_BYTE_SIZE = 4; // Transferred tokens metadata size in bytes, u16 + u16
+export const TRANSFERRED_AMOUNT_BYTE_SIZE = 8; // Snapshotted amount per weighted token in bytes, u64
+export const TRANSFERRED_FEE_BYTE_SIZE = 8; // Underlying fee per weighted token in bytes, u64
 
 export const TRANSFERRED_TOKENS_SIZE =
-  WEIGHTED_TOKENS_CAPACITY +
+  WEIGHTED_TOKENS_CAPACITY *
+    (1 + TRANSFERRED_AMOUNT_BYTE_SIZE + TRANSFERRED_FEE_BYTE_SIZE) +
   TRANSFERRED_TOKENS_BYTE_SIZE +
   ACCOUNT_DISCRIMINATOR_SIZE; // Bytes
 
@@ -67,6 +72,30 @@ export enum OrderType {
   DECONSTRUCTION = "deconstruction",
 }
 
+export enum WeightMode {
+  QUANTITY = "quantity",
+  PERCENTAGE = "percentage",
+}
+
+export enum PriceFeedKind {
+  PYTH = "pyth",
+  SWITCHBOARD = "switchboard",
+}
+
+export enum FeeMode {
+  DILUTION = "dilution",
+  UNDERLYING = "underlying",
+}
+
+export enum Role {
+  FEE_ADMIN = "feeAdmin",
+  PAUSER = "pauser",
+  AUTOREBALANCER = "autorebalancer",
+  CURATOR = "curator",
+}
+
+export const BPS_DENOMINATOR = 10_000;
+
 export enum OrderStatus {
   PENDING = "pending",
   SUCCEEDED = "succeeded",
@@ -3,3 +3,4 @@ export * from "./delay";
 export * from "./envelope";
 export * from "./native";
 export * from "./time";
+export * from "./token2022";
@@ -0,0 +1,48 @@
+import { ASSOCIATED_TOKEN_PROGRAM_ID } from "@saberhq/token-utils";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
+} from "@solana/web3.js";
+
+import { TOKEN_2022_PROGRAM_ID } from "../constants";
+
+// Associated token account of owner for a Token-2022 mint, which is derived
+// with the Token-2022 program id rather than the original token program's
+export const getToken2022AtaAddress = async (
+  mint: PublicKey,
+  owner: PublicKey
+): Promise<PublicKey> =>
+  (
+    await PublicKey.findProgramAddress(
+      [owner.toBuffer(), TOKEN_2022_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+      ASSOCIATED_TOKEN_PROGRAM_ID
+    )
+  )[0];
+
+// Creates the associated token account of owner for a Token-2022 mint
+export const createToken2022AtaInstruction = ({
+  payer,
+  ata,
+  owner,
+  mint,
+}: {
+  payer: PublicKey;
+  ata: PublicKey;
+  owner: PublicKey;
+  mint: PublicKey;
+}): TransactionInstruction =>
+  new TransactionInstruction({
+    programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+    keys: [
+      { pubkey: payer, isSigner: true, isWritable: true },
+      { pubkey: ata, isSigner: false, isWritable: true },
+      { pubkey: owner, isSigner: false, isWritable: false },
+      { pubkey: mint, isSigner: false, isWritable: false },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.alloc(0),
+  });
@@ -15,6 +15,7 @@ import { before } from "mocha";
 
 import { CoherenceSDK, generateBeamsplitterAddress } from "../src";
 import exploitTests from "./exploit-tests";
+import integrationTests from "./integration-tests";
 import stressTests from "./stress-tests";
 import unitTests from "./unit-tests";
 
@@ -99,5 +100,6 @@ before("Initialize coherence helper", () => {
 describe("coherence-beamsplitter", () => {
   stressTests();
   exploitTests();
+  integrationTests();
   unitTests();
 });
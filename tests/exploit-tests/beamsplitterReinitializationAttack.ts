@@ -46,6 +46,7 @@ export default function beamsplitterReinitializationAttack() {
               prismEtfMint,
               weightedTokens: prismEtf.prismEtfData.weightedTokens,
               manager: coherenceHelper.authority,
+              payer: coherenceHelper.authority,
               beamsplitter: coherenceHelper.beamsplitter,
               systemProgram: SystemProgram.programId,
             },
@@ -0,0 +1,9 @@
+import multisigOwner from "./multisigOwner";
+import prismEtfMetadata from "./prismEtfMetadata";
+
+export default function integrationTests() {
+  describe("#INTEGRATION TESTS", () => {
+    multisigOwner();
+    prismEtfMetadata();
+  });
+}
@@ -0,0 +1,157 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { TransactionInstruction as Instruction } from "@solana/web3.js";
+import { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import chai, { expect } from "chai";
+
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+
+chai.use(chaiSolana);
+
+const MOCK_MULTISIG_PROGRAM_ID = new PublicKey(
+  "8uSoS1Fp2iCQCzBwUqUitKWLme5NXuJoFriMBvdQdGC2"
+);
+
+// First 8 bytes of sha256("global:execute"), the Anchor discriminator of mock_multisig::execute
+const EXECUTE_DISCRIMINATOR = Buffer.from([
+  130, 221, 242, 154, 13, 193, 189, 29,
+]);
+
+export default function multisigOwner() {
+  let multisigSigner: PublicKey;
+
+  // Wraps a Beamsplitter instruction in mock_multisig::execute, which signs for it with the multisig's PDA
+  const executeAsMultisig = (instruction: Instruction): TransactionEnvelope => {
+    const dataLength = Buffer.alloc(4);
+    dataLength.writeUInt32LE(instruction.data.length, 0);
+
+    return new TransactionEnvelope(coherenceHelper.provider, [
+      new TransactionInstruction({
+        programId: MOCK_MULTISIG_PROGRAM_ID,
+        keys: [
+          { pubkey: multisigSigner, isSigner: false, isWritable: false },
+          {
+            pubkey: instruction.programId,
+            isSigner: false,
+            isWritable: false,
+          },
+          ...instruction.keys.map((key) => ({
+            ...key,
+            isSigner: key.isSigner && !key.pubkey.equals(multisigSigner),
+          })),
+        ],
+        data: Buffer.concat([
+          EXECUTE_DISCRIMINATOR,
+          dataLength,
+          instruction.data,
+        ]),
+      }),
+    ]);
+  };
+
+  const setDefaultConstructionBps = (
+    authority: PublicKey,
+    newConstructionBps: number
+  ): Instruction =>
+    coherenceHelper.sdk.loader.program.instruction.setDefaultConstructionBps(
+      newConstructionBps,
+      {
+        accounts: {
+          authority,
+          beamsplitter: coherenceHelper.beamsplitter,
+        },
+      }
+    );
+
+  it("Transfer ownership to a multisig PDA", async () => {
+    [multisigSigner] = await PublicKey.findProgramAddress(
+      [Buffer.from("MultisigSigner")],
+      MOCK_MULTISIG_PROGRAM_ID
+    );
+
+    await expectTX(
+      coherenceHelper.sdk.beamsplitter.setOwner({ newOwner: multisigSigner })
+    ).to.be.fulfilled;
+
+    await coherenceHelper.sdk.refreshBeamsplitter();
+    expect(coherenceHelper.sdk.beamsplitter.beamsplitterData?.owner).to.eqAddress(
+      multisigSigner
+    );
+  });
+
+  it("Previous owner can no longer change fees", async () => {
+    const tx = new TransactionEnvelope(coherenceHelper.provider, [
+      setDefaultConstructionBps(coherenceHelper.authority, 50),
+    ]);
+
+    await expectTX(tx).to.be.rejected;
+  });
+
+  it("Multisig changes the default construction fee through CPI", async () => {
+    const oldConstructionBps =
+      coherenceHelper.sdk.beamsplitter.beamsplitterData?.defaultConstructionBps;
+
+    await expectTX(
+      executeAsMultisig(setDefaultConstructionBps(multisigSigner, 50))
+    ).to.be.fulfilled;
+
+    await coherenceHelper.sdk.refreshBeamsplitter();
+    expect(
+      coherenceHelper.sdk.beamsplitter.beamsplitterData?.defaultConstructionBps
+    ).to.equal(50);
+
+    await expectTX(
+      executeAsMultisig(
+        setDefaultConstructionBps(multisigSigner, oldConstructionBps ?? 0)
+      )
+    ).to.be.fulfilled;
+  });
+
+  it("Multisig hands ownership back through CPI", async () => {
+    const setOwner = coherenceHelper.sdk.loader.program.instruction.setOwner({
+      accounts: {
+        owner: multisigSigner,
+        newOwner: coherenceHelper.authority,
+        beamsplitter: coherenceHelper.beamsplitter,
+      },
+    });
+
+    await expectTX(executeAsMultisig(setOwner)).to.be.fulfilled;
+
+    await coherenceHelper.sdk.refreshBeamsplitter();
+    expect(coherenceHelper.sdk.beamsplitter.beamsplitterData?.owner).to.eqAddress(
+      coherenceHelper.authority
+    );
+  });
+
+  it("Multisig manages a Prism ETF that another key pays for", async () => {
+    const [initPrismEtfEnvelope, , prismEtfPda] =
+      await coherenceHelper.sdk.beamsplitter.initPrismEtf({
+        manager: multisigSigner,
+      });
+
+    // The multisig signs init_prism_etf through CPI, the accounts before it are created as usual
+    const { instructions, signers } = initPrismEtfEnvelope;
+    const initPrismEtf = instructions[instructions.length - 1];
+    if (!initPrismEtf) {
+      throw new Error("initPrismEtf returned no instructions");
+    }
+
+    await expectTX(
+      new TransactionEnvelope(
+        coherenceHelper.provider,
+        instructions.slice(0, -1),
+        signers
+      )
+    ).to.be.fulfilled;
+    await expectTX(executeAsMultisig(initPrismEtf)).to.be.fulfilled;
+
+    const prismEtfData = await coherenceHelper.sdk.loader.fetchPrismEtfData(
+      prismEtfPda
+    );
+    expect(prismEtfData?.manager).to.eqAddress(multisigSigner);
+  });
+}
@@ -0,0 +1,127 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import chai, { expect } from "chai";
+
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+
+chai.use(chaiSolana);
+
+// Loaded into the local validator from tests/deps by Anchor.toml
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+
+// Metaplex metadata starts with its key (1 byte), update authority and mint, followed by the borsh encoded name,
+// symbol and uri, each padded with null bytes
+const readMetadata = (data: Buffer) => {
+  let offset = 1 + 32 + 32;
+  const readString = () => {
+    const length = data.readUInt32LE(offset);
+    offset += 4;
+    const value = data
+      .slice(offset, offset + length)
+      .toString("utf8")
+      .replace(/\0/g, "");
+    offset += length;
+    return value;
+  };
+
+  const updateAuthority = new PublicKey(data.slice(1, 33));
+  const name = readString();
+  const symbol = readString();
+  const uri = readString();
+  return { updateAuthority, name, symbol, uri };
+};
+
+export default function prismEtfMetadata() {
+  let prismEtfMint: PublicKey;
+  let prismEtfPda: PublicKey;
+  let metadata: PublicKey;
+
+  const fetchMetadata = async () => {
+    const account = await coherenceHelper.provider.getAccountInfo(metadata);
+    if (!account) {
+      throw new Error("Metadata account does not exist");
+    }
+    return readMetadata(account.accountInfo.data);
+  };
+
+  it("Create metadata for a Prism ETF mint", async () => {
+    const [initPrismEtf, mint, pda] =
+      await coherenceHelper.sdk.beamsplitter.initPrismEtf({});
+    await expectTX(initPrismEtf).to.be.fulfilled;
+    prismEtfMint = mint;
+    prismEtfPda = pda;
+
+    [metadata] = await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        prismEtfMint.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
+    const tx = new TransactionEnvelope(coherenceHelper.provider, [
+      coherenceHelper.sdk.loader.program.instruction.createPrismEtfMetadata(
+        "Coherence Test ETF",
+        "CTE",
+        "https://coherence.finance/cte.json",
+        {
+          accounts: {
+            prismEtfMint,
+            manager: coherenceHelper.authority,
+            payer: coherenceHelper.authority,
+            metadata,
+            prismEtf: prismEtfPda,
+            beamsplitter: coherenceHelper.beamsplitter,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          },
+        }
+      ),
+    ]);
+    await expectTX(tx).to.be.fulfilled;
+
+    const data = await fetchMetadata();
+    expect(data.updateAuthority).to.eqAddress(coherenceHelper.beamsplitter);
+    expect(data.name).to.equal("Coherence Test ETF");
+    expect(data.symbol).to.equal("CTE");
+    expect(data.uri).to.equal("https://coherence.finance/cte.json");
+  });
+
+  it("Manager updates the metadata", async () => {
+    const tx = new TransactionEnvelope(coherenceHelper.provider, [
+      coherenceHelper.sdk.loader.program.instruction.updatePrismEtfMetadata(
+        "Coherence Renamed ETF",
+        "CRE",
+        "https://coherence.finance/cre.json",
+        {
+          accounts: {
+            prismEtfMint,
+            manager: coherenceHelper.authority,
+            metadata,
+            prismEtf: prismEtfPda,
+            beamsplitter: coherenceHelper.beamsplitter,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          },
+        }
+      ),
+    ]);
+    await expectTX(tx).to.be.fulfilled;
+
+    const data = await fetchMetadata();
+    expect(data.name).to.equal("Coherence Renamed ETF");
+    expect(data.symbol).to.equal("CRE");
+    expect(data.uri).to.equal("https://coherence.finance/cre.json");
+  });
+}
@@ -0,0 +1,88 @@
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+
+import type { CoherenceSDK, PriceFeedKind } from "../src";
+import { generatePriceFeedAddress, stringToEnumLike } from "../src";
+import { coherenceHelper } from "./coherenceBeamsplitterTest";
+
+/*
+Registers the price feeds PERCENTAGE mode ETFs are priced with, which the SDK
+does not wrap. Feeds are the oracle accounts in tests/fixtures
+*/
+
+// The fixtures are published at timestamp 0, so accept any age a u32 can hold
+export const ACCEPT_ANY_STALENESS = 4_294_967_295;
+
+export interface TestPriceFeedConfig {
+  // The fixture's pubkey
+  feed: string;
+  kind: PriceFeedKind;
+  maxStaleness?: number;
+  maxConfidenceBps?: number;
+}
+
+export const initTestPriceFeed = async (
+  mint: PublicKey,
+  {
+    feed,
+    kind,
+    maxStaleness = ACCEPT_ANY_STALENESS,
+    maxConfidenceBps = 100,
+  }: TestPriceFeedConfig,
+  sdk: CoherenceSDK = coherenceHelper.sdk
+) => {
+  const [priceFeed, bump] = await generatePriceFeedAddress(
+    mint,
+    coherenceHelper.beamsplitter
+  );
+  return sdk.loader.makeProviderEnvelope([
+    sdk.loader.program.instruction.initPriceFeed(
+      bump,
+      stringToEnumLike(kind),
+      maxStaleness,
+      maxConfidenceBps,
+      {
+        accounts: {
+          mint,
+          feed: new PublicKey(feed),
+          authority: sdk.loader.getUserPublicKey(),
+          payer: sdk.loader.getUserPublicKey(),
+          priceFeed,
+          beamsplitter: coherenceHelper.beamsplitter,
+          systemProgram: SystemProgram.programId,
+        },
+      }
+    ),
+  ]);
+};
+
+export const setTestPriceFeed = async (
+  mint: PublicKey,
+  {
+    feed,
+    kind,
+    maxStaleness = ACCEPT_ANY_STALENESS,
+    maxConfidenceBps = 100,
+  }: TestPriceFeedConfig,
+  sdk: CoherenceSDK = coherenceHelper.sdk
+) => {
+  const [priceFeed] = await generatePriceFeedAddress(
+    mint,
+    coherenceHelper.beamsplitter
+  );
+  return sdk.loader.makeProviderEnvelope([
+    sdk.loader.program.instruction.setPriceFeed(
+      stringToEnumLike(kind),
+      maxStaleness,
+      maxConfidenceBps,
+      {
+        accounts: {
+          mint,
+          feed: new PublicKey(feed),
+          authority: sdk.loader.getUserPublicKey(),
+          priceFeed,
+          beamsplitter: coherenceHelper.beamsplitter,
+        },
+      }
+    ),
+  ]);
+};
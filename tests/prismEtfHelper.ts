@@ -0,0 +1,132 @@
+import { expectTX } from "@saberhq/chai-solana";
+import { PendingTransaction } from "@saberhq/solana-contrib";
+import {
+  createInitMintInstructions,
+  createMintToInstruction,
+  u64,
+} from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import type BN from "bn.js";
+
+import type { UserPrismEtf, WeightedToken } from "../src";
+import { CoherenceSDK, PrismEtf } from "../src";
+import { setupATA } from "./balanceHelper";
+import { coherenceHelper } from "./coherenceBeamsplitterTest";
+
+/*
+Builds the mints and Prism ETFs the unit tests order against, so each suite
+only sets up what it tests
+*/
+
+// An SDK signing with a new funded keypair, to act as someone other than the
+// Beamsplitter owner
+export const createFundedSdk = async (): Promise<[CoherenceSDK, Keypair]> => {
+  const signer = Keypair.generate();
+  await expectTX(
+    new PendingTransaction(
+      coherenceHelper.provider.connection,
+      await coherenceHelper.provider.connection.requestAirdrop(
+        signer.publicKey,
+        LAMPORTS_PER_SOL
+      )
+    )
+  ).to.be.fulfilled;
+
+  const sdk = await CoherenceSDK.initWithSigner({
+    provider: coherenceHelper.provider,
+    signer,
+  });
+  return [sdk, signer];
+};
+
+// Create a mint whose authority is the test signer
+export const createTestMint = async (decimals: number): Promise<PublicKey> => {
+  const mintKP = Keypair.generate();
+  await expectTX(
+    await createInitMintInstructions({
+      provider: coherenceHelper.provider,
+      mintKP,
+      decimals,
+      mintAuthority: coherenceHelper.authority,
+    })
+  ).to.be.fulfilled;
+  return mintKP.publicKey;
+};
+
+// Mint test tokens to the ATA of owner, creating it if needed
+export const mintTestTokens = async (
+  mint: PublicKey,
+  amount: BN,
+  owner: PublicKey = coherenceHelper.authority
+): Promise<PublicKey> => {
+  const ata = await setupATA(mint, owner);
+  await expectTX(
+    createMintToInstruction({
+      provider: coherenceHelper.provider,
+      mint,
+      mintAuthorityKP: coherenceHelper.testSigner,
+      to: ata,
+      amount: new u64(amount.toString()),
+    })
+  ).to.be.fulfilled;
+  return ata;
+};
+
+export const loadTestPrismEtf = async (
+  prismEtfMint: PublicKey,
+  sdk: CoherenceSDK = coherenceHelper.sdk
+): Promise<PrismEtf> =>
+  await PrismEtf.loadPrismEtf({
+    beamsplitter: sdk.beamsplitter,
+    prismEtfMint,
+    userPrismEtf: {} as UserPrismEtf,
+  });
+
+export interface TestPrismEtf {
+  prismEtfMint: PublicKey;
+  prismEtfPda: PublicKey;
+  weightedTokensAcct: PublicKey;
+}
+
+// Create a Prism ETF managed by the signer of sdk and push weightedTokens, if
+// there are any. A new mint is created for it unless one is given
+export const initTestPrismEtf = async (
+  weightedTokens: WeightedToken[],
+  sdk: CoherenceSDK = coherenceHelper.sdk,
+  mint?: PublicKey
+): Promise<TestPrismEtf> => {
+  const [initPrismEtfTx, prismEtfMint, prismEtfPda, weightedTokensAcct] =
+    await sdk.beamsplitter.initPrismEtf({ prismEtfMint: mint });
+  await expectTX(initPrismEtfTx, "Initialize Prism ETF").to.be.fulfilled;
+
+  if (weightedTokens.length > 0) {
+    const pushTokensEnvelopes = await sdk.beamsplitter.pushTokens({
+      prismEtfMint,
+      prismEtfPda,
+      weightedTokens,
+      weightedTokensAcct,
+    });
+
+    // Have to do pushing in seq (Promise.all is not an option)
+    for (const pushTokensEnvelope of pushTokensEnvelopes) {
+      await expectTX(pushTokensEnvelope).to.be.fulfilled;
+    }
+  }
+
+  return { prismEtfMint, prismEtfPda, weightedTokensAcct };
+};
+
+export const finalizeTestPrismEtf = async (
+  { prismEtfMint, prismEtfPda }: TestPrismEtf,
+  sdk: CoherenceSDK = coherenceHelper.sdk
+): Promise<PrismEtf> => {
+  await expectTX(
+    await sdk.beamsplitter.finalizePrismEtf({
+      prismEtfMint,
+      prismEtfPda,
+    }),
+    "Finalize Prism ETF"
+  ).to.be.fulfilled;
+  return await loadTestPrismEtf(prismEtfMint, sdk);
+};
@@ -0,0 +1,264 @@
+import { expectTX } from "@saberhq/chai-solana";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getATAAddress,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import type { AccountMeta } from "@solana/web3.js";
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import type BN from "bn.js";
+
+import type { CoherenceSDK, PrismEtf } from "../src";
+import { PROGRAM_ID } from "../src";
+import { coherenceHelper } from "./coherenceBeamsplitterTest";
+
+/*
+Builds the single asset order instructions, which the SDK does not wrap. Swaps
+are only tested against ETFs weighting the swap mint itself, which the program
+transfers instead of swapping, with the token program standing in as the
+allowed swap program
+*/
+
+// Allow (or, with SystemProgram.programId, disallow) single asset orders
+export const setTestSwapProgram = async (
+  swapProgram: PublicKey,
+  sdk: CoherenceSDK = coherenceHelper.sdk
+): Promise<void> => {
+  await expectTX(
+    sdk.loader.makeProviderEnvelope([
+      sdk.loader.program.instruction.setSwapProgram({
+        accounts: {
+          swapProgram,
+          authority: sdk.loader.getUserPublicKey(),
+          beamsplitter: coherenceHelper.beamsplitter,
+        },
+      }),
+    ])
+  ).to.be.fulfilled;
+};
+
+const orderAccounts = async (prismEtf: PrismEtf, swapMint: PublicKey) => {
+  if (
+    prismEtf.prismEtfData === null ||
+    prismEtf.orderStatePda === null ||
+    prismEtf.transferredTokensAcct === undefined
+  ) {
+    throw new Error("The order state of prismEtf must be initialized first");
+  }
+
+  const orderer = prismEtf.getUserPublicKey();
+  return {
+    prismEtfMint: prismEtf.prismEtfMint,
+    swapMint,
+    orderer,
+    prismEtf: prismEtf.prismEtfPda,
+    orderState: prismEtf.orderStatePda,
+    beamsplitter: prismEtf.getBeamsplitter(),
+    weightedTokens: prismEtf.prismEtfData.weightedTokens,
+    transferredTokens: prismEtf.transferredTokensAcct,
+    ordererSwapAta: await getATAAddress({ mint: swapMint, owner: orderer }),
+    orderSwapAta: await getATAAddress({
+      mint: swapMint,
+      owner: prismEtf.orderStatePda,
+    }),
+  };
+};
+
+export const startTestSingleAssetOrder = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey,
+  inputAmount: BN,
+  minAmountOut: BN,
+  remainingAccounts: AccountMeta[] = []
+) => {
+  const { ordererSwapAta, orderSwapAta, ...accounts } = await orderAccounts(
+    prismEtf,
+    swapMint
+  );
+
+  return prismEtf.makeProviderEnvelope([
+    prismEtf
+      .getProgramInstructions()
+      .startSingleAssetOrder(inputAmount, minAmountOut, {
+        accounts: {
+          ...accounts,
+          ordererSwapAta,
+          orderSwapAta,
+          rent: SYSVAR_RENT_PUBKEY,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        },
+        remainingAccounts,
+      }),
+  ]);
+};
+
+// Move amountIn of the order's input into the ETF, for a weighted token that
+// is the swap mint itself. remainingAccounts may start with the orderer's fee
+// exemption
+export const swapTestCohere = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey,
+  index: number,
+  amountIn: BN,
+  remainingAccounts: AccountMeta[] = []
+) => {
+  const { orderSwapAta, ...accounts } = await orderAccounts(prismEtf, swapMint);
+
+  return prismEtf.makeProviderEnvelope([
+    prismEtf
+      .getProgramInstructions()
+      .swapCohere(index, amountIn, Buffer.from([]), {
+        accounts: {
+          orderer: accounts.orderer,
+          prismEtfMint: accounts.prismEtfMint,
+          transferMint: swapMint,
+          swapMint,
+          orderSwapAta,
+          beamsplitterTransferAta: await getATAAddress({
+            mint: swapMint,
+            owner: prismEtf.prismEtfPda,
+          }),
+          beamsplitter: accounts.beamsplitter,
+          prismEtf: accounts.prismEtf,
+          orderState: accounts.orderState,
+          feeVault: prismEtf.feeVaultPda,
+          feeVaultTransferAta: await getATAAddress({
+            mint: swapMint,
+            owner: prismEtf.feeVaultPda,
+          }),
+          weightedTokens: accounts.weightedTokens,
+          transferredTokens: accounts.transferredTokens,
+          swapProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        },
+        remainingAccounts,
+      }),
+  ]);
+};
+
+// Creates the orderer's ETF ATA it mints to if needed
+export const finalizeTestSingleAssetOrder = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey,
+  remainingAccounts: AccountMeta[] = []
+) => {
+  const { weightedTokens: _, ...accounts } = await orderAccounts(
+    prismEtf,
+    swapMint
+  );
+  const { address: ordererEtfAta, instruction: createOrdererEtfAta } =
+    await prismEtf.getOrCreateATA({ mint: prismEtf.prismEtfMint });
+
+  return prismEtf.makeProviderEnvelope([
+    ...(createOrdererEtfAta === null ? [] : [createOrdererEtfAta]),
+    prismEtf.getProgramInstructions().finalizeSingleAssetOrder({
+      accounts: {
+        ...accounts,
+        ordererEtfAta,
+        feeVaultEtfAta: await getATAAddress({
+          mint: prismEtf.prismEtfMint,
+          owner: prismEtf.feeVaultPda,
+        }),
+        feeVault: prismEtf.feeVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      },
+      remainingAccounts,
+    }),
+  ]);
+};
+
+export const startTestSingleAssetRedemption = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey,
+  amount: BN,
+  minAmountOut: BN
+) => {
+  const { ordererSwapAta: _, ...accounts } = await orderAccounts(
+    prismEtf,
+    swapMint
+  );
+
+  return prismEtf.makeProviderEnvelope([
+    prismEtf
+      .getProgramInstructions()
+      .startSingleAssetRedemption(amount, minAmountOut, {
+        accounts: {
+          ...accounts,
+          ordererEtfAta: await getATAAddress({
+            mint: prismEtf.prismEtfMint,
+            owner: accounts.orderer,
+          }),
+          rent: SYSVAR_RENT_PUBKEY,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        },
+      }),
+  ]);
+};
+
+// Move a redemption's share of a weighted token that is the swap mint itself
+// to the order
+export const swapTestDecohere = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey,
+  index: number
+) => {
+  const { ordererSwapAta, orderSwapAta, ...accounts } = await orderAccounts(
+    prismEtf,
+    swapMint
+  );
+  const [orderVault] = await PublicKey.findProgramAddress(
+    [
+      Buffer.from("OrderVault"),
+      accounts.orderState.toBuffer(),
+      swapMint.toBuffer(),
+    ],
+    PROGRAM_ID
+  );
+
+  return prismEtf.makeProviderEnvelope([
+    prismEtf.getProgramInstructions().swapDecohere(index, Buffer.from([]), {
+      accounts: {
+        orderer: accounts.orderer,
+        prismEtfMint: accounts.prismEtfMint,
+        transferMint: swapMint,
+        swapMint,
+        beamsplitterTransferAta: await getATAAddress({
+          mint: swapMint,
+          owner: prismEtf.prismEtfPda,
+        }),
+        ordererTransferAta: ordererSwapAta,
+        orderSwapAta,
+        orderVault,
+        beamsplitter: accounts.beamsplitter,
+        prismEtf: accounts.prismEtf,
+        orderState: accounts.orderState,
+        weightedTokens: accounts.weightedTokens,
+        transferredTokens: accounts.transferredTokens,
+        swapProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      },
+    }),
+  ]);
+};
+
+export const finalizeTestSingleAssetRedemption = async (
+  prismEtf: PrismEtf,
+  swapMint: PublicKey
+) => {
+  const { weightedTokens: _, ...accounts } = await orderAccounts(
+    prismEtf,
+    swapMint
+  );
+
+  return prismEtf.makeProviderEnvelope([
+    prismEtf.getProgramInstructions().finalizeSingleAssetRedemption({
+      accounts: { ...accounts, tokenProgram: TOKEN_PROGRAM_ID },
+    }),
+  ]);
+};
@@ -0,0 +1,139 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { createInitMintInstructions } from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { Keypair, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { UserPrismEtf, WeightedToken } from "../../src";
+import {
+  OrderType,
+  PrismEtf,
+  PROGRAM_ID,
+  stringToEnumLike,
+} from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+
+chai.use(chaiSolana);
+
+// Assets in the benchmarked Prism ETF
+const BENCHMARK_ASSETS = 10;
+
+// Compute units quote_order may use per asset, and once for the ETF. quote_order runs the same weighted_amount
+// math as start_order, cohere and decohere, so a regression in math.rs shows up here first
+const QUOTE_ORDER_CU_PER_ASSET = 6_000;
+const QUOTE_ORDER_CU_BASE = 15_000;
+
+const consumedComputeUnits = (logs: string[]): number => {
+  const pattern = new RegExp(
+    `^Program ${PROGRAM_ID.toString()} consumed (\\d+) of \\d+ compute units$`
+  );
+  for (const log of logs) {
+    const match = pattern.exec(log);
+    if (match?.[1]) {
+      return parseInt(match[1]);
+    }
+  }
+  throw new Error("Compute units were not logged");
+};
+
+export default function computeUnits() {
+  it(`Quote an order of a Prism ETF with ${BENCHMARK_ASSETS} assets within the compute budget`, async () => {
+    const [initPrismEtFTx, prismEtfMint, prismEtfPda, weightedTokensAcct] =
+      await coherenceHelper.sdk.beamsplitter.initPrismEtf({});
+
+    await expectTX(initPrismEtFTx, "Initialize asset with assetToken").to.be
+      .fulfilled;
+
+    const mints: PublicKey[] = [];
+    const weightedTokens: WeightedToken[] = [];
+    for (let i = 0; i < BENCHMARK_ASSETS; i++) {
+      const mintKP = Keypair.generate();
+      await expectTX(
+        await createInitMintInstructions({
+          provider: coherenceHelper.provider,
+          mintKP,
+          // Spread decimals so both scale directions of weighted_amount are taken
+          decimals: i % 12,
+          mintAuthority: coherenceHelper.authority,
+        })
+      ).to.be.fulfilled;
+
+      mints.push(mintKP.publicKey);
+      weightedTokens.push({
+        mint: mintKP.publicKey,
+        weight: new BN(1_000_003 * (i + 1)),
+      });
+    }
+
+    const pushTokensEnvelopes =
+      await coherenceHelper.sdk.beamsplitter.pushTokens({
+        weightedTokens,
+        prismEtfMint,
+        prismEtfPda,
+        weightedTokensAcct,
+        shouldCreateAtas: false,
+      });
+
+    // Have to do pushing in seq (Promise.all is not an option)
+    for (const pushTokensEnvelope of pushTokensEnvelopes) {
+      await expectTX(pushTokensEnvelope).to.be.fulfilled;
+    }
+
+    await expectTX(
+      await coherenceHelper.sdk.beamsplitter.finalizePrismEtf({
+        prismEtfMint,
+        prismEtfPda,
+      }),
+      "Finalize PrismEtf"
+    ).to.be.fulfilled;
+
+    const prismEtf = await PrismEtf.loadPrismEtf({
+      beamsplitter: coherenceHelper.sdk.beamsplitter,
+      prismEtfMint,
+      userPrismEtf: {} as UserPrismEtf,
+    });
+
+    if (!prismEtf.prismEtfData) {
+      assert.fail("Prism Etf was not successfully created");
+    }
+
+    const quoteOrderTx = prismEtf.makeProviderEnvelope([
+      prismEtf
+        .getProgramInstructions()
+        .quoteOrder(
+          stringToEnumLike(OrderType.CONSTRUCTION),
+          new BN(123_456_789_012),
+          {
+            accounts: {
+              prismEtfMint,
+              prismEtf: prismEtfPda,
+              beamsplitter: prismEtf.getBeamsplitter(),
+              weightedTokens: prismEtf.prismEtfData.weightedTokens,
+              clock: SYSVAR_CLOCK_PUBKEY,
+            },
+            remainingAccounts: mints.map((mint) => ({
+              pubkey: mint,
+              isSigner: false,
+              isWritable: false,
+            })),
+          }
+        ),
+    ]);
+
+    const simulation = await quoteOrderTx.simulate();
+    expect(simulation.value.err).to.be.null;
+
+    const consumed = consumedComputeUnits(simulation.value.logs ?? []);
+    const budget =
+      QUOTE_ORDER_CU_BASE + QUOTE_ORDER_CU_PER_ASSET * BENCHMARK_ASSETS;
+    console.log(
+      `quote_order consumed ${consumed} compute units for ${BENCHMARK_ASSETS} assets (budget ${budget})`
+    );
+
+    expect(consumed).to.be.at.most(budget);
+  });
+}
@@ -1,7 +1,9 @@
+import computeUnits from "./computeUnits";
 import prismEtfSize from "./prismEtfSize";
 
 export default function stressTests() {
   describe("#STRESS TESTS", () => {
     prismEtfSize();
+    computeUnits();
   });
 }
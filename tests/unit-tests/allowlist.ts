@@ -0,0 +1,316 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getATAAddress,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import type { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { CoherenceSDK, PrismEtf } from "../../src";
+import {
+  generateAllowlistEntryAddress,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  stringToEnumLike,
+} from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createFundedSdk,
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function allowlist() {
+  describe("Allowlist", () => {
+    const decimals = 6;
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let testPrismEtf: TestPrismEtf;
+    let holderSdk: CoherenceSDK;
+    let holderKP: Keypair;
+    let payerKP: Keypair;
+    // The ETF as seen by the holder, whose order state it loads
+    let holderPrismEtf: PrismEtf;
+
+    const refreshHolderPrismEtf = async () => {
+      holderPrismEtf = await loadTestPrismEtf(
+        testPrismEtf.prismEtfMint,
+        holderSdk
+      );
+    };
+
+    const setPermissioned = (
+      permissioned: boolean,
+      sdk: CoherenceSDK = coherenceHelper.sdk
+    ) =>
+      sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.setPermissioned(permissioned, {
+          accounts: {
+            prismEtfMint: testPrismEtf.prismEtfMint,
+            manager: sdk.loader.getUserPublicKey(),
+            prismEtf: testPrismEtf.prismEtfPda,
+            beamsplitter: coherenceHelper.beamsplitter,
+          },
+        }),
+      ]);
+
+    const addAllowlistEntry = async (
+      { prismEtfMint, prismEtfPda }: TestPrismEtf,
+      holder: PublicKey
+    ) => {
+      const [allowlistEntry, bump] = await generateAllowlistEntryAddress(
+        prismEtfPda,
+        holder
+      );
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope(
+          [
+            coherenceHelper.sdk.loader.program.instruction.addAllowlistEntry(
+              bump,
+              {
+                accounts: {
+                  prismEtfMint,
+                  holder,
+                  manager: coherenceHelper.authority,
+                  payer: payerKP.publicKey,
+                  prismEtf: prismEtfPda,
+                  allowlistEntry,
+                  beamsplitter: coherenceHelper.beamsplitter,
+                  systemProgram: SystemProgram.programId,
+                },
+              }
+            ),
+          ],
+          [payerKP]
+        )
+      ).to.be.fulfilled;
+      return allowlistEntry;
+    };
+
+    const construct = async () => {
+      await refreshHolderPrismEtf();
+      for (const cohereChunk of await holderPrismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshHolderPrismEtf();
+      await expectTX(await holderPrismEtf.finalizeOrder({})).to.be.fulfilled;
+      await refreshHolderPrismEtf();
+    };
+
+    const startOrder = async (
+      type: OrderType,
+      amount: BN = AMOUNT_TO_CONSTRUCT
+    ) => await holderPrismEtf.startOrder({ type, amount });
+
+    // Starts a CONSTRUCTION with exactly the allowlist entry given, unlike the
+    // SDK which always passes the holder's
+    const startConstructionWith = async (allowlistEntry: PublicKey) => {
+      if (
+        holderPrismEtf.prismEtfData === null ||
+        holderPrismEtf.orderStatePda === null ||
+        holderPrismEtf.transferredTokensAcct === undefined
+      ) {
+        assert.fail("The holder's order state was not initialized");
+      }
+
+      return holderSdk.loader.makeProviderEnvelope([
+        holderSdk.loader.program.instruction.startOrder(
+          stringToEnumLike(OrderType.CONSTRUCTION),
+          AMOUNT_TO_CONSTRUCT,
+          {
+            accounts: {
+              prismEtf: testPrismEtf.prismEtfPda,
+              prismEtfMint: testPrismEtf.prismEtfMint,
+              orderState: holderPrismEtf.orderStatePda,
+              transferredTokens: holderPrismEtf.transferredTokensAcct,
+              orderer: holderKP.publicKey,
+              ordererEtfAta: await getATAAddress({
+                mint: testPrismEtf.prismEtfMint,
+                owner: holderKP.publicKey,
+              }),
+              beamsplitter: coherenceHelper.beamsplitter,
+              rent: SYSVAR_RENT_PUBKEY,
+              clock: SYSVAR_CLOCK_PUBKEY,
+              weightedTokens: holderPrismEtf.prismEtfData.weightedTokens,
+              associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              systemProgram: SystemProgram.programId,
+            },
+            remainingAccounts: [
+              { pubkey: allowlistEntry, isSigner: false, isWritable: false },
+            ],
+          }
+        ),
+      ]);
+    };
+
+    before(async () => {
+      [holderSdk, holderKP] = await createFundedSdk();
+      [, payerKP] = await createFundedSdk();
+
+      const mint = await createTestMint(decimals);
+      await mintTestTokens(
+        mint,
+        new BN(1_000 * 10 ** decimals),
+        holderKP.publicKey
+      );
+
+      testPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+      await finalizeTestPrismEtf(testPrismEtf);
+
+      await refreshHolderPrismEtf();
+      await expectTX(await holderPrismEtf.initOrderState()).to.be.fulfilled;
+      await refreshHolderPrismEtf();
+    });
+
+    it(`Reject making an ETF permissioned without being its manager`, async () => {
+      await expectTX(setPermissioned(true, holderSdk)).to.be.rejected;
+
+      await refreshHolderPrismEtf();
+      expect(holderPrismEtf.prismEtfData?.permissioned).to.be.false;
+    });
+
+    it(`Only let allowlisted holders construct a permissioned ETF`, async () => {
+      await expectTX(setPermissioned(true)).to.be.fulfilled;
+      await refreshHolderPrismEtf();
+      expect(holderPrismEtf.prismEtfData?.permissioned).to.be.true;
+
+      await expectTX(await startOrder(OrderType.CONSTRUCTION)).to.be.rejected;
+
+      const managerBalanceBefore =
+        await coherenceHelper.provider.connection.getBalance(
+          coherenceHelper.authority
+        );
+      const allowlistEntry = await addAllowlistEntry(
+        testPrismEtf,
+        holderKP.publicKey
+      );
+
+      const allowlistEntryData =
+        await coherenceHelper.sdk.loader.program.account.allowlistEntry.fetch(
+          allowlistEntry
+        );
+      expect(allowlistEntryData.prismEtf).to.eqAddress(
+        testPrismEtf.prismEtfPda
+      );
+      expect(allowlistEntryData.holder).to.eqAddress(holderKP.publicKey);
+      expect(allowlistEntryData.payer).to.eqAddress(payerKP.publicKey);
+
+      // The manager only paid the transaction fee, the payer funded the entry
+      const managerBalanceAfter =
+        await coherenceHelper.provider.connection.getBalance(
+          coherenceHelper.authority
+        );
+      expect(managerBalanceBefore - managerBalanceAfter).to.be.lessThan(
+        10_000
+      );
+
+      await expectTX(await startOrder(OrderType.CONSTRUCTION)).to.be.fulfilled;
+      await construct();
+
+      const holderEtfAta = await getATAAddress({
+        mint: testPrismEtf.prismEtfMint,
+        owner: holderKP.publicKey,
+      });
+      expect(await ataBalance(holderEtfAta)).to.be.bignumber.greaterThan(
+        new BN(0)
+      );
+    });
+
+    it(`Reject the allowlist entry of another holder or another ETF`, async () => {
+      const [, otherKP] = await createFundedSdk();
+      const otherHolderEntry = await addAllowlistEntry(
+        testPrismEtf,
+        otherKP.publicKey
+      );
+      await expectTX(await startConstructionWith(otherHolderEntry)).to.be
+        .rejected;
+
+      const otherPrismEtf = await initTestPrismEtf([
+        { mint: await createTestMint(decimals), weight },
+      ]);
+      const otherPrismEtfEntry = await addAllowlistEntry(
+        otherPrismEtf,
+        holderKP.publicKey
+      );
+      await expectTX(await startConstructionWith(otherPrismEtfEntry)).to.be
+        .rejected;
+    });
+
+    it(`Let removed holders redeem, but not construct`, async () => {
+      const [allowlistEntry] = await generateAllowlistEntryAddress(
+        testPrismEtf.prismEtfPda,
+        holderKP.publicKey
+      );
+      const payerBalanceBefore =
+        await coherenceHelper.provider.connection.getBalance(payerKP.publicKey);
+      const rent = (
+        await coherenceHelper.provider.connection.getAccountInfo(allowlistEntry)
+      )?.lamports;
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope([
+          coherenceHelper.sdk.loader.program.instruction.removeAllowlistEntry({
+            accounts: {
+              prismEtfMint: testPrismEtf.prismEtfMint,
+              holder: holderKP.publicKey,
+              manager: coherenceHelper.authority,
+              payer: payerKP.publicKey,
+              prismEtf: testPrismEtf.prismEtfPda,
+              allowlistEntry,
+              beamsplitter: coherenceHelper.beamsplitter,
+            },
+          }),
+        ])
+      ).to.be.fulfilled;
+      expect(
+        await coherenceHelper.provider.connection.getAccountInfo(allowlistEntry)
+      ).to.be.null;
+
+      // The rent goes back to whoever paid for the entry
+      expect(
+        (await coherenceHelper.provider.connection.getBalance(
+          payerKP.publicKey
+        )) - payerBalanceBefore
+      ).to.equal(rent);
+
+      await expectTX(await startOrder(OrderType.CONSTRUCTION)).to.be.rejected;
+
+      // Less than was constructed, as the construction fee was taken from it
+      await expectTX(
+        await startOrder(OrderType.DECONSTRUCTION, AMOUNT_TO_CONSTRUCT.divn(2))
+      ).to.be.fulfilled;
+      await refreshHolderPrismEtf();
+      for (const decohereChunk of await holderPrismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+      await refreshHolderPrismEtf();
+      await expectTX(await holderPrismEtf.finalizeOrder({})).to.be.fulfilled;
+
+      // Lifting the requirement lets anyone construct again
+      await expectTX(setPermissioned(false)).to.be.fulfilled;
+      await refreshHolderPrismEtf();
+      await expectTX(await startOrder(OrderType.CONSTRUCTION)).to.be.fulfilled;
+      await construct();
+    });
+  });
+}
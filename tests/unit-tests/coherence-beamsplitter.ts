@@ -3,7 +3,10 @@ import "chai-bn";
 
 import { chaiSolana, expectTX } from "@saberhq/chai-solana";
 import type { PublicKey } from "@saberhq/solana-contrib";
-import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import {
+  PendingTransaction,
+  TransactionEnvelope,
+} from "@saberhq/solana-contrib";
 import {
   createInitMintInstructions,
   createMintToInstruction,
@@ -13,13 +16,15 @@ import {
   getTokenAccount,
   u64,
 } from "@saberhq/token-utils";
-import { Keypair } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { BN } from "bn.js";
 import chai, { assert, expect } from "chai";
 
 import type { UserPrismEtf, WeightedToken } from "../../src";
 import {
+  CoherenceSDK,
   enumLikeToString,
+  getWeightedAmount,
   OrderType,
   PRISM_ETF_DECIMALS,
   PrismEtf,
@@ -28,6 +33,9 @@ import { coherenceHelper } from "../coherenceBeamsplitterTest";
 
 chai.use(chaiSolana);
 
+const fetchFeeVault = async (feeVault: PublicKey) =>
+  await coherenceHelper.sdk.loader.program.account.feeVault.fetch(feeVault);
+
 export default function constructDeconstruct() {
   describe("Construct & Deconstruct", () => {
     let prismEtfMint: PublicKey;
@@ -279,13 +287,12 @@ export default function constructDeconstruct() {
         return new Error("weight A undefined");
       }
 
-      const scalarA = new BN(10).pow(new BN(PRISM_ETF_DECIMALS));
-
-      let expectedADiff = AMOUNT_TO_CONSTRUCT.mul(
-        new BN(weightedTokens[0]?.weight)
-      )
-        .div(new BN(scalarA))
-        .add(new BN(1));
+      let expectedADiff = getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        new BN(weightedTokens[0]?.weight),
+        PRISM_ETF_DECIMALS,
+        decimalsA
+      ).add(new BN(1));
 
       expectedADiff = expectedADiff.lte(new BN(0)) ? new BN(1) : expectedADiff;
 
@@ -301,13 +308,12 @@ export default function constructDeconstruct() {
         return new Error("weight B undefined");
       }
 
-      const scalarB = new BN(10).pow(new BN(PRISM_ETF_DECIMALS));
-
-      let expectedBDiff = AMOUNT_TO_CONSTRUCT.mul(
-        new BN(weightedTokens[1]?.weight)
-      )
-        .div(new BN(scalarB))
-        .add(new BN(1));
+      let expectedBDiff = getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        new BN(weightedTokens[1]?.weight),
+        PRISM_ETF_DECIMALS,
+        decimalsB
+      ).add(new BN(1));
 
       expectedBDiff = expectedBDiff.lte(new BN(0)) ? new BN(1) : expectedBDiff;
 
@@ -341,7 +347,12 @@ export default function constructDeconstruct() {
 
       const etfBalanceDiff = etfBalanceAfter.sub(etfBalanceBefore);
 
-      assert(etfBalanceDiff.eq(AMOUNT_TO_CONSTRUCT));
+      // The fees are minted to the fee vault
+      assert(
+        etfBalanceDiff.eq(
+          prismEtf.getConstructionFeeSplit(AMOUNT_TO_CONSTRUCT).orderer
+        )
+      );
 
       await refreshPrismEtf();
 
@@ -440,15 +451,18 @@ export default function constructDeconstruct() {
 
       // ==== CONSTRUCT TOKENS (Prerequisite) ====
 
+      // Enough for this order and the next test's after fees
+      const AMOUNT_TO_CONSTRUCT = AMOUNT_TO_DECONSTRUCT.mul(new BN(2));
+
       const prestartOrder = await prismEtf.startOrder({
         type: OrderType.CONSTRUCTION,
-        amount: AMOUNT_TO_DECONSTRUCT,
+        amount: AMOUNT_TO_CONSTRUCT,
       });
 
       await expectTX(prestartOrder).to.be.fulfilled;
 
       const cohere = await prismEtf.cohere({
-        orderStateAmount: AMOUNT_TO_DECONSTRUCT,
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
       });
 
       await Promise.all(
@@ -511,7 +525,10 @@ export default function constructDeconstruct() {
 
       // ==== CHECK ETF BALANCE DIFF =====
 
-      const expectedOrdererDiff = new BN(0);
+      // Finalizing the cancelled order mints the burned tokens back, charging the construction fee again
+      const expectedOrdererDiff = prismEtf
+        .getConstructionFeeSplit(AMOUNT_TO_DECONSTRUCT)
+        .orderer.sub(AMOUNT_TO_DECONSTRUCT);
 
       const etfBalanceAfterOrderer = (
         await getTokenAccount(coherenceHelper.provider, etfATAAddress)
@@ -621,11 +638,12 @@ export default function constructDeconstruct() {
         return new Error("weight A undefined");
       }
 
-      const scalarA = new BN(10).pow(new BN(PRISM_ETF_DECIMALS));
-
-      const expectedADiff = AMOUNT_TO_DECONSTRUCT.mul(
-        new BN(weightedTokens[0]?.weight)
-      ).div(new BN(scalarA));
+      const expectedADiff = getWeightedAmount(
+        AMOUNT_TO_DECONSTRUCT,
+        new BN(weightedTokens[0]?.weight),
+        PRISM_ETF_DECIMALS,
+        decimalsA
+      );
 
       assert(actualTokenABalDiff.eq(new BN(expectedADiff)));
 
@@ -639,11 +657,12 @@ export default function constructDeconstruct() {
         return new Error("weight B undefined");
       }
 
-      const scalarB = new BN(10).pow(new BN(PRISM_ETF_DECIMALS));
-
-      const expectedBDiff = AMOUNT_TO_DECONSTRUCT.mul(
-        new BN(weightedTokens[1]?.weight)
-      ).div(new BN(scalarB));
+      const expectedBDiff = getWeightedAmount(
+        AMOUNT_TO_DECONSTRUCT,
+        new BN(weightedTokens[1]?.weight),
+        PRISM_ETF_DECIMALS,
+        decimalsB
+      );
 
       assert(actualTokenBBalDiff.eq(new BN(expectedBDiff)));
 
@@ -702,119 +721,187 @@ export default function constructDeconstruct() {
         (await getMintInfo(coherenceHelper.provider, prismEtfMint)).decimals;
       const AMOUNT_TO_CONSTRUCT = new BN(1).mul(new BN(_scalar));
 
+      await refreshPrismEtf();
+
       const etfATAAddress = await getATAAddress({
         mint: prismEtfMint,
         owner: coherenceHelper.authority,
       });
 
+      const feeVaultEtfAta = await getATAAddress({
+        mint: prismEtfMint,
+        owner: prismEtf.feeVaultPda,
+      });
+
       const etfBalanceBeforeOrderer = (
         await getTokenAccount(coherenceHelper.provider, etfATAAddress)
       ).amount;
 
-      const newOwner = Keypair.generate();
-      const newManager = Keypair.generate();
+      const etfBalanceBeforeFeeVault = (
+        await getTokenAccount(coherenceHelper.provider, feeVaultEtfAta)
+      ).amount;
 
-      const ownerAta = await getATAAddress({
-        mint: prismEtfMint,
-        owner: newOwner.publicKey,
-      });
+      const feeVaultBefore = await fetchFeeVault(prismEtf.feeVaultPda);
 
-      const managerAta = await getATAAddress({
-        mint: prismEtfMint,
-        owner: newManager.publicKey,
+      const startOrder = await prismEtf.startOrder({
+        type: OrderType.CONSTRUCTION,
+        amount: AMOUNT_TO_CONSTRUCT,
       });
 
-      const etfBalanceBeforeOwner = new BN(0);
-      const etfBalanceBeforeManager = new BN(0);
+      await expectTX(startOrder).to.be.fulfilled;
 
-      const setOwner = coherenceHelper.sdk.beamsplitter.setOwner({
-        newOwner: newOwner.publicKey,
+      const cohere = await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
       });
 
-      await expectTX(setOwner).to.be.fulfilled;
+      await Promise.all(
+        cohere.map((cohereChunk) => expectTX(cohereChunk).to.be.fulfilled)
+      );
 
-      await coherenceHelper.sdk.refreshBeamsplitter();
+      await refreshPrismEtf();
+
+      const finalizeOrder = await prismEtf.finalizeOrder({});
+
+      await expectTX(finalizeOrder).to.be.fulfilled;
+
+      const feeSplit = prismEtf.getConstructionFeeSplit(AMOUNT_TO_CONSTRUCT);
+
+      // ==== CHECK FEES ACCRUED IN THE FEE VAULT =====
+
+      const etfBalanceAfterOrderer = (
+        await getTokenAccount(coherenceHelper.provider, etfATAAddress)
+      ).amount;
+
+      const etfBalanceAfterFeeVault = (
+        await getTokenAccount(coherenceHelper.provider, feeVaultEtfAta)
+      ).amount;
+
+      const feeVaultAfter = await fetchFeeVault(prismEtf.feeVaultPda);
 
       assert(
-        coherenceHelper.sdk.beamsplitter.beamsplitterData?.owner.equals(
-          newOwner.publicKey
-        )
+        etfBalanceAfterOrderer.sub(etfBalanceBeforeOrderer).eq(feeSplit.orderer)
+      );
+      assert(
+        etfBalanceAfterFeeVault
+          .sub(etfBalanceBeforeFeeVault)
+          .eq(feeSplit.owner.add(feeSplit.manager))
+      );
+      assert(
+        feeVaultAfter.protocolFees
+          .sub(feeVaultBefore.protocolFees)
+          .eq(feeSplit.owner)
+      );
+      assert(
+        feeVaultAfter.managerFees
+          .sub(feeVaultBefore.managerFees)
+          .eq(feeSplit.manager)
       );
 
-      const setManager = prismEtf.setManager({
-        newManager: newManager.publicKey,
-      });
+      // ==== OWNER WITHDRAWS =====
 
-      await expectTX(setManager).to.be.fulfilled;
+      const newOwner = Keypair.generate();
 
-      await refreshPrismEtf();
+      await expectTX(
+        new PendingTransaction(
+          coherenceHelper.provider.connection,
+          await coherenceHelper.provider.connection.requestAirdrop(
+            newOwner.publicKey,
+            LAMPORTS_PER_SOL
+          )
+        )
+      ).to.be.fulfilled;
 
-      if (!prismEtf) {
-        assert.fail("Prism Etf was not successfully created");
-      }
+      const setOwner = coherenceHelper.sdk.beamsplitter.setOwner({
+        newOwner: newOwner.publicKey,
+      });
 
-      assert(prismEtf.prismEtfData?.manager.equals(newManager.publicKey));
+      await expectTX(setOwner).to.be.fulfilled;
 
-      const startOrder = await prismEtf.startOrder({
-        type: OrderType.CONSTRUCTION,
-        amount: AMOUNT_TO_CONSTRUCT,
+      const ownerSdk = await CoherenceSDK.initWithSigner({
+        provider: coherenceHelper.provider,
+        signer: newOwner,
       });
 
-      await expectTX(startOrder).to.be.fulfilled;
-
-      const cohere = await prismEtf.cohere({
-        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      const ownerPrismEtf = await PrismEtf.loadPrismEtf({
+        beamsplitter: ownerSdk.beamsplitter,
+        prismEtfMint,
+        userPrismEtf: {} as UserPrismEtf,
       });
 
-      await Promise.all(
-        cohere.map((cohereChunk) => expectTX(cohereChunk).to.be.fulfilled)
-      );
+      // Only the manager can withdraw the manager's fees
+      await expectTX(
+        await ownerPrismEtf.withdrawManagerFees({ amount: feeSplit.manager })
+      ).to.be.rejected;
 
-      await refreshPrismEtf();
+      // No more than what accrued can be withdrawn
+      await expectTX(
+        await ownerPrismEtf.withdrawProtocolFees({
+          amount: feeVaultAfter.protocolFees.add(new BN(1)),
+        })
+      ).to.be.rejected;
 
-      if (!prismEtf.prismEtfData?.manager) {
-        return new Error("Manager undefined");
-      }
+      await expectTX(
+        await ownerPrismEtf.withdrawProtocolFees({ amount: feeSplit.owner })
+      ).to.be.fulfilled;
 
-      const finalizeOrder = await prismEtf.finalizeOrder({});
+      const ownerAta = await getATAAddress({
+        mint: prismEtfMint,
+        owner: newOwner.publicKey,
+      });
 
-      await expectTX(finalizeOrder).to.be.fulfilled;
+      assert(
+        (await getTokenAccount(coherenceHelper.provider, ownerAta)).amount.eq(
+          feeSplit.owner
+        )
+      );
 
-      const etfBalanceAfterOwner = (
-        await getTokenAccount(coherenceHelper.provider, ownerAta)
-      ).amount;
+      // ==== MANAGER WITHDRAWS =====
 
-      const etfBalanceAfterManager = (
-        await getTokenAccount(coherenceHelper.provider, managerAta)
-      ).amount;
+      // The old owner is no longer allowed to withdraw the protocol's fees
+      await expectTX(
+        await prismEtf.withdrawProtocolFees({ amount: new BN(1) })
+      ).to.be.rejected;
 
-      const etfBalanceAfterOrderer = (
+      const etfBalanceBeforeManager = (
         await getTokenAccount(coherenceHelper.provider, etfATAAddress)
       ).amount;
 
-      const feePortion = AMOUNT_TO_CONSTRUCT.mul(
-        new BN(prismEtf.prismEtfData.constructionBps)
-      ).div(new BN(10 ** 4));
-
-      const expectedOrdererDiff = AMOUNT_TO_CONSTRUCT.sub(feePortion);
+      await expectTX(
+        await prismEtf.withdrawManagerFees({ amount: feeSplit.manager })
+      ).to.be.fulfilled;
 
-      const expectedManagerDiff = feePortion
-        .mul(new BN(prismEtf.prismEtfData.managerCut))
-        .div(new BN(10 ** 4));
+      const etfBalanceAfterManager = (
+        await getTokenAccount(coherenceHelper.provider, etfATAAddress)
+      ).amount;
 
-      const expectedOwnerDiff = feePortion.sub(expectedManagerDiff);
+      assert(
+        etfBalanceAfterManager.sub(etfBalanceBeforeManager).eq(feeSplit.manager)
+      );
 
-      const actualOrdererDiff = etfBalanceAfterOrderer.sub(
-        etfBalanceBeforeOrderer
+      const feeVaultWithdrawn = await fetchFeeVault(prismEtf.feeVaultPda);
+      assert(
+        feeVaultWithdrawn.protocolFees.eq(
+          feeVaultAfter.protocolFees.sub(feeSplit.owner)
+        )
       );
-      const actualOwnerDiff = etfBalanceAfterOwner.sub(etfBalanceBeforeOwner);
-      const actualManagerDiff = etfBalanceAfterManager.sub(
-        etfBalanceBeforeManager
+      assert(
+        feeVaultWithdrawn.managerFees.eq(
+          feeVaultAfter.managerFees.sub(feeSplit.manager)
+        )
       );
 
-      assert(expectedOrdererDiff.eq(actualOrdererDiff));
-      assert(expectedOwnerDiff.eq(actualOwnerDiff));
-      assert(expectedManagerDiff.eq(actualManagerDiff));
+      // Hand ownership back for the tests that follow
+      await expectTX(
+        ownerSdk.beamsplitter.setOwner({ newOwner: coherenceHelper.authority })
+      ).to.be.fulfilled;
+
+      await coherenceHelper.sdk.refreshBeamsplitter();
+
+      assert(
+        coherenceHelper.sdk.beamsplitter.beamsplitterData?.owner.equals(
+          coherenceHelper.authority
+        )
+      );
     });
 
     it(`Cancel CONSTRUCT order`, async () => {
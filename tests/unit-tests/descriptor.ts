@@ -0,0 +1,170 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { Keypair } from "@solana/web3.js";
+import { PublicKey, SystemProgram, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { CoherenceSDK } from "../../src";
+import { PRISM_ETF_DECIMALS, PROGRAM_ID } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createFundedSdk,
+  createTestMint,
+  initTestPrismEtf,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+// Descriptor text is stored zero padded
+const unpad = (bytes: number[]): string =>
+  Buffer.from(bytes).toString().replace(/\0+$/, "");
+
+export default function descriptor() {
+  describe("Prism ETF Descriptor", () => {
+    const descriptionHash = Array.from(Buffer.alloc(32, 7));
+
+    let testPrismEtf: TestPrismEtf;
+    let descriptorPda: PublicKey;
+    let descriptorBump: number;
+    let payerKP: Keypair;
+
+    const fetchDescriptor = async () =>
+      await coherenceHelper.sdk.loader.program.account.prismEtfDescriptor.fetch(
+        descriptorPda
+      );
+
+    const setPrismEtfDescriptor = (
+      name: string,
+      symbol: string,
+      tags: string[],
+      sdk: CoherenceSDK = coherenceHelper.sdk
+    ) =>
+      sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.setPrismEtfDescriptor(
+          name,
+          symbol,
+          descriptionHash,
+          tags,
+          {
+            accounts: {
+              prismEtfMint: testPrismEtf.prismEtfMint,
+              manager: sdk.loader.getUserPublicKey(),
+              prismEtf: testPrismEtf.prismEtfPda,
+              descriptor: descriptorPda,
+              beamsplitter: coherenceHelper.beamsplitter,
+            },
+          }
+        ),
+      ]);
+
+    before(async () => {
+      const mint = await createTestMint(6);
+      testPrismEtf = await initTestPrismEtf([
+        { mint, weight: new BN(10 ** PRISM_ETF_DECIMALS) },
+      ]);
+
+      [descriptorPda, descriptorBump] = await PublicKey.findProgramAddress(
+        [
+          Buffer.from("PrismEtfDescriptor"),
+          testPrismEtf.prismEtfPda.toBuffer(),
+        ],
+        PROGRAM_ID
+      );
+
+      [, payerKP] = await createFundedSdk();
+    });
+
+    it(`Describe a Prism ETF, paid for by a key other than the manager`, async () => {
+      const { program } = coherenceHelper.sdk.loader;
+      const managerBalanceBefore =
+        await coherenceHelper.provider.connection.getBalance(
+          coherenceHelper.authority
+        );
+
+      await expectTX(
+        new TransactionEnvelope(
+          coherenceHelper.provider,
+          [
+            program.instruction.initPrismEtfDescriptor(
+              descriptorBump,
+              "Coherence Test ETF",
+              "CTE",
+              descriptionHash,
+              ["defi", "index"],
+              {
+                accounts: {
+                  prismEtfMint: testPrismEtf.prismEtfMint,
+                  manager: coherenceHelper.authority,
+                  payer: payerKP.publicKey,
+                  prismEtf: testPrismEtf.prismEtfPda,
+                  descriptor: descriptorPda,
+                  beamsplitter: coherenceHelper.beamsplitter,
+                  systemProgram: SystemProgram.programId,
+                  clock: SYSVAR_CLOCK_PUBKEY,
+                },
+              }
+            ),
+          ],
+          [payerKP]
+        )
+      ).to.be.fulfilled;
+
+      const descriptorData = await fetchDescriptor();
+      expect(descriptorData.prismEtf).to.eqAddress(testPrismEtf.prismEtfPda);
+      expect(unpad(descriptorData.name)).to.be.equal("Coherence Test ETF");
+      expect(unpad(descriptorData.symbol)).to.be.equal("CTE");
+      expect(descriptorData.descriptionHash).to.deep.equal(descriptionHash);
+      expect(descriptorData.tagsLength).to.be.equal(2);
+      expect(unpad(descriptorData.tags[1])).to.be.equal("index");
+      expect(descriptorData.createdAt.toNumber()).to.be.greaterThan(0);
+
+      // The manager only paid the transaction fee, the payer funded the account
+      const managerBalanceAfter =
+        await coherenceHelper.provider.connection.getBalance(
+          coherenceHelper.authority
+        );
+      expect(managerBalanceBefore - managerBalanceAfter).to.be.lessThan(
+        10_000
+      );
+    });
+
+    it(`Update the descriptor, keeping its creation time`, async () => {
+      const { createdAt } = await fetchDescriptor();
+
+      await expectTX(setPrismEtfDescriptor("Coherence Renamed ETF", "CRE", []))
+        .to.be.fulfilled;
+
+      const descriptorData = await fetchDescriptor();
+      expect(unpad(descriptorData.name)).to.be.equal("Coherence Renamed ETF");
+      expect(unpad(descriptorData.symbol)).to.be.equal("CRE");
+      expect(descriptorData.tagsLength).to.be.equal(0);
+      expect(descriptorData.createdAt).to.be.bignumber.equal(createdAt);
+    });
+
+    it(`Reject text that does not fit the descriptor`, async () => {
+      await expectTX(setPrismEtfDescriptor("A".repeat(33), "CRE", [])).to.be
+        .rejected;
+      await expectTX(setPrismEtfDescriptor("Renamed", "SYMBOLTOOLONG", [])).to
+        .be.rejected;
+      await expectTX(
+        setPrismEtfDescriptor("Renamed", "CRE", new Array<string>(9).fill("t"))
+      ).to.be.rejected;
+    });
+
+    it(`Reject describing an ETF without being its manager`, async () => {
+      const [otherSdk] = await createFundedSdk();
+
+      await expectTX(setPrismEtfDescriptor("Hijacked", "HJK", [], otherSdk)).to
+        .be.rejected;
+
+      expect(unpad((await fetchDescriptor()).name)).to.be.equal(
+        "Coherence Renamed ETF"
+      );
+    });
+  });
+}
@@ -0,0 +1,229 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import type { Event } from "@project-serum/anchor";
+import { EventParser } from "@project-serum/anchor";
+import { chaiSolana } from "@saberhq/chai-solana";
+import type { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  enumLikeToString,
+  getWeightedAmount,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  WeightMode,
+} from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createTestMint,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function events() {
+  describe("Events", () => {
+    const decimalsA = 6;
+    const decimalsB = 9;
+    const weightA = new BN(2 * 10 ** 9);
+    const weightB = new BN(5 * 10 ** 8);
+    const AMOUNT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let mintA: PublicKey;
+    let mintB: PublicKey;
+    let prismEtf: PrismEtf;
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    // Send envelopes in order, collecting the events their logs carry
+    const sendForEvents = async (
+      envelopes: TransactionEnvelope[]
+    ): Promise<Event[]> => {
+      const eventParser = new EventParser(
+        coherenceHelper.sdk.loader.program.programId,
+        coherenceHelper.sdk.loader.program.coder
+      );
+
+      const emitted: Event[] = [];
+      for (const envelope of envelopes) {
+        const receipt = await envelope.confirm();
+        eventParser.parseLogs(
+          receipt.response.meta?.logMessages ?? [],
+          (event) => emitted.push(event)
+        );
+      }
+      return emitted;
+    };
+
+    const named = (emitted: Event[], name: string) =>
+      emitted.filter((event) => event.name === name).map(({ data }) => data);
+
+    before(async () => {
+      mintA = await createTestMint(decimalsA);
+      mintB = await createTestMint(decimalsB);
+      await mintTestTokens(mintA, new BN(1_000 * 10 ** decimalsA));
+      await mintTestTokens(mintB, new BN(1_000).mul(new BN(10 ** decimalsB)));
+    });
+
+    it(`Emit the creation, pushed tokens and finalization of an ETF`, async () => {
+      const [initPrismEtfTx, prismEtfMint, prismEtfPda, weightedTokensAcct] =
+        await coherenceHelper.sdk.beamsplitter.initPrismEtf({});
+      const [created] = named(
+        await sendForEvents([initPrismEtfTx]),
+        "PrismEtfCreated"
+      );
+      expect(created?.prismEtfMint).to.eqAddress(prismEtfMint);
+      expect(created?.manager).to.eqAddress(coherenceHelper.authority);
+      expect(created?.weightedTokens).to.eqAddress(weightedTokensAcct);
+
+      const pushed = named(
+        await sendForEvents(
+          await coherenceHelper.sdk.beamsplitter.pushTokens({
+            prismEtfMint,
+            prismEtfPda,
+            weightedTokens: [
+              { mint: mintA, weight: weightA },
+              { mint: mintB, weight: weightB },
+            ],
+            weightedTokensAcct,
+          })
+        ),
+        "TokensPushed"
+      );
+      expect(pushed.flatMap(({ mints }) => mints)).to.have.length(2);
+      expect(pushed[pushed.length - 1]?.length).to.equal(2);
+
+      const [finalized] = named(
+        await sendForEvents([
+          await coherenceHelper.sdk.beamsplitter.finalizePrismEtf({
+            prismEtfMint,
+            prismEtfPda,
+          }),
+        ]),
+        "PrismEtfFinalized"
+      );
+      expect(finalized?.prismEtfMint).to.eqAddress(prismEtfMint);
+      expect(enumLikeToString(finalized?.weightMode)).to.equal(
+        WeightMode.QUANTITY
+      );
+      expect(finalized?.length).to.equal(2);
+
+      prismEtf = await loadTestPrismEtf(prismEtfMint);
+    });
+
+    it(`Emit each step of a construction`, async () => {
+      const [orderStateCreated] = named(
+        await sendForEvents([await prismEtf.initOrderState()]),
+        "OrderStateCreated"
+      );
+      await refreshPrismEtf();
+      expect(orderStateCreated?.orderer).to.eqAddress(
+        coherenceHelper.authority
+      );
+      expect(orderStateCreated?.orderState).to.eqAddress(
+        prismEtf.orderStatePda
+      );
+
+      const [started] = named(
+        await sendForEvents([
+          await prismEtf.startOrder({
+            type: OrderType.CONSTRUCTION,
+            amount: AMOUNT,
+          }),
+        ]),
+        "OrderStarted"
+      );
+      await refreshPrismEtf();
+      expect(enumLikeToString(started?.orderType)).to.equal(
+        OrderType.CONSTRUCTION
+      );
+      expect(started?.amount).to.be.bignumber.equal(AMOUNT);
+
+      // One per asset, each one more than its weighted amount
+      const cohered = named(
+        await sendForEvents(
+          await prismEtf.cohere({ orderStateAmount: AMOUNT })
+        ),
+        "AssetCohered"
+      );
+      await refreshPrismEtf();
+      expect(cohered.map(({ index }) => index)).to.deep.equal([0, 1]);
+      const amountA = getWeightedAmount(
+        AMOUNT,
+        weightA,
+        PRISM_ETF_DECIMALS,
+        decimalsA
+      );
+      const amountB = getWeightedAmount(
+        AMOUNT,
+        weightB,
+        PRISM_ETF_DECIMALS,
+        decimalsB
+      );
+      expect(cohered[0]?.mint).to.eqAddress(mintA);
+      expect(cohered[0]?.amount).to.be.bignumber.equal(amountA.addn(1));
+      expect(cohered[1]?.mint).to.eqAddress(mintB);
+      expect(cohered[1]?.amount).to.be.bignumber.equal(amountB.addn(1));
+
+      const [finalized] = named(
+        await sendForEvents([await prismEtf.finalizeOrder({})]),
+        "OrderFinalized"
+      );
+      await refreshPrismEtf();
+      const { orderer, owner, manager } =
+        prismEtf.getConstructionFeeSplit(AMOUNT);
+      expect(finalized?.amount).to.be.bignumber.equal(AMOUNT);
+      expect(finalized?.ordererAmount).to.be.bignumber.equal(orderer);
+      expect(finalized?.ownerFee).to.be.bignumber.equal(owner);
+      expect(finalized?.managerFee).to.be.bignumber.equal(manager);
+    });
+
+    it(`Emit each asset paid out by a deconstruction`, async () => {
+      // Less than was constructed, as the construction fee was taken from it
+      const amount = AMOUNT.divn(2);
+
+      const [started] = named(
+        await sendForEvents([
+          await prismEtf.startOrder({
+            type: OrderType.DECONSTRUCTION,
+            amount,
+          }),
+        ]),
+        "OrderStarted"
+      );
+      await refreshPrismEtf();
+      expect(enumLikeToString(started?.orderType)).to.equal(
+        OrderType.DECONSTRUCTION
+      );
+
+      const decohered = named(
+        await sendForEvents(await prismEtf.decohere({})),
+        "AssetDecohered"
+      );
+      await refreshPrismEtf();
+      expect(decohered.map(({ index }) => index)).to.deep.equal([0, 1]);
+      expect(decohered[0]?.amount).to.be.bignumber.equal(
+        getWeightedAmount(amount, weightA, PRISM_ETF_DECIMALS, decimalsA)
+      );
+      expect(decohered[1]?.amount).to.be.bignumber.equal(
+        getWeightedAmount(amount, weightB, PRISM_ETF_DECIMALS, decimalsB)
+      );
+
+      const [finalized] = named(
+        await sendForEvents([await prismEtf.finalizeOrder({})]),
+        "OrderFinalized"
+      );
+      expect(enumLikeToString(finalized?.orderType)).to.equal(
+        OrderType.DECONSTRUCTION
+      );
+      expect(finalized?.ordererAmount).to.be.bignumber.equal(new BN(0));
+    });
+  });
+}
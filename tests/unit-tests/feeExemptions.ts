@@ -0,0 +1,331 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { getATAAddress, TOKEN_PROGRAM_ID } from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { SystemProgram } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { CoherenceSDK, PrismEtf } from "../../src";
+import {
+  FeeMode,
+  generateFeeExemptionAddress,
+  getWeightedAmount,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  stringToEnumLike,
+} from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createFundedSdk,
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+import {
+  finalizeTestSingleAssetOrder,
+  setTestSwapProgram,
+  startTestSingleAssetOrder,
+  swapTestCohere,
+} from "../singleAssetHelper";
+
+chai.use(chaiSolana);
+
+export default function feeExemptions() {
+  describe("Fee Exemptions", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let prismEtf: PrismEtf;
+    let ordererEtfAta: PublicKey;
+    let feeVaultEtfAta: PublicKey;
+    let outsiderSdk: CoherenceSDK;
+    let outsider: PublicKey;
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const feeExemptionAddress = async (
+      exempt: PublicKey,
+      etf: PrismEtf = prismEtf
+    ) => (await generateFeeExemptionAddress(etf.prismEtfPda, exempt))[0];
+
+    const addFeeExemption = async (
+      exempt: PublicKey,
+      constructionBps: number,
+      sdk: CoherenceSDK = coherenceHelper.sdk,
+      etf: PrismEtf = prismEtf
+    ) => {
+      const [feeExemption, bump] = await generateFeeExemptionAddress(
+        etf.prismEtfPda,
+        exempt
+      );
+      return sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.addFeeExemption(bump, constructionBps, {
+          accounts: {
+            prismEtfMint: etf.prismEtfMint,
+            exempt,
+            authority: sdk.loader.getUserPublicKey(),
+            payer: sdk.loader.getUserPublicKey(),
+            prismEtf: etf.prismEtfPda,
+            feeExemption,
+            beamsplitter: coherenceHelper.beamsplitter,
+            systemProgram: SystemProgram.programId,
+          },
+        }),
+      ]);
+    };
+
+    const removeFeeExemption = async (exempt: PublicKey) =>
+      coherenceHelper.sdk.loader.makeProviderEnvelope([
+        coherenceHelper.sdk.loader.program.instruction.removeFeeExemption({
+          accounts: {
+            prismEtfMint: prismEtf.prismEtfMint,
+            exempt,
+            authority: coherenceHelper.authority,
+            payer: coherenceHelper.authority,
+            prismEtf: prismEtf.prismEtfPda,
+            feeExemption: await feeExemptionAddress(exempt),
+            beamsplitter: coherenceHelper.beamsplitter,
+            systemProgram: SystemProgram.programId,
+          },
+        }),
+      ]);
+
+    // Finalize the open order, passing exempt's fee exemption when given
+    const finalizeOrder = async (exempt?: PublicKey) => {
+      const envelope = await prismEtf.finalizeOrder({});
+      const finalizeIx =
+        envelope.instructions[envelope.instructions.length - 1];
+      if (finalizeIx === undefined) {
+        assert.fail("finalizeOrder returned no instructions");
+      }
+      if (exempt !== undefined) {
+        finalizeIx.keys.push({
+          pubkey: await feeExemptionAddress(exempt),
+          isSigner: false,
+          isWritable: false,
+        });
+      }
+      return envelope;
+    };
+
+    // Start and cohere a CONSTRUCTION, leaving it to be finalized
+    const cohereConstruction = async () => {
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+    };
+
+    before(async () => {
+      const mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+
+      prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint, weight }])
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      ordererEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: coherenceHelper.authority,
+      });
+      feeVaultEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: prismEtf.feeVaultPda,
+      });
+      [outsiderSdk] = await createFundedSdk();
+      outsider = outsiderSdk.loader.getUserPublicKey();
+    });
+
+    it(`Only let the fee admin add exemptions`, async () => {
+      await expectTX(
+        await addFeeExemption(coherenceHelper.authority, 0, outsiderSdk)
+      ).to.be.rejected;
+
+      await expectTX(await addFeeExemption(coherenceHelper.authority, 0)).to.be
+        .fulfilled;
+      const feeExemptionData =
+        await coherenceHelper.sdk.loader.program.account.feeExemption.fetch(
+          await feeExemptionAddress(coherenceHelper.authority)
+        );
+      expect(feeExemptionData.prismEtf).to.eqAddress(prismEtf.prismEtfPda);
+      expect(feeExemptionData.exempt).to.eqAddress(coherenceHelper.authority);
+      expect(feeExemptionData.payer).to.eqAddress(coherenceHelper.authority);
+      expect(feeExemptionData.constructionBps).to.equal(0);
+    });
+
+    it(`Waive the construction fee of an orderer passing their exemption`, async () => {
+      const ordererBefore = await ataBalance(ordererEtfAta);
+      const feesBefore = await ataBalance(feeVaultEtfAta);
+
+      await cohereConstruction();
+      await expectTX(await finalizeOrder(coherenceHelper.authority)).to.be
+        .fulfilled;
+
+      expect(
+        (await ataBalance(ordererEtfAta)).sub(ordererBefore)
+      ).to.be.bignumber.equal(AMOUNT_TO_CONSTRUCT);
+      expect(await ataBalance(feeVaultEtfAta)).to.be.bignumber.equal(
+        feesBefore
+      );
+    });
+
+    it(`Reject another orderer's exemption, and charge the fee without one`, async () => {
+      await expectTX(await addFeeExemption(outsider, 0)).to.be.fulfilled;
+
+      await cohereConstruction();
+      await expectTX(await finalizeOrder(outsider)).to.be.rejected;
+
+      const feesBefore = await ataBalance(feeVaultEtfAta);
+      await expectTX(await finalizeOrder()).to.be.fulfilled;
+      const { owner, manager } =
+        prismEtf.getConstructionFeeSplit(AMOUNT_TO_CONSTRUCT);
+      assert(owner.add(manager).gtn(0), "The ETF must charge a fee");
+      expect(
+        (await ataBalance(feeVaultEtfAta)).sub(feesBefore)
+      ).to.be.bignumber.equal(owner.add(manager));
+    });
+
+    it(`Waive the UNDERLYING fee of a single asset order passing the exemption`, async () => {
+      const mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+
+      const testPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope([
+          coherenceHelper.sdk.loader.program.instruction.setFeeMode(
+            stringToEnumLike(FeeMode.UNDERLYING),
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+      let underlyingPrismEtf = await finalizeTestPrismEtf(testPrismEtf);
+      await expectTX(await underlyingPrismEtf.initOrderState()).to.be
+        .fulfilled;
+      underlyingPrismEtf = await loadTestPrismEtf(
+        underlyingPrismEtf.prismEtfMint
+      );
+
+      const inputAmount = getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      );
+      assert(
+        underlyingPrismEtf
+          .getAssetFee(inputAmount, AMOUNT_TO_CONSTRUCT)
+          .gtn(0),
+        "The ETF must charge a fee"
+      );
+      const prismEtfAta = await getATAAddress({
+        mint,
+        owner: underlyingPrismEtf.prismEtfPda,
+      });
+      const underlyingOrdererEtfAta = await getATAAddress({
+        mint: underlyingPrismEtf.prismEtfMint,
+        owner: coherenceHelper.authority,
+      });
+
+      // The ETF's own asset is transferred, not swapped
+      await setTestSwapProgram(TOKEN_PROGRAM_ID);
+      try {
+        await expectTX(
+          await startTestSingleAssetOrder(
+            underlyingPrismEtf,
+            mint,
+            inputAmount,
+            new BN(0)
+          )
+        ).to.be.fulfilled;
+        underlyingPrismEtf = await loadTestPrismEtf(
+          underlyingPrismEtf.prismEtfMint
+        );
+        const exemptionMeta = async (etf: PrismEtf) => ({
+          pubkey: await feeExemptionAddress(coherenceHelper.authority, etf),
+          isSigner: false,
+          isWritable: false,
+        });
+
+        // An exemption only applies to the ETF it was added for
+        await expectTX(
+          await swapTestCohere(underlyingPrismEtf, mint, 0, inputAmount, [
+            await exemptionMeta(prismEtf),
+          ])
+        ).to.be.rejected;
+        await expectTX(
+          await addFeeExemption(
+            coherenceHelper.authority,
+            0,
+            coherenceHelper.sdk,
+            underlyingPrismEtf
+          )
+        ).to.be.fulfilled;
+        await expectTX(
+          await swapTestCohere(underlyingPrismEtf, mint, 0, inputAmount, [
+            await exemptionMeta(underlyingPrismEtf),
+          ])
+        ).to.be.fulfilled;
+        underlyingPrismEtf = await loadTestPrismEtf(
+          underlyingPrismEtf.prismEtfMint
+        );
+
+        // No fee was moved out of what backs the order
+        expect(await ataBalance(prismEtfAta)).to.be.bignumber.equal(
+          inputAmount
+        );
+        const orderAmount = underlyingPrismEtf.orderStateData?.amount;
+        if (orderAmount === undefined) {
+          assert.fail("The order state was not loaded");
+        }
+
+        await expectTX(
+          await finalizeTestSingleAssetOrder(underlyingPrismEtf, mint)
+        ).to.be.fulfilled;
+        expect(
+          await ataBalance(underlyingOrdererEtfAta)
+        ).to.be.bignumber.equal(orderAmount);
+      } finally {
+        await setTestSwapProgram(SystemProgram.programId);
+      }
+    });
+
+    it(`Close an exemption once removed`, async () => {
+      for (const exempt of [coherenceHelper.authority, outsider]) {
+        await expectTX(await removeFeeExemption(exempt)).to.be.fulfilled;
+        expect(
+          await coherenceHelper.provider.connection.getAccountInfo(
+            await feeExemptionAddress(exempt)
+          )
+        ).to.be.null;
+      }
+    });
+  });
+}
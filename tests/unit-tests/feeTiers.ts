@@ -0,0 +1,187 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import { getATAAddress } from "@saberhq/token-utils";
+import { SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import { OrderType, PRISM_ETF_DECIMALS } from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+interface TestFeeTier {
+  minAmount: BN;
+  constructionBps: number;
+}
+
+export default function feeTiers() {
+  describe("Fee Tiers", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const MAX_FEE_TIERS = 8;
+
+    let testPrismEtf: TestPrismEtf;
+    let prismEtf: PrismEtf;
+    // The ETF's construction fee without any tier
+    let baseBps: number;
+
+    const etfAmount = (wholeTokens: number) =>
+      new BN(wholeTokens).mul(new BN(10).pow(new BN(PRISM_ETF_DECIMALS)));
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(testPrismEtf.prismEtfMint);
+    };
+
+    const prismEtfAccounts = () => ({
+      prismEtfMint: testPrismEtf.prismEtfMint,
+      prismEtf: testPrismEtf.prismEtfPda,
+      authority: coherenceHelper.authority,
+      beamsplitter: coherenceHelper.beamsplitter,
+      clock: SYSVAR_CLOCK_PUBKEY,
+    });
+
+    const setFeeTiers = (tiers: TestFeeTier[]) =>
+      new TransactionEnvelope(coherenceHelper.provider, [
+        coherenceHelper.sdk.loader.program.instruction.setFeeTiers(tiers, {
+          accounts: prismEtfAccounts(),
+        }),
+      ]);
+
+    const setConstructionBps = (constructionBps: number) =>
+      new TransactionEnvelope(coherenceHelper.provider, [
+        coherenceHelper.sdk.loader.program.instruction.setConstructionBps(
+          constructionBps,
+          { accounts: prismEtfAccounts() }
+        ),
+      ]);
+
+    // Construct amount and return how many etf tokens the fee vault received
+    const constructForFee = async (amount: BN) => {
+      const feeVaultEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: prismEtf.feeVaultPda,
+      });
+      const feesBefore = await ataBalance(feeVaultEtfAta);
+
+      await expectTX(
+        await prismEtf.startOrder({ type: OrderType.CONSTRUCTION, amount })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: amount,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      return (await ataBalance(feeVaultEtfAta)).sub(feesBefore);
+    };
+
+    before(async () => {
+      const mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+
+      testPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+      await refreshPrismEtf();
+      if (!prismEtf.prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      baseBps = prismEtf.prismEtfData.constructionBps;
+    });
+
+    it(`Reject tiers that don't discount ever larger orders`, async () => {
+      // Charging more than the construction fee
+      await expectTX(
+        setFeeTiers([
+          { minAmount: etfAmount(10), constructionBps: baseBps + 1 },
+        ])
+      ).to.be.rejected;
+
+      // Not in increasing order of size
+      await expectTX(
+        setFeeTiers([
+          { minAmount: etfAmount(100), constructionBps: baseBps },
+          { minAmount: etfAmount(10), constructionBps: 0 },
+        ])
+      ).to.be.rejected;
+
+      // Charging more for a larger order
+      await expectTX(
+        setFeeTiers([
+          { minAmount: etfAmount(10), constructionBps: 0 },
+          { minAmount: etfAmount(100), constructionBps: 1 },
+        ])
+      ).to.be.rejected;
+
+      await expectTX(
+        setFeeTiers(
+          [...Array(MAX_FEE_TIERS + 1).keys()].map((index) => ({
+            minAmount: etfAmount(index + 1),
+            constructionBps: 0,
+          }))
+        )
+      ).to.be.rejected;
+    });
+
+    it(`Charge each order the fee of the largest tier it reaches`, async () => {
+      const discountedBps = Math.floor(baseBps / 2);
+
+      // The ETF is still being designed, so the tiers can be applied right away
+      await expectTX(
+        setFeeTiers([
+          { minAmount: etfAmount(10), constructionBps: discountedBps },
+          { minAmount: etfAmount(100), constructionBps: 0 },
+        ])
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(prismEtf.applyPendingChanges()).to.be.fulfilled;
+      prismEtf = await finalizeTestPrismEtf(testPrismEtf);
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      expect(prismEtf.getConstructionBps(etfAmount(9))).to.equal(baseBps);
+      expect(prismEtf.getConstructionBps(etfAmount(10))).to.equal(
+        discountedBps
+      );
+      expect(prismEtf.getConstructionBps(etfAmount(100))).to.equal(0);
+
+      for (const wholeTokens of [9, 10, 100]) {
+        const amount = etfAmount(wholeTokens);
+        const { owner, manager } = prismEtf.getConstructionFeeSplit(amount);
+        expect(await constructForFee(amount)).to.be.bignumber.equal(
+          owner.add(manager)
+        );
+      }
+
+      // Below the first tier the full fee is charged, from the last one none
+      expect(
+        prismEtf.getConstructionFeeSplit(etfAmount(9)).orderer
+      ).to.be.bignumber.lessThan(etfAmount(9));
+      expect(
+        prismEtf.getConstructionFeeSplit(etfAmount(100)).orderer
+      ).to.be.bignumber.equal(etfAmount(100));
+    });
+
+    it(`Reject a construction fee below an existing tier's`, async () => {
+      await expectTX(setConstructionBps(Math.floor(baseBps / 2) - 1)).to.be
+        .rejected;
+    });
+  });
+}
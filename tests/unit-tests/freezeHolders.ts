@@ -0,0 +1,184 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import {
+  createInitMintInstructions,
+  getATAAddress,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import type { ParsedAccountData, PublicKey } from "@solana/web3.js";
+import { Keypair } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { CoherenceSDK, PrismEtf } from "../../src";
+import { OrderType, PRISM_ETF_DECIMALS } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createFundedSdk,
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function freezeHolders() {
+  describe("Freezing Holders", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let mint: PublicKey;
+    let testPrismEtf: TestPrismEtf;
+    let prismEtf: PrismEtf;
+    let holderEtfAta: PublicKey;
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(testPrismEtf.prismEtfMint);
+    };
+
+    const setFreezable = (
+      { prismEtfMint, prismEtfPda }: TestPrismEtf,
+      freezable: boolean
+    ) =>
+      coherenceHelper.sdk.loader.makeProviderEnvelope([
+        coherenceHelper.sdk.loader.program.instruction.setFreezable(freezable, {
+          accounts: {
+            prismEtfMint,
+            manager: coherenceHelper.authority,
+            prismEtf: prismEtfPda,
+            beamsplitter: coherenceHelper.beamsplitter,
+          },
+        }),
+      ]);
+
+    // Freeze or thaw a holder's etf account, signed by the signer of sdk
+    const setFrozen = (
+      frozen: boolean,
+      tokenAccount: PublicKey = holderEtfAta,
+      sdk: CoherenceSDK = coherenceHelper.sdk
+    ) => {
+      const accounts = {
+        prismEtfMint: testPrismEtf.prismEtfMint,
+        manager: sdk.loader.getUserPublicKey(),
+        holderEtfAta: tokenAccount,
+        prismEtf: testPrismEtf.prismEtfPda,
+        beamsplitter: coherenceHelper.beamsplitter,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      };
+      return sdk.loader.makeProviderEnvelope([
+        frozen
+          ? sdk.loader.program.instruction.freezeHolder({ accounts })
+          : sdk.loader.program.instruction.thawHolder({ accounts }),
+      ]);
+    };
+
+    const fetchTokenAccountState = async (tokenAccount: PublicKey) => {
+      const { value } =
+        await coherenceHelper.provider.connection.getParsedAccountInfo(
+          tokenAccount
+        );
+      return (value?.data as ParsedAccountData).parsed.info.state as string;
+    };
+
+    const startDeconstruction = async (amount: BN) =>
+      await prismEtf.startOrder({ type: OrderType.DECONSTRUCTION, amount });
+
+    before(async () => {
+      mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+    });
+
+    it(`Only make an ETF freezable if Beamsplitter can freeze its mint`, async () => {
+      // The SDK creates etf mints without a freeze authority
+      const unfreezable = await initTestPrismEtf([{ mint, weight }]);
+      await expectTX(setFreezable(unfreezable, true)).to.be.rejected;
+
+      const prismEtfMintKP = Keypair.generate();
+      await expectTX(
+        await createInitMintInstructions({
+          provider: coherenceHelper.provider,
+          mintKP: prismEtfMintKP,
+          decimals: PRISM_ETF_DECIMALS,
+          mintAuthority: coherenceHelper.beamsplitter,
+          freezeAuthority: coherenceHelper.beamsplitter,
+        })
+      ).to.be.fulfilled;
+      testPrismEtf = await initTestPrismEtf(
+        [{ mint, weight }],
+        coherenceHelper.sdk,
+        prismEtfMintKP.publicKey
+      );
+      await expectTX(setFreezable(testPrismEtf, true)).to.be.fulfilled;
+
+      prismEtf = await finalizeTestPrismEtf(testPrismEtf);
+      expect(prismEtf.prismEtfData?.freezable).to.be.true;
+
+      // Holders must know whether they can be frozen before buying in
+      await expectTX(setFreezable(testPrismEtf, false)).to.be.rejected;
+    });
+
+    it(`Let only the manager freeze a holder, and never the fee vault`, async () => {
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      holderEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: coherenceHelper.authority,
+      });
+
+      const [outsiderSdk] = await createFundedSdk();
+      await expectTX(setFrozen(true, holderEtfAta, outsiderSdk)).to.be
+        .rejected;
+
+      const feeVaultEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: prismEtf.feeVaultPda,
+      });
+      await expectTX(setFrozen(true, feeVaultEtfAta)).to.be.rejected;
+
+      await expectTX(setFrozen(true)).to.be.fulfilled;
+      expect(await fetchTokenAccountState(holderEtfAta)).to.equal("frozen");
+    });
+
+    it(`Keep a frozen holder from redeeming until thawed`, async () => {
+      const amount = AMOUNT_TO_CONSTRUCT.divn(2);
+
+      // Deconstructing burns from the frozen account
+      await expectTX(await startDeconstruction(amount)).to.be.rejected;
+
+      await expectTX(setFrozen(false)).to.be.fulfilled;
+      expect(await fetchTokenAccountState(holderEtfAta)).to.equal(
+        "initialized"
+      );
+      await expectTX(await startDeconstruction(amount)).to.be.fulfilled;
+      await refreshPrismEtf();
+      for (const decohereChunk of await prismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+    });
+  });
+}
@@ -15,7 +15,24 @@ import { assert, expect } from "chai";
 import type { UserPrismEtf, WeightedToken } from "../../src";
 import { enumLikeToString, PrismEtf } from "../../src";
 import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import allowlist from "./allowlist";
 import constructDeconstruct from "./coherence-beamsplitter";
+import descriptor from "./descriptor";
+import events from "./events";
+import feeExemptions from "./feeExemptions";
+import feeTiers from "./feeTiers";
+import freezeHolders from "./freezeHolders";
+import nativeSol from "./nativeSol";
+import nesting from "./nesting";
+import orderLimits from "./orderLimits";
+import pendingChanges from "./pendingChanges";
+import percentageMode from "./percentageMode";
+import priceFeeds from "./priceFeeds";
+import quoteOrder from "./quoteOrder";
+import roles from "./roles";
+import singleAsset from "./singleAsset";
+import token2022 from "./token2022";
+import underlyingFees from "./underlyingFees";
 
 export default function unitTests() {
   let prismEtfMint: PublicKey;
@@ -181,5 +198,22 @@ export default function unitTests() {
 
   describe("#UNIT TESTS", () => {
     constructDeconstruct();
+    percentageMode();
+    priceFeeds();
+    quoteOrder();
+    events();
+    singleAsset();
+    underlyingFees();
+    feeTiers();
+    feeExemptions();
+    pendingChanges();
+    roles();
+    descriptor();
+    nesting();
+    orderLimits();
+    allowlist();
+    freezeHolders();
+    nativeSol();
+    token2022();
   });
 }
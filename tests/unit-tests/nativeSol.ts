@@ -0,0 +1,197 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getATAAddress,
+  getMintInfo,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import { NATIVE_MINT } from "@solana/spl-token";
+import type { PublicKey } from "@solana/web3.js";
+import { SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import { getWeightedAmount, OrderType, PRISM_ETF_DECIMALS } from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function nativeSol() {
+  describe("Native SOL", () => {
+    const WSOL_DECIMALS = 9;
+    // A hundredth of a SOL per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS / 100);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let prismEtf: PrismEtf;
+    let etfWsolAta: PublicKey;
+
+    const requiredLamports = () =>
+      getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        weight,
+        PRISM_ETF_DECIMALS,
+        WSOL_DECIMALS
+      );
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const fetchLamports = async () =>
+      new BN(
+        await coherenceHelper.provider.connection.getBalance(
+          coherenceHelper.authority
+        )
+      );
+
+    const fetchSupply = async () =>
+      new BN(
+        (
+          await getMintInfo(coherenceHelper.provider, prismEtf.prismEtfMint)
+        ).supply.toString()
+      );
+
+    // The accounts cohere_native and decohere_native share
+    const nativeAccounts = async () => {
+      if (
+        prismEtf.prismEtfData === null ||
+        prismEtf.orderStatePda === null ||
+        prismEtf.transferredTokensAcct === undefined
+      ) {
+        assert.fail("The order state was not initialized");
+      }
+
+      return {
+        orderer: coherenceHelper.authority,
+        prismEtfMint: prismEtf.prismEtfMint,
+        transferMint: NATIVE_MINT,
+        beamsplitterTransferAta: etfWsolAta,
+        beamsplitter: coherenceHelper.beamsplitter,
+        prismEtf: prismEtf.prismEtfPda,
+        orderState: prismEtf.orderStatePda,
+        feeVault: prismEtf.feeVaultPda,
+        feeVaultTransferAta: await getATAAddress({
+          mint: NATIVE_MINT,
+          owner: prismEtf.feeVaultPda,
+        }),
+        weightedTokens: prismEtf.prismEtfData.weightedTokens,
+        transferredTokens: prismEtf.transferredTokensAcct,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      };
+    };
+
+    const cohereNative = async () =>
+      prismEtf.makeProviderEnvelope([
+        prismEtf.getProgramInstructions().cohereNative(0, {
+          accounts: await nativeAccounts(),
+        }),
+      ]);
+
+    const decohereNative = async () => {
+      if (prismEtf.orderStatePda === null) {
+        assert.fail("The order state was not initialized");
+      }
+      return prismEtf.makeProviderEnvelope([
+        prismEtf.getProgramInstructions().decohereNative(0, {
+          accounts: {
+            ...(await nativeAccounts()),
+            orderNativeAta: await getATAAddress({
+              mint: NATIVE_MINT,
+              owner: prismEtf.orderStatePda,
+            }),
+            rent: SYSVAR_RENT_PUBKEY,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          },
+        }),
+      ]);
+    };
+
+    const startConstruction = async () => {
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+    };
+
+    before(async () => {
+      prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint: NATIVE_MINT, weight }])
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      etfWsolAta = await getATAAddress({
+        mint: NATIVE_MINT,
+        owner: prismEtf.prismEtfPda,
+      });
+    });
+
+    it(`Wrap the orderer's lamports into the ETF's wSOL`, async () => {
+      const backingBefore = await ataBalance(etfWsolAta);
+      const supplyBefore = await fetchSupply();
+
+      await startConstruction();
+      await expectTX(await cohereNative()).to.be.fulfilled;
+      // Cohering twice is a no-op
+      await expectTX(await cohereNative()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // Rounded up by one like cohere
+      expect(
+        (await ataBalance(etfWsolAta)).sub(backingBefore)
+      ).to.be.bignumber.equal(requiredLamports().addn(1));
+
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      expect((await fetchSupply()).sub(supplyBefore)).to.be.bignumber.equal(
+        AMOUNT_TO_CONSTRUCT
+      );
+    });
+
+    it(`Unwrap the wSOL back to the orderer when a construction is unwound`, async () => {
+      const lamportsBefore = await fetchLamports();
+      const backingBefore = await ataBalance(etfWsolAta);
+      const supplyBefore = await fetchSupply();
+
+      await startConstruction();
+      await expectTX(await cohereNative()).to.be.fulfilled;
+      await expectTX(await decohereNative()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      expect(await ataBalance(etfWsolAta)).to.be.bignumber.equal(
+        backingBefore
+      );
+      // The order state's wSOL account was closed, unwrapping it
+      expect(
+        await coherenceHelper.provider.connection.getAccountInfo(
+          await getATAAddress({
+            mint: NATIVE_MINT,
+            owner: prismEtf.orderStatePda ?? SystemProgram.programId,
+          })
+        )
+      ).to.be.null;
+      // Only transaction fees were spent
+      expect(
+        lamportsBefore.sub(await fetchLamports())
+      ).to.be.bignumber.lessThan(requiredLamports());
+
+      // Nothing was left transferred, so finalizing cancels the order
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      expect(await fetchSupply()).to.be.bignumber.equal(supplyBefore);
+    });
+  });
+}
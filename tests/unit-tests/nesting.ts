@@ -0,0 +1,169 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { AccountMeta, PublicKey } from "@solana/web3.js";
+import { SystemProgram } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import { PRISM_ETF_DECIMALS } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+// Mirrors MAX_NESTING_DEPTH in state.rs
+const MAX_NESTING_DEPTH = 3;
+
+export default function nesting() {
+  describe("Nested ETFs", () => {
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let innerPrismEtf: TestPrismEtf;
+
+    const readonly = (pubkey: PublicKey): AccountMeta => ({
+      pubkey,
+      isSigner: false,
+      isWritable: false,
+    });
+
+    // Pushes mint with exactly the remaining accounts given, unlike the SDK which always passes the right ones
+    const pushTokenWith = (
+      { prismEtfMint, prismEtfPda, weightedTokensAcct }: TestPrismEtf,
+      mint: PublicKey,
+      remainingAccounts: AccountMeta[]
+    ) =>
+      new TransactionEnvelope(coherenceHelper.provider, [
+        coherenceHelper.sdk.loader.program.instruction.pushTokens(
+          [{ mint, weight }],
+          {
+            accounts: {
+              prismEtf: prismEtfPda,
+              prismEtfMint,
+              beamsplitter: coherenceHelper.beamsplitter,
+              weightedTokens: weightedTokensAcct,
+              manager: coherenceHelper.authority,
+              systemProgram: SystemProgram.programId,
+            },
+            remainingAccounts,
+          }
+        ),
+      ]);
+
+    const fetchPrismEtfData = async ({ prismEtfMint }: TestPrismEtf) => {
+      const { prismEtfData } = await loadTestPrismEtf(prismEtfMint);
+      if (!prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      return prismEtfData;
+    };
+
+    const fetchLength = async ({ weightedTokensAcct }: TestPrismEtf) =>
+      (
+        await coherenceHelper.sdk.loader.fetchWeightedTokensData(
+          weightedTokensAcct
+        )
+      )?.length;
+
+    before(async () => {
+      const mint = await createTestMint(6);
+      innerPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+      await finalizeTestPrismEtf(innerPrismEtf);
+    });
+
+    it(`Reject pushing a token without its mint`, async () => {
+      const outerPrismEtf = await initTestPrismEtf([]);
+
+      await expectTX(pushTokenWith(outerPrismEtf, await createTestMint(6), []))
+        .to.be.rejected;
+      expect(await fetchLength(outerPrismEtf)).to.be.equal(0);
+    });
+
+    it(`Reject pushing a Prism ETF mint without its PrismEtf`, async () => {
+      const outerPrismEtf = await initTestPrismEtf([]);
+
+      // Leaving out the PrismEtf can't sneak a nested ETF past the depth check
+      await expectTX(
+        pushTokenWith(outerPrismEtf, innerPrismEtf.prismEtfMint, [
+          readonly(innerPrismEtf.prismEtfMint),
+        ])
+      ).to.be.rejected;
+
+      // Nor can passing the PrismEtf of another ETF
+      const otherPrismEtf = await initTestPrismEtf([
+        { mint: await createTestMint(6), weight },
+      ]);
+      await finalizeTestPrismEtf(otherPrismEtf);
+      await expectTX(
+        pushTokenWith(outerPrismEtf, innerPrismEtf.prismEtfMint, [
+          readonly(innerPrismEtf.prismEtfMint),
+          readonly(otherPrismEtf.prismEtfPda),
+        ])
+      ).to.be.rejected;
+
+      expect(await fetchLength(outerPrismEtf)).to.be.equal(0);
+    });
+
+    it(`Reject nesting an ETF in itself or an unfinished ETF`, async () => {
+      const outerPrismEtf = await initTestPrismEtf([]);
+
+      await expectTX(
+        pushTokenWith(outerPrismEtf, outerPrismEtf.prismEtfMint, [
+          readonly(outerPrismEtf.prismEtfMint),
+          readonly(outerPrismEtf.prismEtfPda),
+        ])
+      ).to.be.rejected;
+
+      const unfinishedPrismEtf = await initTestPrismEtf([
+        { mint: await createTestMint(6), weight },
+      ]);
+      const pushTokensEnvelopes =
+        await coherenceHelper.sdk.beamsplitter.pushTokens({
+          ...outerPrismEtf,
+          weightedTokens: [{ mint: unfinishedPrismEtf.prismEtfMint, weight }],
+        });
+      for (const pushTokensEnvelope of pushTokensEnvelopes) {
+        await expectTX(pushTokensEnvelope).to.be.rejected;
+      }
+
+      expect(await fetchLength(outerPrismEtf)).to.be.equal(0);
+    });
+
+    it(`Nest ETFs up to the maximum nesting depth`, async () => {
+      let nestedPrismEtf = innerPrismEtf;
+      for (let depth = 1; depth <= MAX_NESTING_DEPTH; depth++) {
+        const outerPrismEtf = await initTestPrismEtf([
+          { mint: await createTestMint(6), weight },
+          { mint: nestedPrismEtf.prismEtfMint, weight },
+        ]);
+
+        const { nestingDepth, nestedTokens } = await fetchPrismEtfData(
+          outerPrismEtf
+        );
+        expect(nestingDepth).to.be.equal(depth);
+        // Only the second weighted token is a nested ETF
+        expect(new BN(nestedTokens).toNumber()).to.be.equal(0b10);
+
+        await finalizeTestPrismEtf(outerPrismEtf);
+        nestedPrismEtf = outerPrismEtf;
+      }
+
+      const tooDeepPrismEtf = await initTestPrismEtf([]);
+      const pushTokensEnvelopes =
+        await coherenceHelper.sdk.beamsplitter.pushTokens({
+          ...tooDeepPrismEtf,
+          weightedTokens: [{ mint: nestedPrismEtf.prismEtfMint, weight }],
+        });
+      for (const pushTokensEnvelope of pushTokensEnvelopes) {
+        await expectTX(pushTokensEnvelope).to.be.rejected;
+      }
+      expect(await fetchLength(tooDeepPrismEtf)).to.be.equal(0);
+    });
+  });
+}
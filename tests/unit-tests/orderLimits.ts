@@ -0,0 +1,192 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import { getMintInfo, TOKEN_PROGRAM_ID } from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { SystemProgram } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import { getWeightedAmount, OrderType, PRISM_ETF_DECIMALS } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+import {
+  finalizeTestSingleAssetOrder,
+  finalizeTestSingleAssetRedemption,
+  setTestSwapProgram,
+  startTestSingleAssetOrder,
+  startTestSingleAssetRedemption,
+  swapTestCohere,
+  swapTestDecohere,
+} from "../singleAssetHelper";
+
+chai.use(chaiSolana);
+
+export default function orderLimits() {
+  describe("Order Limits", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let mint: PublicKey;
+    let prismEtf: PrismEtf;
+
+    const etfAmount = (wholeTokens: number) =>
+      new BN(wholeTokens).mul(new BN(10).pow(new BN(PRISM_ETF_DECIMALS)));
+
+    const assetAmount = (wholeTokens: number) =>
+      getWeightedAmount(
+        etfAmount(wholeTokens),
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      );
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const fetchSupply = async () =>
+      (await getMintInfo(coherenceHelper.provider, prismEtf.prismEtfMint))
+        .supply;
+
+    // Limits in whole etf tokens, 0 removes the cap or maximum
+    const setOrderLimits = (
+      maxSupply: number,
+      minOrderAmount: number,
+      maxOrderAmount: number
+    ) =>
+      new TransactionEnvelope(coherenceHelper.provider, [
+        coherenceHelper.sdk.loader.program.instruction.setOrderLimits(
+          etfAmount(maxSupply),
+          etfAmount(minOrderAmount),
+          etfAmount(maxOrderAmount),
+          {
+            accounts: {
+              prismEtfMint: prismEtf.prismEtfMint,
+              manager: coherenceHelper.authority,
+              prismEtf: prismEtf.prismEtfPda,
+              beamsplitter: coherenceHelper.beamsplitter,
+            },
+          }
+        ),
+      ]);
+
+    const startOrder = async (wholeTokens: number) =>
+      await prismEtf.startOrder({
+        type: OrderType.CONSTRUCTION,
+        amount: etfAmount(wholeTokens),
+      });
+
+    before(async () => {
+      mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+
+      prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint, weight }])
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // Single asset orders of the ETF's own asset are transferred, not swapped
+      await setTestSwapProgram(TOKEN_PROGRAM_ID);
+    });
+
+    after(async () => {
+      await setTestSwapProgram(SystemProgram.programId);
+    });
+
+    it(`Reject limits with a minimum above the maximum`, async () => {
+      await expectTX(setOrderLimits(0, 10, 5)).to.be.rejected;
+    });
+
+    it(`Hold orders to the minimum, maximum and supply cap`, async () => {
+      await expectTX(setOrderLimits(15, 2, 10)).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      await expectTX(await startOrder(1)).to.be.rejected;
+      await expectTX(await startOrder(11)).to.be.rejected;
+
+      await expectTX(await startOrder(10)).to.be.fulfilled;
+      await refreshPrismEtf();
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: etfAmount(10),
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      expect(await fetchSupply()).to.be.bignumber.equal(etfAmount(10));
+
+      // Within the maximum, but over the supply cap
+      await refreshPrismEtf();
+      await expectTX(await startOrder(6)).to.be.rejected;
+    });
+
+    it(`Hold single asset orders to the limits once their swaps are done`, async () => {
+      await expectTX(
+        await startTestSingleAssetOrder(
+          prismEtf,
+          mint,
+          assetAmount(10),
+          new BN(0)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(
+        await swapTestCohere(prismEtf, mint, 0, assetAmount(10))
+      ).to.be.fulfilled;
+
+      // 10 more would take the supply past the cap of 15
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .rejected;
+
+      await expectTX(setOrderLimits(0, 0, 5)).to.be.fulfilled;
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .rejected;
+
+      await expectTX(setOrderLimits(0, 0, 0)).to.be.fulfilled;
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .fulfilled;
+      expect(await fetchSupply()).to.be.bignumber.equal(etfAmount(20));
+    });
+
+    it(`Hold single asset redemptions to the maximum, but not the minimum`, async () => {
+      await expectTX(setOrderLimits(0, 2, 5)).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      await expectTX(
+        await startTestSingleAssetRedemption(
+          prismEtf,
+          mint,
+          etfAmount(6),
+          new BN(0)
+        )
+      ).to.be.rejected;
+
+      // Holders of less than the minimum can still leave
+      await expectTX(
+        await startTestSingleAssetRedemption(
+          prismEtf,
+          mint,
+          etfAmount(1),
+          new BN(0)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(await swapTestDecohere(prismEtf, mint, 0)).to.be.fulfilled;
+      await expectTX(await finalizeTestSingleAssetRedemption(prismEtf, mint)).to
+        .be.fulfilled;
+      expect(await fetchSupply()).to.be.bignumber.equal(etfAmount(19));
+    });
+  });
+}
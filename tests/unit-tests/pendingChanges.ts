@@ -0,0 +1,241 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { Keypair } from "@solana/web3.js";
+import { SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { CoherenceSDK, PrismEtf } from "../../src";
+import { PRISM_ETF_DECIMALS } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createFundedSdk,
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function pendingChanges() {
+  describe("Pending Changes", () => {
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let managerSdk: CoherenceSDK;
+    let managerKP: Keypair;
+    // The same ETF, signed for by the Beamsplitter owner and by the manager
+    let ownerPrismEtf: PrismEtf;
+    let managerPrismEtf: PrismEtf;
+
+    const refreshPrismEtfs = async () => {
+      ownerPrismEtf = await loadTestPrismEtf(ownerPrismEtf.prismEtfMint);
+      managerPrismEtf = await loadTestPrismEtf(
+        ownerPrismEtf.prismEtfMint,
+        managerSdk
+      );
+    };
+
+    const prismEtfAccounts = (prismEtf: PrismEtf) => ({
+      prismEtfMint: prismEtf.prismEtfMint,
+      prismEtf: prismEtf.prismEtfPda,
+      beamsplitter: coherenceHelper.beamsplitter,
+      clock: SYSVAR_CLOCK_PUBKEY,
+    });
+
+    const setConstructionBps = (constructionBps: number) =>
+      new TransactionEnvelope(coherenceHelper.provider, [
+        coherenceHelper.sdk.loader.program.instruction.setConstructionBps(
+          constructionBps,
+          {
+            accounts: {
+              ...prismEtfAccounts(ownerPrismEtf),
+              authority: coherenceHelper.authority,
+            },
+          }
+        ),
+      ]);
+
+    const setManagerCut = (managerCut: number) =>
+      managerPrismEtf.makeProviderEnvelope([
+        managerSdk.loader.program.instruction.setManagerCut(managerCut, {
+          accounts: {
+            ...prismEtfAccounts(managerPrismEtf),
+            manager: managerKP.publicKey,
+          },
+        }),
+      ]);
+
+    before(async () => {
+      [managerSdk, managerKP] = await createFundedSdk();
+
+      const mint = await createTestMint(6);
+      managerPrismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint, weight }], managerSdk),
+        managerSdk
+      );
+      ownerPrismEtf = await loadTestPrismEtf(managerPrismEtf.prismEtfMint);
+    });
+
+    it(`Reject applying changes before the change delay has passed`, async () => {
+      await expectTX(setManagerCut(1_000)).to.be.fulfilled;
+
+      await refreshPrismEtfs();
+      if (!managerPrismEtf.prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      const { pendingManagerChanges, managerCut } =
+        managerPrismEtf.prismEtfData;
+      expect(pendingManagerChanges.managerCut).to.be.equal(1_000);
+
+      await expectTX(managerPrismEtf.applyPendingChanges()).to.be.rejected;
+
+      await refreshPrismEtfs();
+      expect(managerPrismEtf.prismEtfData?.managerCut).to.be.equal(managerCut);
+    });
+
+    it(`Keep the owner's and the manager's changes apart`, async () => {
+      const managerEffectiveAt = new BN(
+        managerPrismEtf.prismEtfData?.pendingManagerChanges.effectiveAt
+      );
+
+      await expectTX(setConstructionBps(50)).to.be.fulfilled;
+
+      await refreshPrismEtfs();
+      if (!ownerPrismEtf.prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      expect(
+        ownerPrismEtf.prismEtfData.pendingFeeChanges.constructionBps
+      ).to.be.equal(50);
+      // Queueing the owner's change does not restart the manager's delay
+      expect(
+        new BN(ownerPrismEtf.prismEtfData.pendingManagerChanges.effectiveAt)
+      ).to.be.bignumber.equal(managerEffectiveAt);
+
+      // Neither side can cancel the other's changes
+      await expectTX(ownerPrismEtf.cancelPendingManagerChanges()).to.be
+        .rejected;
+      await expectTX(managerPrismEtf.cancelPendingFeeChanges()).to.be.rejected;
+
+      await expectTX(managerPrismEtf.cancelPendingManagerChanges()).to.be
+        .fulfilled;
+
+      await refreshPrismEtfs();
+      expect(managerPrismEtf.prismEtfData?.pendingManagerChanges.managerCut).to
+        .be.null;
+      expect(
+        managerPrismEtf.prismEtfData?.pendingFeeChanges.constructionBps
+      ).to.be.equal(50);
+
+      await expectTX(ownerPrismEtf.cancelPendingFeeChanges()).to.be.fulfilled;
+
+      await refreshPrismEtfs();
+      expect(ownerPrismEtf.prismEtfData?.pendingFeeChanges.constructionBps).to
+        .be.null;
+
+      // Nothing is left to cancel
+      await expectTX(ownerPrismEtf.cancelPendingFeeChanges()).to.be.rejected;
+    });
+
+    it(`Queue fee tiers instead of replacing them right away`, async () => {
+      const feeTiers = [
+        {
+          minAmount: new BN(1_000 * 10 ** PRISM_ETF_DECIMALS),
+          constructionBps: 10,
+        },
+      ];
+
+      await expectTX(
+        new TransactionEnvelope(coherenceHelper.provider, [
+          coherenceHelper.sdk.loader.program.instruction.setFeeTiers(feeTiers, {
+            accounts: {
+              ...prismEtfAccounts(ownerPrismEtf),
+              authority: coherenceHelper.authority,
+            },
+          }),
+        ])
+      ).to.be.fulfilled;
+
+      await refreshPrismEtfs();
+      if (!ownerPrismEtf.prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      const { feeTiersLength, pendingFeeChanges } = ownerPrismEtf.prismEtfData;
+      expect(feeTiersLength).to.be.equal(0);
+      expect(pendingFeeChanges.feeTiersLength).to.be.equal(1);
+      expect(pendingFeeChanges.feeTiers[0].constructionBps).to.be.equal(10);
+
+      await expectTX(ownerPrismEtf.applyPendingChanges()).to.be.rejected;
+      await expectTX(ownerPrismEtf.cancelPendingFeeChanges()).to.be.fulfilled;
+    });
+
+    it(`Apply the manager's changes of an unfinished ETF right away`, async () => {
+      const mint = await createTestMint(6);
+      const { prismEtfMint } = await initTestPrismEtf(
+        [{ mint, weight }],
+        managerSdk
+      );
+      const unfinishedPrismEtf = await loadTestPrismEtf(
+        prismEtfMint,
+        managerSdk
+      );
+
+      await expectTX(
+        unfinishedPrismEtf.setManager({ newManager: coherenceHelper.authority })
+      ).to.be.fulfilled;
+
+      // The manager only changes once the queued change is applied
+      let prismEtf = await loadTestPrismEtf(prismEtfMint);
+      assert(prismEtf.prismEtfData?.manager.equals(managerKP.publicKey));
+
+      await expectTX(prismEtf.applyPendingChanges()).to.be.fulfilled;
+
+      prismEtf = await loadTestPrismEtf(prismEtfMint);
+      assert(prismEtf.prismEtfData?.manager.equals(coherenceHelper.authority));
+      expect(prismEtf.prismEtfData?.pendingManagerChanges.manager).to.be.null;
+
+      // Applied changes can't be applied again
+      await expectTX(prismEtf.applyPendingChanges()).to.be.rejected;
+    });
+
+    it(`Queue a new change delay behind the current one`, async () => {
+      const beamsplitterData =
+        await coherenceHelper.sdk.beamsplitter.fetchBeamsplitterData(
+          coherenceHelper.beamsplitter
+        );
+      if (!beamsplitterData) {
+        assert.fail("Beamsplitter was not initialized");
+      }
+      const { changeDelay } = beamsplitterData;
+
+      // A delay of 0 must not take effect before the current delay has passed
+      await expectTX(
+        coherenceHelper.sdk.beamsplitter.setChangeDelay({ changeDelay: 0 })
+      ).to.be.fulfilled;
+
+      await expectTX(coherenceHelper.sdk.beamsplitter.applyChangeDelay()).to.be
+        .rejected;
+
+      let queuedData =
+        await coherenceHelper.sdk.beamsplitter.fetchBeamsplitterData(
+          coherenceHelper.beamsplitter
+        );
+      expect(queuedData?.changeDelay).to.be.equal(changeDelay);
+      expect(queuedData?.pendingChangeDelay).to.be.equal(0);
+
+      // Queue the current delay again, so no shorter delay is left pending
+      await expectTX(
+        coherenceHelper.sdk.beamsplitter.setChangeDelay({ changeDelay })
+      ).to.be.fulfilled;
+
+      queuedData = await coherenceHelper.sdk.beamsplitter.fetchBeamsplitterData(
+        coherenceHelper.beamsplitter
+      );
+      expect(queuedData?.pendingChangeDelay).to.be.equal(changeDelay);
+    });
+  });
+}
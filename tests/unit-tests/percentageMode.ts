@@ -0,0 +1,213 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { getATAAddress, getMintInfo } from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  BPS_DENOMINATOR,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  PriceFeedKind,
+  stringToEnumLike,
+  WeightMode,
+} from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import pyth2Usd from "../fixtures/pyth_2_usd.json";
+import switchboard05Usd from "../fixtures/switchboard_0_5_usd.json";
+import { initTestPriceFeed } from "../priceFeedHelper";
+import type { TestPrismEtf } from "../prismEtfHelper";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+// One dollar (PRICE_DECIMALS) per etf token before any supply exists
+const INITIAL_NAV = new BN(10 ** 9);
+
+export default function percentageMode() {
+  describe("Percentage Weight Mode", () => {
+    // Priced at 2.00 by the Pyth fixture
+    const decimalsA = 6;
+    // Priced at 0.5 by the Switchboard fixture
+    const decimalsB = 9;
+
+    let mintA: PublicKey;
+    let mintB: PublicKey;
+    let prismEtf: PrismEtf;
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    // An ETF holding mintA and mintB at the given allocations (bps)
+    const initPercentagePrismEtf = async (
+      allocationA: number,
+      allocationB: number
+    ): Promise<TestPrismEtf> => {
+      const testPrismEtf = await initTestPrismEtf([
+        { mint: mintA, weight: new BN(allocationA) },
+        { mint: mintB, weight: new BN(allocationB) },
+      ]);
+
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope([
+          coherenceHelper.sdk.loader.program.instruction.setWeightMode(
+            stringToEnumLike(WeightMode.PERCENTAGE),
+            INITIAL_NAV,
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+
+      return testPrismEtf;
+    };
+
+    const fetchBacking = async () => {
+      if (prismEtf.prismEtfData === null) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+      const weightedTokensData =
+        await coherenceHelper.sdk.loader.fetchWeightedTokensData(
+          prismEtf.prismEtfData.weightedTokens
+        );
+      if (weightedTokensData === null) {
+        assert.fail("Weighted tokens were not initialized");
+      }
+      return weightedTokensData.backing
+        .slice(0, 2)
+        .map((backing) => new BN(backing));
+    };
+
+    const snapshotAmounts = () => {
+      if (prismEtf.transferredTokensData === null) {
+        assert.fail("Transferred tokens were not initialized");
+      }
+      return prismEtf.transferredTokensData.amounts
+        .slice(0, 2)
+        .map((amount) => new BN(amount));
+    };
+
+    before(async () => {
+      mintA = await createTestMint(decimalsA);
+      mintB = await createTestMint(decimalsB);
+      await mintTestTokens(mintA, new BN(1_000 * 10 ** decimalsA));
+      await mintTestTokens(mintB, new BN(1_000).mul(new BN(10 ** decimalsB)));
+
+      await expectTX(
+        await initTestPriceFeed(mintA, {
+          feed: pyth2Usd.pubkey,
+          kind: PriceFeedKind.PYTH,
+        })
+      ).to.be.fulfilled;
+      await expectTX(
+        await initTestPriceFeed(mintB, {
+          feed: switchboard05Usd.pubkey,
+          kind: PriceFeedKind.SWITCHBOARD,
+        })
+      ).to.be.fulfilled;
+    });
+
+    it(`Reject finishing an ETF whose allocations do not add up to 100%`, async () => {
+      const testPrismEtf = await initPercentagePrismEtf(6_000, 3_000);
+
+      await expectTX(
+        await coherenceHelper.sdk.beamsplitter.finalizePrismEtf(testPrismEtf)
+      ).to.be.rejected;
+    });
+
+    it(`Price a construction at the current NAV and back it with what was transferred`, async () => {
+      prismEtf = await finalizeTestPrismEtf(
+        await initPercentagePrismEtf(6_000, BPS_DENOMINATOR - 6_000)
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      const amount = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+      await expectTX(
+        await prismEtf.startOrder({ type: OrderType.CONSTRUCTION, amount })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // 10 etf tokens at the initial NAV are worth 10.00: 6.00 of A at 2.00 and
+      // 4.00 of B at 0.5
+      const [amountA, amountB] = snapshotAmounts();
+      expect(amountA).to.be.bignumber.equal(new BN(3 * 10 ** decimalsA));
+      expect(amountB).to.be.bignumber.equal(new BN(8 * 10 ** decimalsB));
+
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: amount,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // The backing is exactly what the ETF holds, including the unit cohere
+      // rounds up by
+      const [backingA, backingB] = await fetchBacking();
+      const etfAtaA = await getATAAddress({
+        mint: mintA,
+        owner: prismEtf.prismEtfPda,
+      });
+      const etfAtaB = await getATAAddress({
+        mint: mintB,
+        owner: prismEtf.prismEtfPda,
+      });
+      expect(backingA).to.be.bignumber.equal(await ataBalance(etfAtaA));
+      expect(backingB).to.be.bignumber.equal(await ataBalance(etfAtaB));
+      expect(backingA).to.be.bignumber.equal(amountA.addn(1));
+      expect(backingB).to.be.bignumber.equal(amountB.addn(1));
+    });
+
+    it(`Pay a deconstruction its pro rata share of the backing`, async () => {
+      const [backingA, backingB] = await fetchBacking();
+      // Includes any fee minted to the fee vault on construction
+      const supply = (
+        await getMintInfo(coherenceHelper.provider, prismEtf.prismEtfMint)
+      ).supply;
+
+      const amount = new BN(5 * 10 ** PRISM_ETF_DECIMALS);
+      await expectTX(
+        await prismEtf.startOrder({ type: OrderType.DECONSTRUCTION, amount })
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      const [shareA, shareB] = snapshotAmounts();
+      expect(shareA).to.be.bignumber.equal(
+        backingA.mul(amount).div(new BN(supply.toString()))
+      );
+      expect(shareB).to.be.bignumber.equal(
+        backingB.mul(amount).div(new BN(supply.toString()))
+      );
+
+      for (const decohereChunk of await prismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+
+      const [backingAfterA, backingAfterB] = await fetchBacking();
+      expect(backingAfterA).to.be.bignumber.equal(backingA.sub(shareA));
+      expect(backingAfterB).to.be.bignumber.equal(backingB.sub(shareB));
+    });
+  });
+}
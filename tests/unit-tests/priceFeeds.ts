@@ -0,0 +1,272 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getATAAddress,
+  getMintInfo,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import type { AccountMeta } from "@solana/web3.js";
+import {
+  PublicKey,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  BPS_DENOMINATOR,
+  generatePriceFeedAddress,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  PriceFeedKind,
+  stringToEnumLike,
+  WeightMode,
+} from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import pyth1UsdUncertain from "../fixtures/pyth_1_usd_uncertain.json";
+import pyth2Usd from "../fixtures/pyth_2_usd.json";
+import switchboard05Usd from "../fixtures/switchboard_0_5_usd.json";
+import { initTestPriceFeed, setTestPriceFeed } from "../priceFeedHelper";
+import {
+  createFundedSdk,
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function priceFeeds() {
+  describe("Price Feeds", () => {
+    const decimals = 6;
+    const AMOUNT_TO_CONSTRUCT = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let mint: PublicKey;
+    let priceFeed: PublicKey;
+    // Holds only mint, so every construction is priced by its feed alone
+    let prismEtf: PrismEtf;
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const startConstruction = async () =>
+      await prismEtf.startOrder({
+        type: OrderType.CONSTRUCTION,
+        amount: AMOUNT_TO_CONSTRUCT,
+      });
+
+    // Starts a CONSTRUCTION priced with exactly the accounts given, unlike the
+    // SDK which always passes the registered ones
+    const startConstructionWith = async (remainingAccounts: AccountMeta[]) => {
+      if (
+        prismEtf.prismEtfData === null ||
+        prismEtf.orderStatePda === null ||
+        prismEtf.transferredTokensAcct === undefined
+      ) {
+        assert.fail("The order state was not initialized");
+      }
+
+      return prismEtf.makeProviderEnvelope([
+        prismEtf
+          .getProgramInstructions()
+          .startOrder(
+            stringToEnumLike(OrderType.CONSTRUCTION),
+            AMOUNT_TO_CONSTRUCT,
+            {
+              accounts: {
+                prismEtf: prismEtf.prismEtfPda,
+                prismEtfMint: prismEtf.prismEtfMint,
+                orderState: prismEtf.orderStatePda,
+                transferredTokens: prismEtf.transferredTokensAcct,
+                orderer: coherenceHelper.authority,
+                ordererEtfAta: await getATAAddress({
+                  mint: prismEtf.prismEtfMint,
+                  owner: coherenceHelper.authority,
+                }),
+                beamsplitter: coherenceHelper.beamsplitter,
+                rent: SYSVAR_RENT_PUBKEY,
+                clock: SYSVAR_CLOCK_PUBKEY,
+                weightedTokens: prismEtf.prismEtfData.weightedTokens,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+              },
+              remainingAccounts,
+            }
+          ),
+      ]);
+    };
+
+    before(async () => {
+      mint = await createTestMint(decimals);
+      await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+      [priceFeed] = await generatePriceFeedAddress(
+        mint,
+        coherenceHelper.beamsplitter
+      );
+
+      const testPrismEtf = await initTestPrismEtf([
+        { mint, weight: new BN(BPS_DENOMINATOR) },
+      ]);
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope([
+          coherenceHelper.sdk.loader.program.instruction.setWeightMode(
+            stringToEnumLike(WeightMode.PERCENTAGE),
+            new BN(10 ** 9),
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+      prismEtf = await finalizeTestPrismEtf(testPrismEtf);
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+    });
+
+    it(`Only let the curator register feeds, with a confidence limit of at most 100%`, async () => {
+      const [curatorlessSdk] = await createFundedSdk();
+      const pyth = { feed: pyth2Usd.pubkey, kind: PriceFeedKind.PYTH };
+
+      await expectTX(await initTestPriceFeed(mint, pyth, curatorlessSdk)).to
+        .be.rejected;
+      await expectTX(
+        await initTestPriceFeed(mint, {
+          ...pyth,
+          maxConfidenceBps: BPS_DENOMINATOR + 1,
+        })
+      ).to.be.rejected;
+
+      await expectTX(await initTestPriceFeed(mint, pyth)).to.be.fulfilled;
+      const priceFeedData =
+        await coherenceHelper.sdk.loader.program.account.priceFeed.fetch(
+          priceFeed
+        );
+      expect(priceFeedData.mint).to.eqAddress(mint);
+      expect(priceFeedData.feed).to.eqAddress(new PublicKey(pyth2Usd.pubkey));
+      expect(priceFeedData.decimals).to.equal(decimals);
+
+      await expectTX(await setTestPriceFeed(mint, pyth, curatorlessSdk)).to.be
+        .rejected;
+    });
+
+    it(`Reject pricing with a feed other than the registered one`, async () => {
+      await expectTX(
+        await startConstructionWith([
+          { pubkey: priceFeed, isSigner: false, isWritable: false },
+          {
+            pubkey: new PublicKey(switchboard05Usd.pubkey),
+            isSigner: false,
+            isWritable: false,
+          },
+        ])
+      ).to.be.rejected;
+
+      // Nor without any price at all
+      await expectTX(await startConstructionWith([])).to.be.rejected;
+    });
+
+    it(`Reject a feed read as the wrong kind`, async () => {
+      await expectTX(
+        await setTestPriceFeed(mint, {
+          feed: pyth2Usd.pubkey,
+          kind: PriceFeedKind.SWITCHBOARD,
+        })
+      ).to.be.fulfilled;
+      await expectTX(await startConstruction()).to.be.rejected;
+    });
+
+    it(`Reject prices older than the feed's max staleness`, async () => {
+      // The fixture was published at timestamp 0, long before now
+      await expectTX(
+        await setTestPriceFeed(mint, {
+          feed: pyth2Usd.pubkey,
+          kind: PriceFeedKind.PYTH,
+          maxStaleness: 60,
+        })
+      ).to.be.fulfilled;
+      await expectTX(await startConstruction()).to.be.rejected;
+    });
+
+    it(`Reject prices less certain than the feed's max confidence`, async () => {
+      // The fixture is 1.00 give or take 0.10, 10% of the price
+      const uncertain = {
+        feed: pyth1UsdUncertain.pubkey,
+        kind: PriceFeedKind.PYTH,
+      };
+
+      await expectTX(
+        await setTestPriceFeed(mint, {
+          ...uncertain,
+          maxConfidenceBps: 999,
+        })
+      ).to.be.fulfilled;
+      await expectTX(await startConstruction()).to.be.rejected;
+
+      await expectTX(
+        await setTestPriceFeed(mint, {
+          ...uncertain,
+          maxConfidenceBps: 1_000,
+        })
+      ).to.be.fulfilled;
+      await expectTX(await startConstruction()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // 1 etf token at the initial NAV is worth 1.00, so 1 token at 1.00
+      expect(
+        new BN(prismEtf.transferredTokensData?.amounts[0])
+      ).to.be.bignumber.equal(new BN(10 ** decimals));
+    });
+
+    it(`Price with a Switchboard feed once repointed to one`, async () => {
+      // Finish the order left open by the last test
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      await expectTX(
+        await setTestPriceFeed(mint, {
+          feed: switchboard05Usd.pubkey,
+          kind: PriceFeedKind.SWITCHBOARD,
+        })
+      ).to.be.fulfilled;
+      await expectTX(await startConstruction()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      // With a single asset the NAV moves with its price, so a construction
+      // takes the backing per etf token whatever the price, less rounding
+      const weightedTokensData =
+        await coherenceHelper.sdk.loader.fetchWeightedTokensData(
+          prismEtf.prismEtfData?.weightedTokens ?? PublicKey.default
+        );
+      const supply = (
+        await getMintInfo(coherenceHelper.provider, prismEtf.prismEtfMint)
+      ).supply;
+      const backingShare = new BN(weightedTokensData?.backing[0])
+        .mul(AMOUNT_TO_CONSTRUCT)
+        .div(new BN(supply.toString()));
+      const amount = new BN(prismEtf.transferredTokensData?.amounts[0]);
+      expect(amount).to.be.bignumber.lte(backingShare);
+      expect(amount).to.be.bignumber.gte(backingShare.subn(2));
+    });
+  });
+}
@@ -0,0 +1,149 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import type { PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  BPS_DENOMINATOR,
+  getWeightedAmount,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  PriceFeedKind,
+  stringToEnumLike,
+  WeightMode,
+} from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import pyth2Usd from "../fixtures/pyth_2_usd.json";
+import { initTestPriceFeed } from "../priceFeedHelper";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function quoteOrder() {
+  describe("Quote Order", () => {
+    const decimalsA = 6;
+    const decimalsB = 9;
+    const weightA = new BN(2 * 10 ** 9);
+    const weightB = new BN(5 * 10 ** 8);
+    const AMOUNT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let mintA: PublicKey;
+    let mintB: PublicKey;
+
+    const weightedAmounts = () => [
+      getWeightedAmount(AMOUNT, weightA, PRISM_ETF_DECIMALS, decimalsA),
+      getWeightedAmount(AMOUNT, weightB, PRISM_ETF_DECIMALS, decimalsB),
+    ];
+
+    // Compare BN arrays by value
+    const asStrings = (amounts: BN[]) =>
+      amounts.map((amount) => amount.toString());
+
+    before(async () => {
+      mintA = await createTestMint(decimalsA);
+      mintB = await createTestMint(decimalsB);
+      await mintTestTokens(mintA, new BN(1_000 * 10 ** decimalsA));
+    });
+
+    it(`Quote QUANTITY orders from the weights, without a NAV`, async () => {
+      const prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([
+          { mint: mintA, weight: weightA },
+          { mint: mintB, weight: weightB },
+        ])
+      );
+
+      const constructionQuote = await prismEtf.quoteOrder({
+        type: OrderType.CONSTRUCTION,
+        amount: AMOUNT,
+      });
+      expect(constructionQuote.nav).to.be.null;
+      // Cohere takes one more than the weighted amount of each asset
+      expect(asStrings(constructionQuote.amounts)).to.deep.equal(
+        asStrings(weightedAmounts().map((amount) => amount.addn(1)))
+      );
+      const { orderer, owner, manager } =
+        prismEtf.getConstructionFeeSplit(AMOUNT);
+      expect(constructionQuote.ordererAmount).to.be.bignumber.equal(orderer);
+      expect(constructionQuote.ownerFee).to.be.bignumber.equal(owner);
+      expect(constructionQuote.managerFee).to.be.bignumber.equal(manager);
+
+      const deconstructionQuote = await prismEtf.quoteOrder({
+        type: OrderType.DECONSTRUCTION,
+        amount: AMOUNT,
+      });
+      expect(asStrings(deconstructionQuote.amounts)).to.deep.equal(
+        asStrings(weightedAmounts())
+      );
+      expect(deconstructionQuote.ordererAmount).to.be.bignumber.equal(AMOUNT);
+      expect(deconstructionQuote.ownerFee).to.be.bignumber.equal(new BN(0));
+      expect(deconstructionQuote.managerFee).to.be.bignumber.equal(new BN(0));
+    });
+
+    it(`Quote PERCENTAGE constructions at what start_order then asks for`, async () => {
+      // Priced at 2.00
+      await expectTX(
+        await initTestPriceFeed(mintA, {
+          feed: pyth2Usd.pubkey,
+          kind: PriceFeedKind.PYTH,
+        })
+      ).to.be.fulfilled;
+
+      const testPrismEtf = await initTestPrismEtf([
+        { mint: mintA, weight: new BN(BPS_DENOMINATOR) },
+      ]);
+      await expectTX(
+        coherenceHelper.sdk.loader.makeProviderEnvelope([
+          coherenceHelper.sdk.loader.program.instruction.setWeightMode(
+            stringToEnumLike(WeightMode.PERCENTAGE),
+            new BN(10 ** 9),
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+      let prismEtf: PrismEtf = await finalizeTestPrismEtf(testPrismEtf);
+
+      const quote = await prismEtf.quoteOrder({
+        type: OrderType.CONSTRUCTION,
+        amount: AMOUNT,
+      });
+      // Nothing has been constructed yet, so the NAV is the initial one
+      expect(quote.nav).to.be.bignumber.equal(new BN(10 ** 9));
+      // 10 etf tokens worth 10.00 take 5 of A at 2.00, plus the unit cohere
+      // rounds up by
+      expect(quote.amounts[0]).to.be.bignumber.equal(
+        new BN(5 * 10 ** decimalsA + 1)
+      );
+
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT,
+        })
+      ).to.be.fulfilled;
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+      expect(
+        new BN(prismEtf.transferredTokensData?.amounts[0]).addn(1)
+      ).to.be.bignumber.equal(quote.amounts[0]);
+    });
+  });
+}
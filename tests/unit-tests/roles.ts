@@ -0,0 +1,144 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TOKEN_PROGRAM_ID } from "@saberhq/token-utils";
+import type { Keypair } from "@solana/web3.js";
+import { PublicKey } from "@solana/web3.js";
+import chai, { assert, expect } from "chai";
+
+import type { CoherenceSDK } from "../../src";
+import { Role, stringToEnumLike } from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import { createFundedSdk } from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function roles() {
+  describe("Roles", () => {
+    let pauserSdk: CoherenceSDK;
+    let pauserKP: Keypair;
+
+    const fetchBeamsplitterData = async () => {
+      const beamsplitterData =
+        await coherenceHelper.sdk.beamsplitter.fetchBeamsplitterData(
+          coherenceHelper.beamsplitter
+        );
+      if (!beamsplitterData) {
+        assert.fail("Beamsplitter was not initialized");
+      }
+      return beamsplitterData;
+    };
+
+    const grantRole = (sdk: CoherenceSDK, role: Role, grantee: PublicKey) =>
+      sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.grantRole(stringToEnumLike(role), {
+          accounts: {
+            grantee,
+            owner: sdk.loader.getUserPublicKey(),
+            beamsplitter: coherenceHelper.beamsplitter,
+          },
+        }),
+      ]);
+
+    const revokeRole = (sdk: CoherenceSDK, role: Role) =>
+      sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.revokeRole(stringToEnumLike(role), {
+          accounts: {
+            owner: sdk.loader.getUserPublicKey(),
+            beamsplitter: coherenceHelper.beamsplitter,
+          },
+        }),
+      ]);
+
+    const setPaused = (sdk: CoherenceSDK, paused: boolean) =>
+      sdk.loader.makeProviderEnvelope([
+        sdk.loader.program.instruction.setPaused(paused, {
+          accounts: {
+            authority: sdk.loader.getUserPublicKey(),
+            beamsplitter: coherenceHelper.beamsplitter,
+          },
+        }),
+      ]);
+
+    before(async () => {
+      [pauserSdk, pauserKP] = await createFundedSdk();
+    });
+
+    it(`Reject granting a role without being the owner`, async () => {
+      await expectTX(grantRole(pauserSdk, Role.PAUSER, pauserKP.publicKey)).to
+        .be.rejected;
+
+      const { pauser } = await fetchBeamsplitterData();
+      assert(!pauser.equals(pauserKP.publicKey));
+    });
+
+    it(`Let only the holder of a granted role use it`, async () => {
+      // Without the role, the key can't pause new orders
+      await expectTX(setPaused(pauserSdk, true)).to.be.rejected;
+
+      await expectTX(
+        grantRole(coherenceHelper.sdk, Role.PAUSER, pauserKP.publicKey)
+      ).to.be.fulfilled;
+
+      let beamsplitterData = await fetchBeamsplitterData();
+      assert(beamsplitterData.pauser.equals(pauserKP.publicKey));
+
+      await expectTX(setPaused(pauserSdk, true)).to.be.fulfilled;
+      beamsplitterData = await fetchBeamsplitterData();
+      expect(beamsplitterData.paused).to.be.true;
+
+      await expectTX(setPaused(pauserSdk, false)).to.be.fulfilled;
+      beamsplitterData = await fetchBeamsplitterData();
+      expect(beamsplitterData.paused).to.be.false;
+
+      // A role only covers its own instructions
+      await expectTX(
+        pauserSdk.loader.makeProviderEnvelope([
+          pauserSdk.loader.program.instruction.setSwapProgram({
+            accounts: {
+              swapProgram: TOKEN_PROGRAM_ID,
+              authority: pauserKP.publicKey,
+              beamsplitter: coherenceHelper.beamsplitter,
+            },
+          }),
+        ])
+      ).to.be.rejected;
+    });
+
+    it(`Take a revoked role away from its holder but not the owner`, async () => {
+      await expectTX(revokeRole(pauserSdk, Role.PAUSER)).to.be.rejected;
+
+      await expectTX(revokeRole(coherenceHelper.sdk, Role.PAUSER)).to.be
+        .fulfilled;
+
+      const { pauser } = await fetchBeamsplitterData();
+      assert(!pauser.equals(pauserKP.publicKey));
+
+      await expectTX(setPaused(pauserSdk, true)).to.be.rejected;
+
+      // The owner holds every role
+      await expectTX(setPaused(coherenceHelper.sdk, true)).to.be.fulfilled;
+      await expectTX(setPaused(coherenceHelper.sdk, false)).to.be.fulfilled;
+    });
+
+    it(`Grant and revoke the autorebalancer role`, async () => {
+      const [, autorebalancerKP] = await createFundedSdk();
+
+      await expectTX(
+        grantRole(
+          coherenceHelper.sdk,
+          Role.AUTOREBALANCER,
+          autorebalancerKP.publicKey
+        )
+      ).to.be.fulfilled;
+      let { autorebalancer } = await fetchBeamsplitterData();
+      expect(autorebalancer).to.eqAddress(autorebalancerKP.publicKey);
+
+      await expectTX(revokeRole(pauserSdk, Role.AUTOREBALANCER)).to.be
+        .rejected;
+      await expectTX(revokeRole(coherenceHelper.sdk, Role.AUTOREBALANCER)).to.be
+        .fulfilled;
+      ({ autorebalancer } = await fetchBeamsplitterData());
+      expect(autorebalancer).to.eqAddress(PublicKey.default);
+    });
+  });
+}
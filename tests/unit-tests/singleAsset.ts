@@ -0,0 +1,230 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import {
+  getATAAddress,
+  getMintInfo,
+  TOKEN_PROGRAM_ID,
+} from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { SystemProgram } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import { getWeightedAmount, PRISM_ETF_DECIMALS } from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+import {
+  finalizeTestSingleAssetOrder,
+  finalizeTestSingleAssetRedemption,
+  setTestSwapProgram,
+  startTestSingleAssetOrder,
+  startTestSingleAssetRedemption,
+  swapTestCohere,
+  swapTestDecohere,
+} from "../singleAssetHelper";
+
+chai.use(chaiSolana);
+
+export default function singleAsset() {
+  describe("Single Asset Orders", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+
+    let mint: PublicKey;
+    let ordererSwapAta: PublicKey;
+    let ordererEtfAta: PublicKey;
+    let prismEtf: PrismEtf;
+
+    const etfAmount = (wholeTokens: number) =>
+      new BN(wholeTokens).mul(new BN(10).pow(new BN(PRISM_ETF_DECIMALS)));
+
+    const assetAmount = (wholeTokens: number) =>
+      getWeightedAmount(
+        etfAmount(wholeTokens),
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      );
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const fetchSupply = async () =>
+      new BN(
+        (
+          await getMintInfo(coherenceHelper.provider, prismEtf.prismEtfMint)
+        ).supply.toString()
+      );
+
+    before(async () => {
+      mint = await createTestMint(decimals);
+      ordererSwapAta = await mintTestTokens(
+        mint,
+        new BN(1_000 * 10 ** decimals)
+      );
+
+      prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint, weight }])
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+      ordererEtfAta = await getATAAddress({
+        mint: prismEtf.prismEtfMint,
+        owner: coherenceHelper.authority,
+      });
+    });
+
+    after(async () => {
+      await setTestSwapProgram(SystemProgram.programId);
+    });
+
+    it(`Reject single asset orders until a swap program is allowed`, async () => {
+      await expectTX(
+        await startTestSingleAssetOrder(
+          prismEtf,
+          mint,
+          assetAmount(1),
+          new BN(0)
+        )
+      ).to.be.rejected;
+
+      // The ETF's own asset is transferred, not swapped
+      await setTestSwapProgram(TOKEN_PROGRAM_ID);
+    });
+
+    it(`Mint what the swaps back and refund the input left over`, async () => {
+      const inputBefore = await ataBalance(ordererSwapAta);
+      const supplyBefore = await fetchSupply();
+
+      await expectTX(
+        await startTestSingleAssetOrder(
+          prismEtf,
+          mint,
+          assetAmount(12),
+          etfAmount(10)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(
+        await swapTestCohere(prismEtf, mint, 0, assetAmount(10))
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      expect(prismEtf.orderStateData?.amount).to.be.bignumber.equal(
+        etfAmount(10)
+      );
+
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .fulfilled;
+      await refreshPrismEtf();
+
+      // Only what was swapped was spent
+      expect(inputBefore.sub(await ataBalance(ordererSwapAta))).to.be.bignumber
+        .equal(assetAmount(10));
+      expect((await fetchSupply()).sub(supplyBefore)).to.be.bignumber.equal(
+        etfAmount(10)
+      );
+      expect(await ataBalance(ordererEtfAta)).to.be.bignumber.equal(
+        prismEtf.getConstructionFeeSplit(etfAmount(10)).orderer
+      );
+    });
+
+    it(`Reject finalizing below the minimum out, but let the order be unwound`, async () => {
+      const inputBefore = await ataBalance(ordererSwapAta);
+      const supplyBefore = await fetchSupply();
+
+      await expectTX(
+        await startTestSingleAssetOrder(
+          prismEtf,
+          mint,
+          assetAmount(5),
+          etfAmount(6)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(
+        await swapTestCohere(prismEtf, mint, 0, assetAmount(5))
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .rejected;
+
+      // Decohering every swapped asset cancels the order instead
+      for (const decohereChunk of await prismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await finalizeTestSingleAssetOrder(prismEtf, mint)).to.be
+        .fulfilled;
+
+      expect(await ataBalance(ordererSwapAta)).to.be.bignumber.equal(
+        inputBefore
+      );
+      expect(await fetchSupply()).to.be.bignumber.equal(supplyBefore);
+    });
+
+    it(`Pay a redemption out in the swap mint`, async () => {
+      const outputBefore = await ataBalance(ordererSwapAta);
+      const supplyBefore = await fetchSupply();
+
+      await expectTX(
+        await startTestSingleAssetRedemption(
+          prismEtf,
+          mint,
+          etfAmount(3),
+          assetAmount(3)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(await swapTestDecohere(prismEtf, mint, 0)).to.be.fulfilled;
+      await expectTX(await finalizeTestSingleAssetRedemption(prismEtf, mint)).to
+        .be.fulfilled;
+
+      expect(
+        (await ataBalance(ordererSwapAta)).sub(outputBefore)
+      ).to.be.bignumber.equal(assetAmount(3));
+      expect(supplyBefore.sub(await fetchSupply())).to.be.bignumber.equal(
+        etfAmount(3)
+      );
+    });
+
+    it(`Reject swapping out less than the minimum, unless the asset is taken as is`, async () => {
+      const outputBefore = await ataBalance(ordererSwapAta);
+
+      await expectTX(
+        await startTestSingleAssetRedemption(
+          prismEtf,
+          mint,
+          etfAmount(3),
+          assetAmount(3).addn(1)
+        )
+      ).to.be.fulfilled;
+      await refreshPrismEtf();
+      await expectTX(await swapTestDecohere(prismEtf, mint, 0)).to.be.rejected;
+
+      // A plain decohere gives up the minimum
+      for (const decohereChunk of await prismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+      await refreshPrismEtf();
+      await expectTX(await finalizeTestSingleAssetRedemption(prismEtf, mint)).to
+        .be.fulfilled;
+
+      expect(
+        (await ataBalance(ordererSwapAta)).sub(outputBefore)
+      ).to.be.bignumber.equal(assetAmount(3));
+    });
+  });
+}
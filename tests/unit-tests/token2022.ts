@@ -0,0 +1,378 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import type { PublicKey } from "@solana/web3.js";
+import {
+  Keypair,
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  createToken2022AtaInstruction,
+  FeeMode,
+  getToken2022AtaAddress,
+  getWeightedAmount,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  stringToEnumLike,
+  TOKEN_2022_PROGRAM_ID,
+} from "../../src";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+// Token-2022 instructions, built by hand like the program does
+const INITIALIZE_MINT = 0;
+const MINT_TO = 7;
+const TRANSFER_FEE_EXTENSION = 26;
+const INITIALIZE_TRANSFER_FEE_CONFIG = 0;
+const INITIALIZE_NON_TRANSFERABLE_MINT = 32;
+
+// Mint lengths with a TransferFeeConfig (108 bytes) or NonTransferable (no
+// data) extension, after the account type byte and the extension's header
+const TRANSFER_FEE_MINT_LENGTH = 165 + 1 + 4 + 108;
+const NON_TRANSFERABLE_MINT_LENGTH = 165 + 1 + 4;
+
+const u64Bytes = (amount: BN) => amount.toArrayLike(Buffer, "le", 8);
+
+// Amount of a Token-2022 account, read directly as the RPC's token balance
+// methods only know the original token program
+const token2022Balance = async (account: PublicKey): Promise<BN> => {
+  const accountInfo =
+    await coherenceHelper.provider.connection.getAccountInfo(account);
+  if (accountInfo === null) {
+    assert.fail("The token account does not exist");
+  }
+  return new BN(accountInfo.data.subarray(64, 72), "le");
+};
+
+// Creates a Token-2022 mint of the test signer, with the extension
+// initialized by extensionIx
+const createToken2022Mint = async (
+  decimals: number,
+  length: number,
+  extensionIx: (mint: PublicKey) => TransactionInstruction
+): Promise<PublicKey> => {
+  const mintKP = Keypair.generate();
+  const mint = mintKP.publicKey;
+  const lamports =
+    await coherenceHelper.provider.connection.getMinimumBalanceForRentExemption(
+      length
+    );
+  await expectTX(
+    new TransactionEnvelope(
+      coherenceHelper.provider,
+      [
+        SystemProgram.createAccount({
+          fromPubkey: coherenceHelper.authority,
+          newAccountPubkey: mint,
+          lamports,
+          space: length,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        extensionIx(mint),
+        new TransactionInstruction({
+          programId: TOKEN_2022_PROGRAM_ID,
+          keys: [
+            { pubkey: mint, isSigner: false, isWritable: true },
+            { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+          ],
+          data: Buffer.concat([
+            Buffer.from([INITIALIZE_MINT, decimals]),
+            coherenceHelper.authority.toBuffer(),
+            // No freeze authority
+            Buffer.from([0]),
+          ]),
+        }),
+      ],
+      [mintKP]
+    )
+  ).to.be.fulfilled;
+  return mint;
+};
+
+// Creates owner's Token-2022 account of mint, minting amount to it
+const mintToken2022 = async (
+  mint: PublicKey,
+  owner: PublicKey,
+  amount: BN
+): Promise<PublicKey> => {
+  const ata = await getToken2022AtaAddress(mint, owner);
+  await expectTX(
+    new TransactionEnvelope(coherenceHelper.provider, [
+      createToken2022AtaInstruction({
+        payer: coherenceHelper.authority,
+        ata,
+        owner,
+        mint,
+      }),
+      new TransactionInstruction({
+        programId: TOKEN_2022_PROGRAM_ID,
+        keys: [
+          { pubkey: mint, isSigner: false, isWritable: true },
+          { pubkey: ata, isSigner: false, isWritable: true },
+          {
+            pubkey: coherenceHelper.authority,
+            isSigner: true,
+            isWritable: false,
+          },
+        ],
+        data: Buffer.concat([Buffer.from([MINT_TO]), u64Bytes(amount)]),
+      }),
+    ])
+  ).to.be.fulfilled;
+  return ata;
+};
+
+export default function token2022() {
+  describe("Token-2022", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+    const TRANSFER_FEE_BPS = 100;
+    const MAXIMUM_FEE = new BN(1_000_000);
+
+    let mint: PublicKey;
+    let prismEtf: PrismEtf;
+    let ordererAta: PublicKey;
+    let etfAta: PublicKey;
+
+    // Mirrors Token-2022's fee, rounded up and capped at the maximum fee
+    const transferFee = (amount: BN) =>
+      BN.min(
+        amount
+          .muln(TRANSFER_FEE_BPS)
+          .addn(10_000 - 1)
+          .divn(10_000),
+        MAXIMUM_FEE
+      );
+
+    const refreshPrismEtf = async () => {
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+    };
+
+    const initTransferFeeConfig = (feeMint: PublicKey) =>
+      new TransactionInstruction({
+        programId: TOKEN_2022_PROGRAM_ID,
+        keys: [{ pubkey: feeMint, isSigner: false, isWritable: true }],
+        data: Buffer.concat([
+          Buffer.from([TRANSFER_FEE_EXTENSION, INITIALIZE_TRANSFER_FEE_CONFIG]),
+          // Neither a config nor a withdraw withheld authority
+          Buffer.from([0, 0]),
+          new BN(TRANSFER_FEE_BPS).toArrayLike(Buffer, "le", 2),
+          u64Bytes(MAXIMUM_FEE),
+        ]),
+      });
+
+    // The accounts cohere_token_2022 and decohere_token_2022 share
+    const token2022Accounts = (etf: PrismEtf) => {
+      if (
+        etf.prismEtfData === null ||
+        etf.orderStatePda === null ||
+        etf.transferredTokensAcct === undefined
+      ) {
+        assert.fail("The order state was not initialized");
+      }
+
+      return {
+        orderer: coherenceHelper.authority,
+        prismEtfMint: etf.prismEtfMint,
+        transferMint: mint,
+        ordererTransferAta: ordererAta,
+        beamsplitterTransferAta: etfAta,
+        beamsplitter: coherenceHelper.beamsplitter,
+        prismEtf: etf.prismEtfPda,
+        orderState: etf.orderStatePda,
+        weightedTokens: etf.prismEtfData.weightedTokens,
+        transferredTokens: etf.transferredTokensAcct,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      };
+    };
+
+    const cohereToken2022 = (etf: PrismEtf = prismEtf) =>
+      etf.makeProviderEnvelope([
+        etf.getProgramInstructions().cohereToken2022(0, {
+          accounts: { ...token2022Accounts(etf), clock: SYSVAR_CLOCK_PUBKEY },
+        }),
+      ]);
+
+    const decohereToken2022 = () =>
+      prismEtf.makeProviderEnvelope([
+        prismEtf.getProgramInstructions().decohereToken2022(0, {
+          accounts: token2022Accounts(prismEtf),
+        }),
+      ]);
+
+    const startOrder = async (type: OrderType, amount: BN) => {
+      await expectTX(await prismEtf.startOrder({ type, amount })).to.be
+        .fulfilled;
+      await refreshPrismEtf();
+    };
+
+    before(async () => {
+      mint = await createToken2022Mint(
+        decimals,
+        TRANSFER_FEE_MINT_LENGTH,
+        initTransferFeeConfig
+      );
+      ordererAta = await mintToken2022(
+        mint,
+        coherenceHelper.authority,
+        new BN(1_000 * 10 ** decimals)
+      );
+
+      // pushTokens creates the ETF's Token-2022 account
+      prismEtf = await finalizeTestPrismEtf(
+        await initTestPrismEtf([{ mint, weight }])
+      );
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      await refreshPrismEtf();
+
+      etfAta = await getToken2022AtaAddress(mint, prismEtf.prismEtfPda);
+    });
+
+    it(`Reject non-transferable mints`, async () => {
+      const nonTransferableMint = await createToken2022Mint(
+        decimals,
+        NON_TRANSFERABLE_MINT_LENGTH,
+        (extensionMint) =>
+          new TransactionInstruction({
+            programId: TOKEN_2022_PROGRAM_ID,
+            keys: [
+              { pubkey: extensionMint, isSigner: false, isWritable: true },
+            ],
+            data: Buffer.from([INITIALIZE_NON_TRANSFERABLE_MINT]),
+          })
+      );
+
+      const [initPrismEtfTx, prismEtfMint, prismEtfPda, weightedTokensAcct] =
+        await coherenceHelper.sdk.beamsplitter.initPrismEtf({});
+      await expectTX(initPrismEtfTx).to.be.fulfilled;
+      const pushTokensEnvelopes =
+        await coherenceHelper.sdk.beamsplitter.pushTokens({
+          prismEtfMint,
+          prismEtfPda,
+          weightedTokens: [{ mint: nonTransferableMint, weight }],
+          weightedTokensAcct,
+        });
+      for (const pushTokensEnvelope of pushTokensEnvelopes) {
+        await expectTX(pushTokensEnvelope).to.be.rejected;
+      }
+    });
+
+    it(`Credit the required amount net of the transfer fee`, async () => {
+      const ordererBefore = await token2022Balance(ordererAta);
+      const etfBefore = await token2022Balance(etfAta);
+      // Rounded up by one like cohere
+      const required = getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      ).addn(1);
+
+      await startOrder(OrderType.CONSTRUCTION, AMOUNT_TO_CONSTRUCT);
+      await expectTX(await cohereToken2022()).to.be.fulfilled;
+      // Cohering twice is a no-op
+      await expectTX(await cohereToken2022()).to.be.fulfilled;
+
+      const credited = (await token2022Balance(etfAta)).sub(etfBefore);
+      const sent = ordererBefore.sub(await token2022Balance(ordererAta));
+      expect(credited).to.be.bignumber.gte(required);
+      // The fee was withheld from what the orderer sent, not the credit
+      assert(transferFee(sent).gtn(0), "The mint must charge a fee");
+      expect(sent.sub(transferFee(sent))).to.be.bignumber.equal(credited);
+
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+    });
+
+    it(`Decohere to the orderer, who pays the transfer fee`, async () => {
+      const amount = AMOUNT_TO_CONSTRUCT.divn(2);
+      const ordererBefore = await token2022Balance(ordererAta);
+      const etfBefore = await token2022Balance(etfAta);
+      const required = getWeightedAmount(
+        amount,
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      );
+
+      await startOrder(OrderType.DECONSTRUCTION, amount);
+      await expectTX(await decohereToken2022()).to.be.fulfilled;
+
+      expect(
+        etfBefore.sub(await token2022Balance(etfAta))
+      ).to.be.bignumber.equal(required);
+      expect(
+        (await token2022Balance(ordererAta)).sub(ordererBefore)
+      ).to.be.bignumber.equal(required.sub(transferFee(required)));
+
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+    });
+
+    it(`Reject cohering Token-2022 assets in UNDERLYING fee mode`, async () => {
+      const testPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+      await expectTX(
+        new TransactionEnvelope(coherenceHelper.provider, [
+          coherenceHelper.sdk.loader.program.instruction.setFeeMode(
+            stringToEnumLike(FeeMode.UNDERLYING),
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+      let underlyingPrismEtf = await finalizeTestPrismEtf(testPrismEtf);
+      await expectTX(await underlyingPrismEtf.initOrderState()).to.be
+        .fulfilled;
+      underlyingPrismEtf = await loadTestPrismEtf(
+        underlyingPrismEtf.prismEtfMint
+      );
+      await expectTX(
+        await underlyingPrismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+      underlyingPrismEtf = await loadTestPrismEtf(
+        underlyingPrismEtf.prismEtfMint
+      );
+
+      const underlyingEtfAta = await getToken2022AtaAddress(
+        mint,
+        underlyingPrismEtf.prismEtfPda
+      );
+      const accounts = {
+        ...token2022Accounts(underlyingPrismEtf),
+        beamsplitterTransferAta: underlyingEtfAta,
+        clock: SYSVAR_CLOCK_PUBKEY,
+      };
+      await expectTX(
+        underlyingPrismEtf.makeProviderEnvelope([
+          underlyingPrismEtf
+            .getProgramInstructions()
+            .cohereToken2022(0, { accounts }),
+        ])
+      ).to.be.rejected;
+    });
+  });
+}
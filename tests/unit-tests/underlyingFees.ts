@@ -0,0 +1,197 @@
+/* eslint-disable @typescript-eslint/no-unsafe-member-access */
+import "chai-bn";
+
+import { chaiSolana, expectTX } from "@saberhq/chai-solana";
+import { TransactionEnvelope } from "@saberhq/solana-contrib";
+import { getATAAddress } from "@saberhq/token-utils";
+import type { PublicKey } from "@solana/web3.js";
+import { BN } from "bn.js";
+import chai, { assert, expect } from "chai";
+
+import type { PrismEtf } from "../../src";
+import {
+  BPS_DENOMINATOR,
+  FeeMode,
+  getWeightedAmount,
+  OrderType,
+  PRISM_ETF_DECIMALS,
+  stringToEnumLike,
+} from "../../src";
+import { ataBalance } from "../balanceHelper";
+import { coherenceHelper } from "../coherenceBeamsplitterTest";
+import {
+  createTestMint,
+  finalizeTestPrismEtf,
+  initTestPrismEtf,
+  loadTestPrismEtf,
+  mintTestTokens,
+} from "../prismEtfHelper";
+
+chai.use(chaiSolana);
+
+export default function underlyingFees() {
+  describe("Underlying Fees", () => {
+    const decimals = 6;
+    // One whole asset token per whole etf token
+    const weight = new BN(10 ** PRISM_ETF_DECIMALS);
+    const AMOUNT_TO_CONSTRUCT = new BN(10 * 10 ** PRISM_ETF_DECIMALS);
+
+    let mint: PublicKey;
+    let ordererAta: PublicKey;
+    let feeVaultAta: PublicKey;
+    let prismEtf: PrismEtf;
+
+    const fetchFeeVault = async () =>
+      await coherenceHelper.sdk.loader.program.account.feeVault.fetch(
+        prismEtf.feeVaultPda
+      );
+
+    const requiredAmount = () =>
+      getWeightedAmount(
+        AMOUNT_TO_CONSTRUCT,
+        weight,
+        PRISM_ETF_DECIMALS,
+        decimals
+      );
+
+    before(async () => {
+      mint = await createTestMint(decimals);
+      ordererAta = await mintTestTokens(mint, new BN(1_000 * 10 ** decimals));
+
+      const testPrismEtf = await initTestPrismEtf([{ mint, weight }]);
+
+      await expectTX(
+        new TransactionEnvelope(coherenceHelper.provider, [
+          coherenceHelper.sdk.loader.program.instruction.setFeeMode(
+            stringToEnumLike(FeeMode.UNDERLYING),
+            {
+              accounts: {
+                prismEtfMint: testPrismEtf.prismEtfMint,
+                manager: coherenceHelper.authority,
+                prismEtf: testPrismEtf.prismEtfPda,
+                beamsplitter: coherenceHelper.beamsplitter,
+              },
+            }
+          ),
+        ])
+      ).to.be.fulfilled;
+
+      prismEtf = await finalizeTestPrismEtf(testPrismEtf);
+
+      await expectTX(await prismEtf.initOrderState()).to.be.fulfilled;
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+
+      feeVaultAta = await getATAAddress({ mint, owner: prismEtf.feeVaultPda });
+    });
+
+    it(`Reject pushing a mint the ETF already weights`, async () => {
+      const otherMint = await createTestMint(decimals);
+      const { prismEtfMint, prismEtfPda, weightedTokensAcct } =
+        await initTestPrismEtf([{ mint: otherMint, weight }]);
+
+      const pushTokensEnvelopes =
+        await coherenceHelper.sdk.beamsplitter.pushTokens({
+          prismEtfMint,
+          prismEtfPda,
+          weightedTokens: [{ mint: otherMint, weight: weight.muln(2) }],
+          weightedTokensAcct,
+          // The ETF's ATA for the mint already exists
+          shouldCreateAtas: false,
+        });
+
+      for (const pushTokensEnvelope of pushTokensEnvelopes) {
+        await expectTX(pushTokensEnvelope).to.be.rejected;
+      }
+
+      const weightedTokensData =
+        await coherenceHelper.sdk.loader.fetchWeightedTokensData(
+          weightedTokensAcct
+        );
+      expect(weightedTokensData?.length).to.be.equal(1);
+    });
+
+    it(`Refund the asset fee when a CONSTRUCT order is unwound`, async () => {
+      const fee = prismEtf.getAssetFee(requiredAmount(), AMOUNT_TO_CONSTRUCT);
+      assert(fee.gtn(0), "Asset fee must be charged in UNDERLYING fee mode");
+
+      const ordererBalBefore = await ataBalance(ordererAta);
+
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+
+      // The vault holds the fee, but it is owed to no one until finalize
+      expect(await ataBalance(feeVaultAta)).to.be.bignumber.equal(fee);
+      let feeVaultData = await fetchFeeVault();
+      expect(feeVaultData.protocolAssetFees[0]).to.be.bignumber.equal(
+        new BN(0)
+      );
+      expect(feeVaultData.managerAssetFees[0]).to.be.bignumber.equal(new BN(0));
+
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+      for (const decohereChunk of await prismEtf.decohere({})) {
+        await expectTX(decohereChunk).to.be.fulfilled;
+      }
+
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+
+      // Only the extra unit cohere rounds up by stays in the ETF
+      expect(await ataBalance(ordererAta)).to.be.bignumber.equal(
+        ordererBalBefore.subn(1)
+      );
+      expect(await ataBalance(feeVaultAta)).to.be.bignumber.equal(new BN(0));
+
+      feeVaultData = await fetchFeeVault();
+      expect(feeVaultData.protocolAssetFees[0]).to.be.bignumber.equal(
+        new BN(0)
+      );
+      expect(feeVaultData.managerAssetFees[0]).to.be.bignumber.equal(new BN(0));
+    });
+
+    it(`Accrue the asset fee when a CONSTRUCT order is finalized`, async () => {
+      const fee = prismEtf.getAssetFee(requiredAmount(), AMOUNT_TO_CONSTRUCT);
+
+      prismEtf = await loadTestPrismEtf(prismEtf.prismEtfMint);
+      await expectTX(
+        await prismEtf.startOrder({
+          type: OrderType.CONSTRUCTION,
+          amount: AMOUNT_TO_CONSTRUCT,
+        })
+      ).to.be.fulfilled;
+
+      for (const cohereChunk of await prismEtf.cohere({
+        orderStateAmount: AMOUNT_TO_CONSTRUCT,
+      })) {
+        await expectTX(cohereChunk).to.be.fulfilled;
+      }
+
+      await expectTX(await prismEtf.finalizeOrder({})).to.be.fulfilled;
+
+      if (!prismEtf.prismEtfData) {
+        assert.fail("Prism Etf was not successfully created");
+      }
+
+      const managerFee = fee
+        .muln(prismEtf.prismEtfData.managerCut)
+        .divn(BPS_DENOMINATOR);
+
+      const feeVaultData = await fetchFeeVault();
+      expect(feeVaultData.managerAssetFees[0]).to.be.bignumber.equal(
+        managerFee
+      );
+      expect(feeVaultData.protocolAssetFees[0]).to.be.bignumber.equal(
+        fee.sub(managerFee)
+      );
+      expect(await ataBalance(feeVaultAta)).to.be.bignumber.equal(fee);
+    });
+  });
+}